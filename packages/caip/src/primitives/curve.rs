@@ -12,12 +12,14 @@ pub enum Curve {
     Ed25519,
 }
 impl Storable for Curve {
-    fn to_bytes(&self) -> Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
+    // Candid always encodes a fieldless variant, the empty fallback is never written
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Owned(Encode!(self).unwrap_or_default())
     }
 
+    // Corrupt bytes decode as the default curve instead of trapping the canister
     fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), Curve).unwrap()
+        Decode!(bytes.as_ref(), Curve).unwrap_or(Curve::Secp256k1)
     }
 
     const BOUND: Bound = Bound::Unbounded;
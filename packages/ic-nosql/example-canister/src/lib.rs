@@ -3,12 +3,14 @@
 //! This canister shows how to use ic-nosql to manage different types of data
 //! in a single canister with proper memory management.
 
-use candid::{Decode, Encode};
 use ic_cdk::api::management_canister::main::raw_rand;
 use ic_cdk::{init, post_upgrade, pre_upgrade, query, update};
-use ic_nosql::{define_model, CandidType, DatabaseManager};
+use ic_nosql::{
+    define_model, schedule_migration, CandidType, Database, DatabaseManager, Model, SchemaMetadata,
+};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::time::Duration;
 
 // Example models using the define_model! macro
 define_model! {
@@ -22,6 +24,9 @@ define_model! {
 
     primary_key: id -> String,
     secondary_key: username -> String,
+    unique_indexes: {
+        username: |user: &User| vec![user.username.clone()],
+    },
 }
 
 define_model! {
@@ -31,6 +36,9 @@ define_model! {
         pub user_id: String,
         pub title: String,
         pub content: String,
+        // Added in schema version 1, older posts get theirs from their migration
+        #[cfg(not(feature = "legacy-schema"))]
+        pub summary: String,
         pub created_at: u64,
     }
 
@@ -38,6 +46,52 @@ define_model! {
     secondary_key: user_id -> String,
 }
 
+// Migration of posts written before summaries existed, left out of legacy schema builds
+#[cfg(not(feature = "legacy-schema"))]
+mod post_migrations {
+    use super::Post;
+    use ic_nosql::database::Document;
+    use ic_nosql::{CandidType, Deserialize, Migration};
+
+    /// Schema version posts are written with
+    pub const POST_SCHEMA_VERSION: u32 = 1;
+
+    // Posts as stored at schema version 0
+    #[derive(CandidType, Deserialize)]
+    struct PostV0 {
+        id: String,
+        user_id: String,
+        title: String,
+        content: String,
+        created_at: u64,
+    }
+
+    pub struct AddPostSummaries;
+
+    impl Migration<Post> for AddPostSummaries {
+        fn from_version(&self) -> u32 {
+            0
+        }
+
+        fn migrate(&self, bytes: &[u8]) -> Result<Post, String> {
+            let post = Document::<PostV0>::from_bytes(bytes)?.data;
+            Ok(Post {
+                id: post.id,
+                user_id: post.user_id,
+                title: post.title,
+                summary: summarize(&post.content),
+                content: post.content,
+                created_at: post.created_at,
+            })
+        }
+    }
+
+    /// Summary of a post, the start of its content
+    pub fn summarize(content: &str) -> String {
+        content.chars().take(100).collect()
+    }
+}
+
 define_model! {
     #[derive(Debug, Clone, CandidType, Deserialize, Serialize)]
     pub struct Comment {
@@ -69,9 +123,40 @@ fn init() {
             .expect("Failed to register posts model");
         db.register_model("comments", Some(12), None)
             .expect("Failed to register comments model");
+        db.register_index("users", "username", Some(13))
+            .expect("Failed to register users username index");
+        // Post contents may exceed the size bound, so they are stored in chunks
+        db.register_chunks("posts", Some(14))
+            .expect("Failed to register posts chunks");
+        db.register_metadata(Some(15))
+            .expect("Failed to register metadata region");
+    });
+
+    // Rewrite posts of older schema versions in the background, resuming after upgrades
+    schedule_migration(Duration::from_secs(1), || {
+        DB_MANAGER.with(|db| {
+            let db = db.borrow();
+            db.migrate_batch("posts", &posts_db(&db)?, 10)
+        })
     });
 }
 
+// Posts database, reading posts of older schema versions through their migrations
+fn posts_db(db: &DatabaseManager) -> Result<Database<Post>, String> {
+    let posts = db.get_simple_database("posts")?;
+    #[cfg(not(feature = "legacy-schema"))]
+    let posts = posts.with_migrations(
+        post_migrations::POST_SCHEMA_VERSION,
+        vec![Box::new(post_migrations::AddPostSummaries)],
+    );
+    Ok(posts)
+}
+
+// Users database, keeping the unique username index up to date
+fn users_db(db: &DatabaseManager) -> Result<Database<User>, String> {
+    db.get_database_with_indexes("users", None, User::indexes())
+}
+
 #[pre_upgrade]
 fn pre_upgrade() {
     // Stable memory is automatically handled by ic-nosql
@@ -103,7 +188,7 @@ async fn create_user(username: String, email: String) -> Result<User, String> {
 
     DB_MANAGER.with(|db| {
         let db = db.borrow();
-        db.insert("users", &id, &user)?;
+        users_db(&db)?.insert("users".to_string(), Some(id), user.clone())?;
         Ok(user)
     })
 }
@@ -116,6 +201,15 @@ fn get_user(id: String) -> Result<User, String> {
     })
 }
 
+#[query]
+fn get_user_by_username(username: String) -> Result<User, String> {
+    DB_MANAGER.with(|db| {
+        let db = db.borrow();
+        let document = users_db(&db)?.get_by_unique("username", &username)?;
+        Ok(document.data)
+    })
+}
+
 #[query]
 fn list_users(page: usize, size: usize) -> Result<Vec<User>, String> {
     const MAX_PAGE_SIZE: usize = 1000;
@@ -127,6 +221,15 @@ fn list_users(page: usize, size: usize) -> Result<Vec<User>, String> {
     })
 }
 
+#[update]
+fn delete_user(id: String) -> Result<User, String> {
+    DB_MANAGER.with(|db| {
+        let db = db.borrow();
+        let document = users_db(&db)?.delete("users", Some(id))?;
+        Ok(document.data)
+    })
+}
+
 // Post management functions
 #[update]
 async fn create_post(user_id: String, title: String, content: String) -> Result<Post, String> {
@@ -143,13 +246,15 @@ async fn create_post(user_id: String, title: String, content: String) -> Result<
         id: id.clone(),
         user_id,
         title,
+        #[cfg(not(feature = "legacy-schema"))]
+        summary: post_migrations::summarize(&content),
         content,
         created_at: ic_cdk::api::time(),
     };
 
     DB_MANAGER.with(|db| {
         let db = db.borrow();
-        db.insert("posts", &id, &post)?;
+        posts_db(&db)?.insert("posts".to_string(), Some(id), post.clone())?;
         Ok(post)
     })
 }
@@ -158,7 +263,7 @@ async fn create_post(user_id: String, title: String, content: String) -> Result<
 fn get_post(id: String) -> Result<Post, String> {
     DB_MANAGER.with(|db| {
         let db = db.borrow();
-        db.get::<Post>("posts", &id)
+        Ok(posts_db(&db)?.get("posts", Some(id))?.data)
     })
 }
 
@@ -166,11 +271,19 @@ fn get_post(id: String) -> Result<Post, String> {
 fn list_posts(page: usize, size: usize) -> Result<Vec<Post>, String> {
     DB_MANAGER.with(|db| {
         let db = db.borrow();
-        let response = db.query::<Post>("posts", size, page)?;
+        let response = posts_db(&db)?.query(Some("posts"), None, size, page)?;
         Ok(response.results.into_iter().map(|doc| doc.data).collect())
     })
 }
 
+#[update]
+fn delete_post(id: String) -> Result<Post, String> {
+    DB_MANAGER.with(|db| {
+        let db = db.borrow();
+        Ok(posts_db(&db)?.delete("posts", Some(id))?.data)
+    })
+}
+
 // Comment management functions
 #[update]
 async fn create_comment(
@@ -229,6 +342,15 @@ fn get_database_stats() -> Vec<String> {
     })
 }
 
+// Schema version and migration progress of a model
+#[query]
+fn get_schema_metadata(model: String) -> Result<SchemaMetadata, String> {
+    DB_MANAGER.with(|db| {
+        let db = db.borrow();
+        db.schema_metadata(&model)
+    })
+}
+
 // Health check
 #[query]
 fn health_check() -> String {
@@ -3,10 +3,21 @@
 //! This module contains the main database implementation, database manager,
 //! and all related types for the NoSQL database system.
 
+pub use self::batch::WriteBatch;
 pub use self::manager::DatabaseManager;
+pub use self::migration::schedule_migration;
 pub use self::nosql_db::Database;
-pub use self::types::{CompositeKey, CompositeKeys, Document, QueryResponse};
+pub use self::query_builder::QueryBuilder;
+pub use self::types::{
+    ChunkKey, CompositeKey, CompositeKeys, CorruptEntry, CorruptIndexEntry, Cursor, CursorResponse,
+    DatabaseError, Document, IntegrityReport, MigrationProgress, Page, QueryResponse,
+    SchemaMetadata, SortKeyCondition, SortOrder, StoredValue, WriteCondition,
+    DEFAULT_MAX_VALUE_SIZE,
+};
 
+pub mod batch;
 pub mod manager;
+pub mod migration;
 pub mod nosql_db;
+pub mod query_builder;
 pub mod types;
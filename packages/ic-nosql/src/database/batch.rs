@@ -0,0 +1,422 @@
+use candid::CandidType;
+use ic_stable_structures::Storable;
+use serde::{Deserialize, Serialize};
+
+use super::nosql_db::Database;
+use super::types::{CompositeKey, DatabaseError, WriteCondition};
+
+/// Closure putting a key back the way it was before a write
+type Undo<'a> = Box<dyn FnOnce() + 'a>;
+
+/// A write staged in a batch, applied against its own database
+trait StagedWrite<'a> {
+    /// Apply the write, returning how to undo it
+    fn apply(&self) -> Result<Undo<'a>, DatabaseError>;
+}
+
+struct Put<'a, T, SecondaryKey>
+where
+    T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    SecondaryKey: Clone + Ord + Storable,
+{
+    database: &'a Database<T, SecondaryKey>,
+    key: CompositeKey,
+    data: T,
+    condition: Option<WriteCondition>,
+}
+
+impl<'a, T, SecondaryKey> StagedWrite<'a> for Put<'a, T, SecondaryKey>
+where
+    T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    SecondaryKey: Clone + Ord + Storable,
+{
+    fn apply(&self) -> Result<Undo<'a>, DatabaseError> {
+        let (_, previous) =
+            self.database
+                .put(self.key.clone(), self.data.clone(), self.condition.as_ref())?;
+        let (database, key) = (self.database, self.key.clone());
+        Ok(Box::new(move || database.restore(&key, previous)))
+    }
+}
+
+struct Delete<'a, T, SecondaryKey>
+where
+    T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    SecondaryKey: Clone + Ord + Storable,
+{
+    database: &'a Database<T, SecondaryKey>,
+    key: CompositeKey,
+    condition: Option<WriteCondition>,
+}
+
+impl<'a, T, SecondaryKey> StagedWrite<'a> for Delete<'a, T, SecondaryKey>
+where
+    T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    SecondaryKey: Clone + Ord + Storable,
+{
+    fn apply(&self) -> Result<Undo<'a>, DatabaseError> {
+        let previous = self.database.remove(&self.key, self.condition.as_ref())?;
+        let (database, key) = (self.database, self.key.clone());
+        Ok(Box::new(move || database.restore(&key, Some(previous))))
+    }
+}
+
+/// Puts and deletes across one or more databases, applied all together or not at all
+///
+/// Writes are applied in the order they were staged, so later writes see the effects of
+/// earlier ones. If any write fails its condition, a unique index or a lookup, the writes
+/// already applied are rolled back, indexes included, and the error is returned.
+#[derive(Default)]
+pub struct WriteBatch<'a> {
+    writes: Vec<Box<dyn StagedWrite<'a> + 'a>>,
+}
+
+impl<'a> WriteBatch<'a> {
+    /// Create an empty batch
+    pub fn new() -> Self {
+        WriteBatch { writes: Vec::new() }
+    }
+
+    /// Stage an insert or update of a document
+    pub fn put<T, SecondaryKey>(
+        &mut self,
+        database: &'a Database<T, SecondaryKey>,
+        partition_key: &str,
+        sort_key: Option<String>,
+        data: T,
+    ) -> &mut Self
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone + 'a,
+        SecondaryKey: Clone + Ord + Storable + 'a,
+    {
+        self.stage_put(database, partition_key, sort_key, data, None)
+    }
+
+    /// Stage an insert or update of a document, only applied if the condition holds
+    pub fn put_if<T, SecondaryKey>(
+        &mut self,
+        database: &'a Database<T, SecondaryKey>,
+        partition_key: &str,
+        sort_key: Option<String>,
+        data: T,
+        condition: WriteCondition,
+    ) -> &mut Self
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone + 'a,
+        SecondaryKey: Clone + Ord + Storable + 'a,
+    {
+        self.stage_put(database, partition_key, sort_key, data, Some(condition))
+    }
+
+    /// Stage the delete of a document, failing the batch if it does not exist
+    pub fn delete<T, SecondaryKey>(
+        &mut self,
+        database: &'a Database<T, SecondaryKey>,
+        partition_key: &str,
+        sort_key: Option<String>,
+    ) -> &mut Self
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone + 'a,
+        SecondaryKey: Clone + Ord + Storable + 'a,
+    {
+        self.stage_delete(database, partition_key, sort_key, None)
+    }
+
+    /// Stage the delete of a document, only applied if the condition holds
+    pub fn delete_if<T, SecondaryKey>(
+        &mut self,
+        database: &'a Database<T, SecondaryKey>,
+        partition_key: &str,
+        sort_key: Option<String>,
+        condition: WriteCondition,
+    ) -> &mut Self
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone + 'a,
+        SecondaryKey: Clone + Ord + Storable + 'a,
+    {
+        self.stage_delete(database, partition_key, sort_key, Some(condition))
+    }
+
+    /// Number of staged writes
+    pub fn len(&self) -> usize {
+        self.writes.len()
+    }
+
+    /// Whether no write is staged
+    pub fn is_empty(&self) -> bool {
+        self.writes.is_empty()
+    }
+
+    /// Apply every staged write, or none of them if one fails
+    pub fn commit(self) -> Result<(), DatabaseError> {
+        let mut undo_log = Vec::with_capacity(self.writes.len());
+        for write in &self.writes {
+            match write.apply() {
+                Ok(undo) => undo_log.push(undo),
+                Err(error) => {
+                    // Roll back in reverse order so each key ends up as it started
+                    for undo in undo_log.into_iter().rev() {
+                        undo();
+                    }
+                    return Err(error);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn stage_put<T, SecondaryKey>(
+        &mut self,
+        database: &'a Database<T, SecondaryKey>,
+        partition_key: &str,
+        sort_key: Option<String>,
+        data: T,
+        condition: Option<WriteCondition>,
+    ) -> &mut Self
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone + 'a,
+        SecondaryKey: Clone + Ord + Storable + 'a,
+    {
+        self.writes.push(Box::new(Put {
+            database,
+            key: CompositeKey {
+                partition_key: partition_key.to_string(),
+                sort_key,
+            },
+            data,
+            condition,
+        }));
+        self
+    }
+
+    fn stage_delete<T, SecondaryKey>(
+        &mut self,
+        database: &'a Database<T, SecondaryKey>,
+        partition_key: &str,
+        sort_key: Option<String>,
+        condition: Option<WriteCondition>,
+    ) -> &mut Self
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone + 'a,
+        SecondaryKey: Clone + Ord + Storable + 'a,
+    {
+        self.writes.push(Box::new(Delete {
+            database,
+            key: CompositeKey {
+                partition_key: partition_key.to_string(),
+                sort_key,
+            },
+            condition,
+        }));
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_stable_structures::memory_manager::{MemoryId, MemoryManager};
+    use ic_stable_structures::{DefaultMemoryImpl, StableBTreeMap};
+    use std::cell::RefCell;
+
+    #[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq)]
+    struct Balance {
+        owner: String,
+        amount: u64,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq)]
+    struct Transfer {
+        from: String,
+        to: String,
+        amount: u64,
+    }
+
+    thread_local! {
+        static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+            RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+    }
+
+    // Helper function creating a database in the memory, with a unique index on owners
+    fn create_balances_db(memory_id: u8, index_memory_id: u8) -> Database<Balance> {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(memory_id))),
+        ));
+        let owner_index = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(index_memory_id))),
+        ));
+        Database::new(map, None, None).with_unique_index(
+            "owner",
+            owner_index,
+            Box::new(|balance: &Balance| vec![balance.owner.clone()]),
+        )
+    }
+
+    fn create_transfers_db(memory_id: u8) -> Database<Transfer> {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(memory_id))),
+        ));
+        Database::new(map, None, None)
+    }
+
+    fn balance(owner: &str, amount: u64) -> Balance {
+        Balance {
+            owner: owner.to_string(),
+            amount,
+        }
+    }
+
+    #[test]
+    fn test_batch_applies_across_databases() {
+        let balances = create_balances_db(0, 1);
+        let transfers = create_transfers_db(2);
+        balances
+            .insert("a".to_string(), None, balance("alice", 100))
+            .unwrap();
+        balances
+            .insert("b".to_string(), None, balance("bob", 0))
+            .unwrap();
+
+        let mut batch = WriteBatch::new();
+        batch
+            .put_if(
+                &balances,
+                "a",
+                None,
+                balance("alice", 60),
+                WriteCondition::VersionEquals(1),
+            )
+            .put_if(
+                &balances,
+                "b",
+                None,
+                balance("bob", 40),
+                WriteCondition::VersionEquals(1),
+            )
+            .put_if(
+                &transfers,
+                "a",
+                Some("1".to_string()),
+                Transfer {
+                    from: "alice".to_string(),
+                    to: "bob".to_string(),
+                    amount: 40,
+                },
+                WriteCondition::NotExists,
+            );
+        assert_eq!(batch.len(), 3);
+        batch.commit().unwrap();
+
+        let alice = balances.get("a", None).unwrap();
+        assert_eq!((alice.data.amount, alice.version), (60, 2));
+        assert_eq!(balances.get("b", None).unwrap().data.amount, 40);
+        assert_eq!(
+            transfers.get("a", Some("1".to_string())).unwrap().version,
+            1
+        );
+
+        // Deletes are staged the same way
+        let mut batch = WriteBatch::new();
+        batch
+            .delete(&transfers, "a", Some("1".to_string()))
+            .delete_if(&balances, "b", None, WriteCondition::VersionEquals(2));
+        batch.commit().unwrap();
+        assert!(transfers.get("a", Some("1".to_string())).is_err());
+        assert!(balances.get_by_unique("owner", "bob").is_err());
+    }
+
+    #[test]
+    fn test_failed_batch_rolls_back_documents_and_indexes() {
+        let balances = create_balances_db(3, 4);
+        let transfers = create_transfers_db(5);
+        balances
+            .insert("a".to_string(), None, balance("alice", 100))
+            .unwrap();
+        balances
+            .insert("b".to_string(), None, balance("bob", 0))
+            .unwrap();
+
+        // The last write fails its condition after the others were applied
+        let mut batch = WriteBatch::new();
+        batch
+            .put(&balances, "a", None, balance("alicia", 60))
+            .delete(&balances, "b", None)
+            .put(&balances, "c", None, balance("carol", 5))
+            .put_if(
+                &transfers,
+                "a",
+                None,
+                Transfer {
+                    from: "alice".to_string(),
+                    to: "bob".to_string(),
+                    amount: 40,
+                },
+                WriteCondition::VersionEquals(1),
+            );
+        let result = batch.commit();
+        assert_eq!(
+            result,
+            Err(DatabaseError::ConditionFailed {
+                partition_key: "a".to_string(),
+                sort_key: None,
+            })
+        );
+
+        let alice = balances.get("a", None).unwrap();
+        assert_eq!(
+            (alice.data.clone(), alice.version),
+            (balance("alice", 100), 1)
+        );
+        assert_eq!(balances.get("b", None).unwrap().data, balance("bob", 0));
+        assert!(balances.get("c", None).is_err());
+        assert!(transfers.get("a", None).is_err());
+        assert_eq!(
+            balances
+                .get_by_unique("owner", "alice")
+                .unwrap()
+                .partition_key,
+            "a"
+        );
+        assert_eq!(
+            balances
+                .get_by_unique("owner", "bob")
+                .unwrap()
+                .partition_key,
+            "b"
+        );
+        assert!(balances.get_by_unique("owner", "alicia").is_err());
+        assert!(balances.get_by_unique("owner", "carol").is_err());
+
+        // Unique conflicts, including ones created within the batch, roll back too
+        let mut batch = WriteBatch::new();
+        batch.put(&balances, "c", None, balance("carol", 5)).put(
+            &balances,
+            "d",
+            None,
+            balance("carol", 5),
+        );
+        assert!(matches!(
+            batch.commit(),
+            Err(DatabaseError::UniqueConstraintViolation { .. })
+        ));
+        assert!(balances.get("c", None).is_err());
+
+        // So do conditions on keys that already exist and deletes of missing documents
+        let mut batch = WriteBatch::new();
+        batch.put_if(
+            &balances,
+            "a",
+            None,
+            balance("alice", 1),
+            WriteCondition::NotExists,
+        );
+        assert!(batch.commit().is_err());
+        let mut batch = WriteBatch::new();
+        batch.delete(&balances, "missing", None);
+        assert!(matches!(
+            batch.commit(),
+            Err(DatabaseError::DocumentNotFound { .. })
+        ));
+        assert_eq!(balances.get("a", None).unwrap().data.amount, 100);
+    }
+}
@@ -1,13 +1,21 @@
 use candid::CandidType;
 use ic_stable_structures::memory_manager::MemoryId;
-use ic_stable_structures::Storable;
+use ic_stable_structures::{StableBTreeMap, Storable};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
-use super::nosql_db::Database;
-use super::types::{CompositeKey, CompositeKeys, Document, QueryResponse};
-use crate::memory::stable_memory::create_stable_btree_map;
+use super::nosql_db::{corrupt_index_entries, Database};
+use super::types::{
+    ChunkKey, CompositeKey, Cursor, CursorResponse, Document, IntegrityReport, MigrationProgress,
+    QueryResponse, SchemaMetadata, SortKeyCondition, SortOrder, StoredValue,
+};
+use crate::memory::stable_memory::{create_stable_btree_map, Memory};
+use crate::traits::{Index, SecondaryKeyFn};
+use crate::utils::serialization::{deserialize_from_bytes, serialize_to_bytes};
+
+// Entry reserving the memory of the metadata region among the registered models
+const METADATA_MODEL: &str = "__metadata";
 
 /// DatabaseManager provides a centralized way to manage multiple models and their storage
 pub struct DatabaseManager {
@@ -21,6 +29,19 @@ struct ModelInfo {
     _model_name: String,
     primary_memory_id: MemoryId,
     secondary_memory_id: Option<MemoryId>,
+    index_memory_ids: BTreeMap<String, MemoryId>,
+    max_value_size: Option<u32>,
+    chunk_memory_id: Option<MemoryId>,
+}
+
+impl ModelInfo {
+    // Check whether the model stores anything in the memory
+    fn uses_memory(&self, memory_id: MemoryId) -> bool {
+        self.primary_memory_id == memory_id
+            || self.secondary_memory_id == Some(memory_id)
+            || self.index_memory_ids.values().any(|id| *id == memory_id)
+            || self.chunk_memory_id == Some(memory_id)
+    }
 }
 
 impl DatabaseManager {
@@ -57,15 +78,8 @@ impl DatabaseManager {
             Some(id) => {
                 // Check if this memory ID is already in use
                 let memory_id = MemoryId::new(id);
-                for (_, info) in models.iter() {
-                    if info.primary_memory_id == memory_id {
-                        return Err(format!("Memory ID {} is already in use", id));
-                    }
-                    if let Some(secondary_id) = info.secondary_memory_id {
-                        if secondary_id == memory_id {
-                            return Err(format!("Memory ID {} is already in use", id));
-                        }
-                    }
+                if models.values().any(|info| info.uses_memory(memory_id)) {
+                    return Err(format!("Memory ID {} is already in use", id));
                 }
                 MemoryId::new(id)
             }
@@ -82,15 +96,8 @@ impl DatabaseManager {
             .map(|id| {
                 // Check if this memory ID is already in use
                 let memory_id = MemoryId::new(id);
-                for (_, info) in models.iter() {
-                    if info.primary_memory_id == memory_id {
-                        return Err(format!("Memory ID {} is already in use", id));
-                    }
-                    if let Some(secondary_id) = info.secondary_memory_id {
-                        if secondary_id == memory_id {
-                            return Err(format!("Memory ID {} is already in use", id));
-                        }
-                    }
+                if models.values().any(|info| info.uses_memory(memory_id)) {
+                    return Err(format!("Memory ID {} is already in use", id));
                 }
                 Ok(memory_id)
             })
@@ -100,12 +107,235 @@ impl DatabaseManager {
             _model_name: model_name.to_string(),
             primary_memory_id: primary_id,
             secondary_memory_id: secondary_id,
+            index_memory_ids: BTreeMap::new(),
+            max_value_size: None,
+            chunk_memory_id: None,
         };
 
         models.insert(model_name.to_string(), model_info);
         Ok(())
     }
 
+    /// Register a named index of a registered model
+    ///
+    /// # Arguments
+    /// * `model_name` - Name of the registered model
+    /// * `index_name` - Name the index is queried by, unique within the model
+    /// * `memory_id` - Memory ID for the index (None for auto-allocation)
+    pub fn register_index(
+        &self,
+        model_name: &str,
+        index_name: &str,
+        memory_id: Option<u8>,
+    ) -> Result<(), String> {
+        let mut models = self.registered_models.borrow_mut();
+
+        if !models.contains_key(model_name) {
+            return Err(format!("Model '{}' is not registered", model_name));
+        }
+
+        let memory_id = self.allocate_memory_id(&models, memory_id)?;
+
+        let model_info = models
+            .get_mut(model_name)
+            .ok_or_else(|| format!("Model '{}' is not registered", model_name))?;
+        if model_info.index_memory_ids.contains_key(index_name) {
+            return Err(format!(
+                "Index '{}' of model '{}' is already registered",
+                index_name, model_name
+            ));
+        }
+        model_info
+            .index_memory_ids
+            .insert(index_name.to_string(), memory_id);
+        Ok(())
+    }
+
+    /// Bound the size of a single stored document of a registered model, 4096 bytes by default
+    pub fn set_max_value_size(&self, model_name: &str, max_value_size: u32) -> Result<(), String> {
+        if max_value_size == 0 {
+            return Err("Maximum value size must be greater than 0.".to_string());
+        }
+
+        let mut models = self.registered_models.borrow_mut();
+        let model_info = models
+            .get_mut(model_name)
+            .ok_or_else(|| format!("Model '{}' is not registered", model_name))?;
+        model_info.max_value_size = Some(max_value_size);
+        Ok(())
+    }
+
+    /// Store documents of a registered model over its size bound in chunks
+    ///
+    /// # Arguments
+    /// * `model_name` - Name of the registered model
+    /// * `memory_id` - Memory ID for the continuation entries (None for auto-allocation)
+    pub fn register_chunks(&self, model_name: &str, memory_id: Option<u8>) -> Result<(), String> {
+        let mut models = self.registered_models.borrow_mut();
+
+        if !models.contains_key(model_name) {
+            return Err(format!("Model '{}' is not registered", model_name));
+        }
+
+        let memory_id = self.allocate_memory_id(&models, memory_id)?;
+
+        let model_info = models
+            .get_mut(model_name)
+            .ok_or_else(|| format!("Model '{}' is not registered", model_name))?;
+        if model_info.chunk_memory_id.is_some() {
+            return Err(format!(
+                "Chunks of model '{}' are already registered",
+                model_name
+            ));
+        }
+        model_info.chunk_memory_id = Some(memory_id);
+        Ok(())
+    }
+
+    // Helper method checking a requested memory ID is free, or allocating the next one
+    fn allocate_memory_id(
+        &self,
+        models: &HashMap<String, ModelInfo>,
+        memory_id: Option<u8>,
+    ) -> Result<MemoryId, String> {
+        match memory_id {
+            Some(id) => {
+                let memory_id = MemoryId::new(id);
+                if models.values().any(|info| info.uses_memory(memory_id)) {
+                    return Err(format!("Memory ID {} is already in use", id));
+                }
+                Ok(memory_id)
+            }
+            None => {
+                // Auto-allocate memory ID
+                let mut next_id = self.next_memory_id.borrow_mut();
+                let id = MemoryId::new(*next_id);
+                *next_id += 1;
+                Ok(id)
+            }
+        }
+    }
+
+    /// Register the metadata region, keeping the schema version and migrations of every model
+    ///
+    /// # Arguments
+    /// * `memory_id` - Memory ID for the region (None for auto-allocation)
+    pub fn register_metadata(&self, memory_id: Option<u8>) -> Result<(), String> {
+        let mut models = self.registered_models.borrow_mut();
+
+        if models.contains_key(METADATA_MODEL) {
+            return Err("Metadata region is already registered".to_string());
+        }
+
+        let memory_id = self.allocate_memory_id(&models, memory_id)?;
+        models.insert(
+            METADATA_MODEL.to_string(),
+            ModelInfo {
+                _model_name: METADATA_MODEL.to_string(),
+                primary_memory_id: memory_id,
+                secondary_memory_id: None,
+                index_memory_ids: BTreeMap::new(),
+                max_value_size: None,
+                chunk_memory_id: None,
+            },
+        );
+        Ok(())
+    }
+
+    /// Get the schema version and latest migration of a registered model
+    ///
+    /// Corrupt metadata is returned as an error rather than read as version 0, which would
+    /// migrate every document again.
+    pub fn schema_metadata(&self, model_name: &str) -> Result<SchemaMetadata, String> {
+        let metadata_map = self.metadata_map(model_name)?;
+        Self::read_metadata(&metadata_map, model_name)
+    }
+
+    /// Migrate the next batch of documents of a model to the schema version of the database
+    ///
+    /// Starts a migration when the stored schema version of the model is older than the one
+    /// of the database, and resumes the stored migration otherwise, so batches can be spread
+    /// over messages and upgrades. The model is at the new schema version once the returned
+    /// progress is completed.
+    pub fn migrate_batch<T, SecondaryKey>(
+        &self,
+        model_name: &str,
+        database: &Database<T, SecondaryKey>,
+        batch_size: usize,
+    ) -> Result<MigrationProgress, String>
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+        SecondaryKey: Storable + Ord + Clone,
+    {
+        if batch_size == 0 {
+            return Err("Batch size must be greater than 0.".to_string());
+        }
+
+        let mut metadata_map = self.metadata_map(model_name)?;
+        let mut metadata = Self::read_metadata(&metadata_map, model_name)?;
+        let schema_version = database.schema_version();
+        if metadata.schema_version > schema_version {
+            return Err(format!(
+                "Model '{}' is stored at schema version {}, newer than {}",
+                model_name, metadata.schema_version, schema_version
+            ));
+        }
+        if metadata.schema_version == schema_version {
+            // Nothing left to migrate, report the latest migration if there was one
+            let mut completed = MigrationProgress::new(schema_version, schema_version);
+            completed.completed = true;
+            return Ok(metadata.migration.unwrap_or(completed));
+        }
+
+        let mut progress = match metadata.migration.take() {
+            Some(progress) if progress.to_version == schema_version && !progress.completed => {
+                progress
+            }
+            _ => MigrationProgress::new(metadata.schema_version, schema_version),
+        };
+        database.migrate_batch(&mut progress, batch_size);
+
+        if progress.completed {
+            metadata.schema_version = schema_version;
+        }
+        metadata.migration = Some(progress.clone());
+        let bytes = serialize_to_bytes(&metadata)?;
+        metadata_map.insert(model_name.to_string(), StoredValue(bytes));
+        Ok(progress)
+    }
+
+    // Helper method opening the metadata region, for a registered model
+    fn metadata_map(
+        &self,
+        model_name: &str,
+    ) -> Result<StableBTreeMap<String, StoredValue, Memory>, String> {
+        let models = self.registered_models.borrow();
+        if !models.contains_key(model_name) {
+            return Err(format!("Model '{}' is not registered", model_name));
+        }
+        let memory_id = models
+            .get(METADATA_MODEL)
+            .map(|info| info.primary_memory_id)
+            .ok_or("Metadata region is not registered".to_string())?;
+        Ok(create_stable_btree_map::<String, StoredValue>(memory_id))
+    }
+
+    // Helper method decoding the metadata of a model, models without metadata are at version 0
+    fn read_metadata(
+        metadata_map: &StableBTreeMap<String, StoredValue, Memory>,
+        model_name: &str,
+    ) -> Result<SchemaMetadata, String> {
+        match metadata_map.get(&model_name.to_string()) {
+            Some(value) => deserialize_from_bytes(&value.into_bytes()).map_err(|error| {
+                format!(
+                    "Schema metadata of model '{}' is corrupt: {}",
+                    model_name, error
+                )
+            }),
+            None => Ok(SchemaMetadata::default()),
+        }
+    }
+
     /// Create a database instance for a registered model
     pub fn get_database<T, SecondaryKey>(
         &self,
@@ -122,22 +352,67 @@ impl DatabaseManager {
             .ok_or_else(|| format!("Model '{}' is not registered", model_name))?;
 
         // Create primary map
-        let primary_map = RefCell::new(create_stable_btree_map::<CompositeKey, Document<T>>(
+        let primary_map = RefCell::new(create_stable_btree_map::<CompositeKey, StoredValue>(
             model_info.primary_memory_id,
         ));
 
         // Create secondary index if requested
         let secondary_index = model_info.secondary_memory_id.map(|memory_id| {
-            RefCell::new(create_stable_btree_map::<SecondaryKey, CompositeKeys>(
+            RefCell::new(create_stable_btree_map::<SecondaryKey, StoredValue>(
                 memory_id,
             ))
         });
 
-        Ok(Database::new(
-            primary_map,
-            secondary_index,
-            get_secondary_key,
-        ))
+        let mut database = Database::new(primary_map, secondary_index, get_secondary_key);
+        if let Some(max_value_size) = model_info.max_value_size {
+            database = database.with_max_value_size(max_value_size);
+        }
+        if let Some(memory_id) = model_info.chunk_memory_id {
+            database = database
+                .with_chunks(RefCell::new(
+                    create_stable_btree_map::<ChunkKey, StoredValue>(memory_id),
+                ));
+        }
+
+        Ok(database)
+    }
+
+    /// Create a database instance for a registered model with its named indexes
+    ///
+    /// Every index must have been registered with `register_index`.
+    pub fn get_database_with_indexes<T, SecondaryKey>(
+        &self,
+        model_name: &str,
+        get_secondary_key: Option<SecondaryKeyFn<T, SecondaryKey>>,
+        indexes: Vec<Index<T>>,
+    ) -> Result<Database<T, SecondaryKey>, String>
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+        SecondaryKey: Storable + Ord + Clone,
+    {
+        let mut database = self.get_database(model_name, get_secondary_key)?;
+
+        let models = self.registered_models.borrow();
+        let model_info = models
+            .get(model_name)
+            .ok_or_else(|| format!("Model '{}' is not registered", model_name))?;
+        for index in indexes {
+            let memory_id = model_info.index_memory_ids.get(index.name).ok_or_else(|| {
+                format!(
+                    "Index '{}' of model '{}' is not registered",
+                    index.name, model_name
+                )
+            })?;
+            let index_map =
+                RefCell::new(create_stable_btree_map::<String, StoredValue>(*memory_id));
+            database = if index.unique {
+                database.with_unique_index(index.name, index_map, index.get_keys)
+            } else {
+                database.with_index(index.name, index_map, index.get_keys)
+            };
+        }
+
+        Ok(database)
     }
 
     /// Create a database instance without secondary index
@@ -154,7 +429,7 @@ impl DatabaseManager {
         T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
     {
         let db = self.get_simple_database::<T>(model_name)?;
-        db.insert(model_name.to_string(), Some(key.to_string()), data.clone())
+        Ok(db.insert(model_name.to_string(), Some(key.to_string()), data.clone())?)
     }
 
     /// Get data from a registered model's database
@@ -167,6 +442,16 @@ impl DatabaseManager {
         Ok(document.data)
     }
 
+    /// Delete data from a registered model's database, returning the deleted data
+    pub fn delete<T>(&self, model_name: &str, key: &str) -> Result<T, String>
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    {
+        let db = self.get_simple_database::<T>(model_name)?;
+        let document = db.delete(model_name, Some(key.to_string()))?;
+        Ok(document.data)
+    }
+
     /// Query data from a registered model's database
     pub fn query<T>(
         &self,
@@ -181,6 +466,51 @@ impl DatabaseManager {
         db.query(Some(model_name), None, page_size, page_number)
     }
 
+    /// Query a registered model's database in key order, continuing from an optional cursor
+    pub fn query_range<T>(
+        &self,
+        model_name: &str,
+        condition: Option<SortKeyCondition>,
+        order: SortOrder,
+        limit: usize,
+        cursor: Option<Cursor>,
+    ) -> Result<CursorResponse<T>, String>
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    {
+        let db = self.get_simple_database::<T>(model_name)?;
+        db.query_partition(model_name, condition, order, limit, cursor)
+    }
+
+    /// Decode every document of a registered model, listing the ones that are corrupt
+    ///
+    /// Its named indexes and chunks are checked too, and its schema metadata when the metadata
+    /// region is registered. Its secondary index is checked by `Database::verify_integrity` on
+    /// a database opened with the secondary key.
+    pub fn verify_integrity<T>(&self, model_name: &str) -> Result<IntegrityReport, String>
+    where
+        T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    {
+        let db = self.get_simple_database::<T>(model_name)?;
+        let mut report = db.verify_integrity();
+        let index_memory_ids = self
+            .registered_models
+            .borrow()
+            .get(model_name)
+            .map(|model_info| model_info.index_memory_ids.clone())
+            .unwrap_or_default();
+        for (name, memory_id) in index_memory_ids {
+            let index_map = create_stable_btree_map::<String, StoredValue>(memory_id);
+            report
+                .corrupt_index_entries
+                .extend(corrupt_index_entries(&index_map, Some(&name)));
+        }
+        if let Ok(metadata_map) = self.metadata_map(model_name) {
+            report.corrupt_metadata = Self::read_metadata(&metadata_map, model_name).err();
+        }
+        Ok(report)
+    }
+
     /// List all registered models
     pub fn list_models(&self) -> Vec<String> {
         self.registered_models.borrow().keys().cloned().collect()
@@ -211,15 +541,8 @@ impl DatabaseManager {
         for id in start..=end {
             // Check if this memory ID is already in use
             let memory_id = MemoryId::new(id);
-            for (_, info) in models.iter() {
-                if info.primary_memory_id == memory_id {
-                    return Err(format!("Memory ID {} is already in use", id));
-                }
-                if let Some(secondary_id) = info.secondary_memory_id {
-                    if secondary_id == memory_id {
-                        return Err(format!("Memory ID {} is already in use", id));
-                    }
-                }
+            if models.values().any(|info| info.uses_memory(memory_id)) {
+                return Err(format!("Memory ID {} is already in use", id));
             }
         }
 
@@ -230,6 +553,9 @@ impl DatabaseManager {
                 _model_name: description.to_string(),
                 primary_memory_id: MemoryId::new(start),
                 secondary_memory_id: Some(MemoryId::new(end)),
+                index_memory_ids: BTreeMap::new(),
+                max_value_size: None,
+                chunk_memory_id: None,
             },
         );
 
@@ -248,3 +574,210 @@ impl Default for DatabaseManager {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::Migration;
+
+    #[test]
+    fn test_register_index_memory_ids() {
+        let db_manager = DatabaseManager::new();
+        db_manager
+            .register_model("users", Some(30), Some(31))
+            .unwrap();
+        db_manager
+            .register_index("users", "email", Some(32))
+            .unwrap();
+
+        // Memory IDs stay unique across models, secondary and named indexes
+        assert!(db_manager
+            .register_index("users", "name", Some(31))
+            .is_err());
+        assert!(db_manager
+            .register_index("users", "email", Some(33))
+            .is_err());
+        assert!(db_manager
+            .register_index("posts", "title", Some(33))
+            .is_err());
+        assert!(db_manager.register_model("posts", Some(32), None).is_err());
+
+        let with_email = db_manager.get_database_with_indexes::<String, ()>(
+            "users",
+            None,
+            vec![Index::new("email", |email: &String| vec![email.clone()])],
+        );
+        assert!(with_email.is_ok());
+        let with_unknown = db_manager.get_database_with_indexes::<String, ()>(
+            "users",
+            None,
+            vec![Index::new("name", |name: &String| vec![name.clone()])],
+        );
+        assert!(with_unknown.is_err());
+    }
+
+    #[test]
+    fn test_size_bound_and_chunks() {
+        let db_manager = DatabaseManager::new();
+        db_manager.register_model("notes", Some(40), None).unwrap();
+        db_manager.register_model("drafts", Some(41), None).unwrap();
+        db_manager.set_max_value_size("notes", 64).unwrap();
+        db_manager.set_max_value_size("drafts", 64).unwrap();
+        db_manager.register_chunks("drafts", Some(42)).unwrap();
+
+        assert!(db_manager.set_max_value_size("notes", 0).is_err());
+        assert!(db_manager.register_chunks("notes", Some(40)).is_err());
+        assert!(db_manager.register_chunks("drafts", Some(43)).is_err());
+        assert!(db_manager.register_chunks("posts", None).is_err());
+
+        // Oversized writes fail without chunks and round-trip with them
+        let text = "x".repeat(500);
+        assert!(db_manager.insert("notes", "big", &text).is_err());
+        assert!(db_manager.get::<String>("notes", "big").is_err());
+        db_manager.insert("drafts", "big", &text).unwrap();
+        assert_eq!(db_manager.get::<String>("drafts", "big"), Ok(text));
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize, CandidType)]
+    struct ContactV0 {
+        name: String,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq)]
+    struct Contact {
+        name: String,
+        email: String,
+    }
+
+    struct AddEmail;
+
+    impl Migration<Contact> for AddEmail {
+        fn from_version(&self) -> u32 {
+            0
+        }
+
+        fn migrate(&self, bytes: &[u8]) -> Result<Contact, String> {
+            let contact = Document::<ContactV0>::from_bytes(bytes)?.data;
+            Ok(Contact {
+                email: format!("{}@example.com", contact.name),
+                name: contact.name,
+            })
+        }
+    }
+
+    #[test]
+    fn test_migrate_batches_to_new_schema_version() {
+        let db_manager = DatabaseManager::new();
+        db_manager
+            .register_model("contacts", Some(50), None)
+            .unwrap();
+        let contacts_v1 = || {
+            db_manager
+                .get_simple_database::<Contact>("contacts")
+                .unwrap()
+                .with_migrations(1, vec![Box::new(AddEmail)])
+        };
+        assert!(db_manager
+            .migrate_batch("contacts", &contacts_v1(), 2)
+            .is_err());
+        db_manager.register_metadata(Some(51)).unwrap();
+        assert!(db_manager.register_model("other", Some(51), None).is_err());
+
+        // Documents written with the old schema
+        let contacts_v0 = db_manager
+            .get_simple_database::<ContactV0>("contacts")
+            .unwrap();
+        for name in ["a", "b", "c", "d", "e"] {
+            let contact = ContactV0 {
+                name: name.to_string(),
+            };
+            contacts_v0
+                .insert("contacts".to_string(), Some(name.to_string()), contact)
+                .unwrap();
+        }
+
+        // Reads migrate old documents before any batch ran
+        let contacts = contacts_v1();
+        let contact = contacts.get("contacts", Some("a".to_string())).unwrap();
+        assert_eq!(contact.data.email, "a@example.com");
+        assert_eq!((contact.version, contact.schema_version), (1, 1));
+
+        // Batches resume from the stored progress until every document is migrated
+        let mut batches = 0;
+        let progress = loop {
+            let progress = db_manager.migrate_batch("contacts", &contacts, 2).unwrap();
+            batches += 1;
+            if progress.completed {
+                break progress;
+            }
+        };
+        assert_eq!(batches, 3);
+        assert_eq!((progress.from_version, progress.to_version), (0, 1));
+        assert_eq!((progress.migrated, progress.failed), (5, 0));
+
+        let metadata = db_manager.schema_metadata("contacts").unwrap();
+        assert_eq!(metadata.schema_version, 1);
+        assert_eq!(metadata.migration, Some(progress.clone()));
+        assert_eq!(
+            db_manager.migrate_batch("contacts", &contacts, 2),
+            Ok(progress)
+        );
+
+        // Migrated documents decode without their migration, an older database is refused
+        let without_migrations = db_manager
+            .get_simple_database::<Contact>("contacts")
+            .unwrap()
+            .with_migrations(1, Vec::new());
+        let contact = without_migrations
+            .get("contacts", Some("e".to_string()))
+            .unwrap();
+        assert_eq!(contact.data.email, "e@example.com");
+        assert!(db_manager
+            .migrate_batch("contacts", &contacts_v0, 2)
+            .is_err());
+    }
+
+    #[test]
+    fn test_corrupt_schema_metadata_is_reported() {
+        let db_manager = DatabaseManager::new();
+        db_manager
+            .register_model("contacts", Some(60), None)
+            .unwrap();
+        db_manager.register_metadata(Some(61)).unwrap();
+        let contacts = db_manager
+            .get_simple_database::<Contact>("contacts")
+            .unwrap()
+            .with_migrations(1, vec![Box::new(AddEmail)]);
+        assert_eq!(
+            db_manager.verify_integrity::<Contact>("contacts"),
+            Ok(IntegrityReport::default())
+        );
+
+        create_stable_btree_map::<String, StoredValue>(MemoryId::new(61))
+            .insert("contacts".to_string(), StoredValue(vec![0xff]));
+
+        // Corrupt metadata is not read as version 0, so no migration runs again
+        assert!(db_manager.schema_metadata("contacts").is_err());
+        assert!(db_manager.migrate_batch("contacts", &contacts, 2).is_err());
+        let report = db_manager.verify_integrity::<Contact>("contacts").unwrap();
+        assert!(report.corrupt.is_empty());
+        assert!(report.corrupt_metadata.is_some());
+    }
+
+    #[test]
+    fn test_corrupt_index_entries_are_reported() {
+        let db_manager = DatabaseManager::new();
+        db_manager.register_model("tags", Some(70), None).unwrap();
+        db_manager.register_index("tags", "name", Some(71)).unwrap();
+        create_stable_btree_map::<String, StoredValue>(MemoryId::new(71))
+            .insert("rust".to_string(), StoredValue(vec![0xff]));
+
+        let report = db_manager.verify_integrity::<String>("tags").unwrap();
+        assert_eq!(report.corrupt_index_entries.len(), 1);
+        assert_eq!(
+            report.corrupt_index_entries[0].index.as_deref(),
+            Some("name")
+        );
+        assert_eq!(report.corrupt_index_entries[0].key, b"rust".to_vec());
+    }
+}
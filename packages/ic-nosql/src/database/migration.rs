@@ -0,0 +1,27 @@
+use std::rc::Rc;
+use std::time::Duration;
+
+use super::types::MigrationProgress;
+use crate::utils::serialization::report_error;
+
+/// Run migration batches on timers until the migration completes
+///
+/// Each batch runs in its own message, keeping large migrations within the instruction limit.
+/// `run_batch` usually calls `DatabaseManager::migrate_batch`. Timers do not survive upgrades,
+/// so schedule the migration again from `post_upgrade`: it resumes from the progress kept in
+/// the metadata region.
+pub fn schedule_migration(
+    interval: Duration,
+    run_batch: impl Fn() -> Result<MigrationProgress, String> + 'static,
+) {
+    schedule_next(interval, Rc::new(run_batch));
+}
+
+// Schedule the next batch, stopping once the migration completes or fails
+fn schedule_next(interval: Duration, run_batch: Rc<dyn Fn() -> Result<MigrationProgress, String>>) {
+    ic_cdk_timers::set_timer(interval, move || match run_batch() {
+        Ok(progress) if !progress.completed => schedule_next(interval, run_batch),
+        Ok(_) => {}
+        Err(error) => report_error(&format!("Migration stopped: {}", error)),
+    });
+}
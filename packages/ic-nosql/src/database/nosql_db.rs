@@ -1,20 +1,44 @@
+use candid::types::reserved::Reserved;
 use candid::CandidType;
 use ic_stable_structures::{StableBTreeMap, Storable};
 use serde::{Deserialize, Serialize};
 use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::Bound;
 
-use super::types::{CompositeKey, CompositeKeys, Document, QueryResponse};
+use super::query_builder::QueryBuilder;
+use super::types::{
+    ChunkKey, CompositeKey, CompositeKeys, CorruptEntry, CorruptIndexEntry, Cursor, CursorResponse,
+    DatabaseError, Document, IntegrityReport, MigrationProgress, QueryResponse, SortKeyCondition,
+    SortOrder, StoredValue, WriteCondition, DEFAULT_MAX_VALUE_SIZE,
+};
 use crate::memory::stable_memory::Memory;
+use crate::traits::{IndexKeyFn, Migration};
+use crate::utils::serialization::report_error;
+use crate::utils::time::{self, Clock};
 
-/// Database implementation with support for primary and secondary indexes
+/// A named index and the function deriving the keys of a document in it
+struct NamedIndex<T> {
+    map: RefCell<StableBTreeMap<String, StoredValue, Memory>>,
+    get_keys: IndexKeyFn<T>,
+    unique: bool,
+}
+
+/// Database implementation with support for primary, secondary and named indexes
 pub struct Database<T, SecondaryKey = ()>
 where
     T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
     SecondaryKey: Clone + Ord + Storable,
 {
-    map: RefCell<StableBTreeMap<CompositeKey, Document<T>, Memory>>, // Primary map
-    secondary_index: Option<RefCell<StableBTreeMap<SecondaryKey, CompositeKeys, Memory>>>, // Optional secondary index
+    map: RefCell<StableBTreeMap<CompositeKey, StoredValue, Memory>>, // Primary map
+    secondary_index: Option<RefCell<StableBTreeMap<SecondaryKey, StoredValue, Memory>>>, // Optional secondary index
     get_secondary_key: Option<Box<dyn Fn(&T) -> Option<SecondaryKey>>>, // Function to derive secondary index key
+    indexes: BTreeMap<String, NamedIndex<T>>,                           // Named indexes by name
+    clock: Clock,        // Time source for `updated_at`
+    max_value_size: u32, // Size bound of a single stored entry
+    chunks: Option<RefCell<StableBTreeMap<ChunkKey, StoredValue, Memory>>>, // Continuation entries of large documents
+    schema_version: u32, // Schema version stamped on written documents
+    migrations: Vec<Box<dyn Migration<T>>>, // Readers of documents of older schema versions
 }
 
 impl<T, SecondaryKey> Database<T, SecondaryKey>
@@ -24,98 +48,140 @@ where
 {
     /// Constructor to initialize the NoSQL database with an optional secondary index
     pub fn new(
-        map: RefCell<StableBTreeMap<CompositeKey, Document<T>, Memory>>,
-        secondary_index: Option<RefCell<StableBTreeMap<SecondaryKey, CompositeKeys, Memory>>>,
+        map: RefCell<StableBTreeMap<CompositeKey, StoredValue, Memory>>,
+        secondary_index: Option<RefCell<StableBTreeMap<SecondaryKey, StoredValue, Memory>>>,
         get_secondary_key: Option<Box<dyn Fn(&T) -> Option<SecondaryKey>>>,
     ) -> Self {
         Database {
             map,
             secondary_index,
             get_secondary_key,
+            indexes: BTreeMap::new(),
+            clock: time::now,
+            max_value_size: DEFAULT_MAX_VALUE_SIZE,
+            chunks: None,
+            schema_version: 0,
+            migrations: Vec::new(),
         }
     }
 
+    /// Write documents at a schema version, reading older ones through their migrations
+    ///
+    /// Documents of an older schema version are migrated whenever they are read, and
+    /// rewritten at the current one by `DatabaseManager::migrate_batch`.
+    pub fn with_migrations(
+        mut self,
+        schema_version: u32,
+        migrations: Vec<Box<dyn Migration<T>>>,
+    ) -> Self {
+        self.schema_version = schema_version;
+        self.migrations = migrations;
+        self
+    }
+
+    /// Schema version documents are written with
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+
+    /// Bound the size of a single stored entry, 4096 bytes by default
+    ///
+    /// Without chunking, writing a larger document fails with `DatabaseError::ValueTooLarge`.
+    pub fn with_max_value_size(mut self, max_value_size: u32) -> Self {
+        self.max_value_size = max_value_size.max(1);
+        self
+    }
+
+    /// Split documents over the size bound across continuation entries in the chunk map
+    pub fn with_chunks(
+        mut self,
+        chunks: RefCell<StableBTreeMap<ChunkKey, StoredValue, Memory>>,
+    ) -> Self {
+        self.chunks = Some(chunks);
+        self
+    }
+
+    /// Use another time source for the `updated_at` of written documents
+    pub fn with_clock(mut self, clock: Clock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Add a named index, queried with `query_index`
+    pub fn with_index(
+        mut self,
+        name: &str,
+        map: RefCell<StableBTreeMap<String, StoredValue, Memory>>,
+        get_keys: IndexKeyFn<T>,
+    ) -> Self {
+        self.indexes.insert(
+            name.to_string(),
+            NamedIndex {
+                map,
+                get_keys,
+                unique: false,
+            },
+        );
+        self
+    }
+
+    /// Add a unique index, looked up with `get_by_unique`
+    ///
+    /// Inserts fail with `DatabaseError::UniqueConstraintViolation` when another document
+    /// already holds one of their keys.
+    pub fn with_unique_index(
+        mut self,
+        name: &str,
+        map: RefCell<StableBTreeMap<String, StoredValue, Memory>>,
+        get_keys: IndexKeyFn<T>,
+    ) -> Self {
+        self.indexes.insert(
+            name.to_string(),
+            NamedIndex {
+                map,
+                get_keys,
+                unique: true,
+            },
+        );
+        self
+    }
+
     /// Insert a document and update secondary index if applicable
     pub fn insert(
         &self,
         partition_key: String,
         sort_key: Option<String>,
         data: T,
-    ) -> Result<Document<T>, String> {
-        let document = Document {
-            partition_key: partition_key.clone(),
-            sort_key: sort_key.clone(),
-            data: data.clone(),
+    ) -> Result<Document<T>, DatabaseError> {
+        let key = CompositeKey {
+            partition_key,
+            sort_key,
         };
+        let (document, _) = self.put(key, data, None)?;
+        Ok(document)
+    }
 
+    /// Write a document only if its stored version is the expected one
+    ///
+    /// `None` expects no document under the key. Documents stored before versioning
+    /// have version 0. Returns `DatabaseError::ConditionFailed` when another write
+    /// happened in between, leaving the stored document untouched.
+    pub fn compare_and_put(
+        &self,
+        partition_key: String,
+        sort_key: Option<String>,
+        expected_version: Option<u64>,
+        data: T,
+    ) -> Result<Document<T>, DatabaseError> {
         let key = CompositeKey {
-            partition_key: partition_key.clone(),
-            sort_key: sort_key.clone(),
+            partition_key,
+            sort_key,
         };
-
-        // To avoid borrowing conflicts, check and remove existing documents in separate scopes
-        let old_secondary_key = {
-            // Check if the key already exists in the primary map
-            if let Some(existing_document) = self.map.borrow().get(&key) {
-                // If the document exists, check if there is an old secondary key
-                if let (Some(_), Some(get_secondary_key)) =
-                    (&self.secondary_index, &self.get_secondary_key)
-                {
-                    get_secondary_key(&existing_document.data)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
+        let condition = match expected_version {
+            Some(version) => WriteCondition::VersionEquals(version),
+            None => WriteCondition::NotExists,
         };
-
-        // If an old secondary key exists, update the secondary index
-        if let Some(old_key) = old_secondary_key {
-            if let (Some(secondary_index), _) = (&self.secondary_index, &self.get_secondary_key) {
-                let mut index_map = secondary_index.borrow_mut();
-                if let Some(mut composite_keys) = index_map.get(&old_key) {
-                    // Remove the stale key from the secondary index
-                    composite_keys.0.retain(|k| k != &key);
-
-                    // If no keys remain, remove the secondary key entry
-                    if composite_keys.0.is_empty() {
-                        index_map.remove(&old_key);
-                    } else {
-                        index_map.insert(old_key, composite_keys);
-                    }
-                }
-            }
-        }
-
-        // Remove the old document
-        {
-            let mut map = self.map.borrow_mut();
-            // Explicitly remove the old document from the primary map to free memory
-            map.remove(&key);
-            // Insert into the primary map
-            map.insert(key.clone(), document.clone());
-        }
-
-        // Update the secondary index if a key function is provided
-        if let (Some(secondary_index), Some(get_secondary_key)) =
-            (&self.secondary_index, &self.get_secondary_key)
-        {
-            if let Some(new_secondary_key) = get_secondary_key(&data) {
-                let mut index_map = secondary_index.borrow_mut();
-
-                // Check if the secondary key already exists
-                if let Some(mut composite_keys) = index_map.get(&new_secondary_key) {
-                    // If it exists, append the new key
-                    composite_keys.0.push(key.clone());
-                    index_map.insert(new_secondary_key.clone(), composite_keys);
-                } else {
-                    // If it doesn't exist, create a new CompositeKeys entry
-                    index_map.insert(new_secondary_key, CompositeKeys(vec![key.clone()]));
-                }
-            }
-        }
-
+        let (document, _) = self.put(key, data, Some(&condition))?;
         Ok(document)
     }
 
@@ -131,10 +197,41 @@ where
         };
 
         // Attempt to retrieve the document from the primary map
-        self.map
+        self.read(&key)?.ok_or("Document not found.".to_string())
+    }
+
+    /// Delete a single document by partition key and optional sort key, returning it
+    pub fn delete(
+        &self,
+        partition_key: &str,
+        sort_key: Option<String>,
+    ) -> Result<Document<T>, String> {
+        let key = CompositeKey {
+            partition_key: partition_key.to_string(),
+            sort_key,
+        };
+        Ok(self.remove(&key, None)?)
+    }
+
+    /// Delete every document of a partition, returning how many were deleted
+    pub fn delete_partition(&self, partition_key: &str) -> Result<usize, String> {
+        // Collect the documents first, the map cannot be modified while iterating
+        let documents: Vec<(CompositeKey, Result<Document<T>, DatabaseError>)> = self
+            .map
             .borrow()
-            .get(&key)
-            .ok_or("Document not found.".to_string())
+            .range(Self::partition_range(partition_key))
+            .map(|(key, value)| {
+                let document = self.load(&key, value);
+                (key, document)
+            })
+            .collect();
+
+        for (key, document) in &documents {
+            // Corrupt documents are dropped too, their index entries no longer resolve
+            self.replace(key, document.as_ref().ok(), None);
+        }
+
+        Ok(documents.len())
     }
 
     /// Query by either partition key or secondary index with pagination
@@ -173,6 +270,674 @@ where
         }
     }
 
+    /// Query a partition in sort key order, continuing from an optional cursor
+    ///
+    /// Documents are streamed from the primary map, so the cost of a page does not grow
+    /// with how deep into the partition it is.
+    pub fn query_partition(
+        &self,
+        partition_key: &str,
+        condition: Option<SortKeyCondition>,
+        order: SortOrder,
+        limit: usize,
+        cursor: Option<Cursor>,
+    ) -> Result<CursorResponse<T>, String> {
+        if limit == 0 {
+            return Err("Limit must be greater than 0.".to_string());
+        }
+        if let Some(SortKeyCondition::Between(low, high)) = &condition {
+            if low > high {
+                return Err("Sort key range start must not be greater than its end.".to_string());
+            }
+        }
+
+        let (mut start, mut end) = Self::sort_key_bounds(partition_key, condition.as_ref());
+
+        // Continue strictly after the last returned key, in the direction of the query
+        if let Some(cursor) = cursor {
+            if cursor.last_key().partition_key != partition_key {
+                return Err("Cursor does not belong to the queried partition.".to_string());
+            }
+            let last_key = Bound::Excluded(cursor.last_key().clone());
+            match order {
+                SortOrder::Ascending => start = last_key,
+                SortOrder::Descending => end = last_key,
+            }
+        }
+
+        let map = self.map.borrow();
+        let range = map.range((start, end));
+        let entries: Box<dyn Iterator<Item = (CompositeKey, StoredValue)>> = match order {
+            SortOrder::Ascending => Box::new(range),
+            SortOrder::Descending => Box::new(range.rev()),
+        };
+
+        Ok(self.cursor_page(entries, limit))
+    }
+
+    // Helper method collecting up to `limit` documents, skipping and reporting corrupt ones
+    fn cursor_page(
+        &self,
+        entries: impl Iterator<Item = (CompositeKey, StoredValue)>,
+        limit: usize,
+    ) -> CursorResponse<T> {
+        let mut results = Vec::new();
+        let mut last_key = None;
+        let mut documents = entries.filter_map(|(key, value)| {
+            let document = self.load_or_report(&key, value)?;
+            Some((key, document))
+        });
+        for (key, document) in documents.by_ref().take(limit) {
+            results.push(document);
+            last_key = Some(key);
+        }
+
+        // Only hand out a cursor when another document follows
+        let next_cursor = match documents.next() {
+            Some(_) => last_key.map(Cursor::new),
+            None => None,
+        };
+
+        CursorResponse {
+            results,
+            next_cursor,
+        }
+    }
+
+    /// Query a named index by key with pagination
+    pub fn query_index(
+        &self,
+        name: &str,
+        key: &str,
+        page_size: usize,
+        page_number: usize,
+    ) -> Result<QueryResponse<T>, String> {
+        // Validate page params
+        if page_size == 0 {
+            return Err("Page size must be greater than 0.".to_string());
+        }
+        if page_number == 0 {
+            return Err("Page number must be greater than 0.".to_string());
+        }
+
+        let index = self
+            .indexes
+            .get(name)
+            .ok_or_else(|| format!("Index '{}' is not configured.", name))?;
+
+        // Retrieve keys matching the index key
+        let keys = index_entry(&index.map.borrow(), &key.to_string())
+            .ok_or("No entries found for the given index key.".to_string())?;
+
+        // Get all matching documents
+        let matching_documents: Vec<Document<T>> = keys
+            .0
+            .iter()
+            .filter_map(|key| self.read_or_report(key))
+            .collect();
+
+        paginate(matching_documents, page_size, page_number)
+    }
+
+    /// Get the single document holding a key of a unique index
+    pub fn get_by_unique(&self, name: &str, key: &str) -> Result<Document<T>, String> {
+        let index = self
+            .indexes
+            .get(name)
+            .ok_or_else(|| format!("Index '{}' is not configured.", name))?;
+        if !index.unique {
+            return Err(format!("Index '{}' is not unique.", name));
+        }
+
+        let key = index_entry(&index.map.borrow(), &key.to_string())
+            .and_then(|keys| keys.0.first().cloned())
+            .ok_or("Document not found.".to_string())?;
+        self.read(&key)?.ok_or("Document not found.".to_string())
+    }
+
+    /// Start a query builder with filters, limits, counts and projections
+    pub fn query_builder(&self) -> QueryBuilder<'_, T, SecondaryKey> {
+        QueryBuilder::new(self)
+    }
+
+    /// Scan every document in primary key order with pagination
+    ///
+    /// Unlike `query`, a page past the end yields an empty result instead of an error.
+    pub fn scan(&self, page_size: usize, page_number: usize) -> Result<QueryResponse<T>, String> {
+        // Validate page params
+        if page_size == 0 {
+            return Err("Page size must be greater than 0.".to_string());
+        }
+        if page_number == 0 {
+            return Err("Page number must be greater than 0.".to_string());
+        }
+
+        let map = self.map.borrow();
+        let total_documents = map.len() as usize;
+        let total_pages = total_documents.div_ceil(page_size);
+        let start_index = (page_number - 1) * page_size;
+
+        Ok(QueryResponse {
+            page_number,
+            page_size,
+            total_pages,
+            results: map
+                .iter()
+                .skip(start_index)
+                .take(page_size)
+                .filter_map(|(key, value)| self.load_or_report(&key, value))
+                .collect(),
+        })
+    }
+
+    /// Scan every document in primary key order, continuing from an optional cursor
+    ///
+    /// Unlike `scan`, corrupt documents do not shorten a page and the cost of a page does not
+    /// grow with how deep into the database it is. The cursor may come from any partition.
+    pub fn scan_from(
+        &self,
+        limit: usize,
+        cursor: Option<Cursor>,
+    ) -> Result<CursorResponse<T>, String> {
+        if limit == 0 {
+            return Err("Limit must be greater than 0.".to_string());
+        }
+
+        let start = match cursor {
+            Some(cursor) => Bound::Excluded(cursor.last_key().clone()),
+            None => Bound::Unbounded,
+        };
+        let map = self.map.borrow();
+        let entries = map.range((start, Bound::Unbounded));
+        Ok(self.cursor_page(entries, limit))
+    }
+
+    /// Decode every stored document and index entry, listing the ones that are corrupt
+    ///
+    /// Reads skip corrupt documents and report them on the debug log, `get` returns
+    /// `DatabaseError::CorruptDocument` for them. Index queries skip corrupt index entries
+    /// the same way. The secondary index is only checked when the database derives its keys,
+    /// as its key type is unknown otherwise. Continuation entries no document refers to are
+    /// listed as orphaned.
+    pub fn verify_integrity(&self) -> IntegrityReport {
+        let mut report = IntegrityReport::default();
+        let map = self.map.borrow();
+        for (key, value) in map.iter() {
+            report.checked += 1;
+            if let Err(error) = self.load(&key, value) {
+                report.corrupt.push(CorruptEntry {
+                    key,
+                    error: error.to_string(),
+                });
+            }
+        }
+
+        if let (Some(secondary_index), Some(_)) = (&self.secondary_index, &self.get_secondary_key) {
+            report
+                .corrupt_index_entries
+                .extend(corrupt_index_entries(&secondary_index.borrow(), None));
+        }
+        for (name, index) in &self.indexes {
+            report
+                .corrupt_index_entries
+                .extend(corrupt_index_entries(&index.map.borrow(), Some(name)));
+        }
+
+        if let Some(chunks) = &self.chunks {
+            for (chunk_key, _) in chunks.borrow().iter() {
+                let referenced = map
+                    .get(&chunk_key.key)
+                    .and_then(|value| value.chunk_count())
+                    .is_some_and(|count| chunk_key.index < count);
+                if !referenced {
+                    report.orphaned_chunks.push(chunk_key);
+                }
+            }
+        }
+        report
+    }
+
+    /// Remove a corrupt document from the database, returning its raw bytes
+    ///
+    /// The bytes of a chunked document are reassembled from the chunks that remain. Index
+    /// entries of the document are left behind and no longer resolve to it.
+    pub fn quarantine(
+        &self,
+        partition_key: &str,
+        sort_key: Option<String>,
+    ) -> Result<Vec<u8>, String> {
+        let key = CompositeKey {
+            partition_key: partition_key.to_string(),
+            sort_key,
+        };
+        let value = self
+            .map
+            .borrow()
+            .get(&key)
+            .ok_or("Document not found.".to_string())?;
+        if self.load(&key, value.clone()).is_ok() {
+            return Err("Document is not corrupt.".to_string());
+        }
+
+        let bytes = match (value.chunk_count(), &self.chunks) {
+            (Some(count), Some(chunks)) => {
+                let chunks = chunks.borrow();
+                (0..count)
+                    .filter_map(|index| {
+                        chunks.get(&ChunkKey {
+                            key: key.clone(),
+                            index,
+                        })
+                    })
+                    .flat_map(StoredValue::into_bytes)
+                    .collect()
+            }
+            _ => value.into_bytes(),
+        };
+        self.erase(&key);
+        Ok(bytes)
+    }
+
+    // Write a document if the condition holds, returning it and the document it replaced
+    pub(crate) fn put(
+        &self,
+        key: CompositeKey,
+        data: T,
+        condition: Option<&WriteCondition>,
+    ) -> Result<(Document<T>, Option<Document<T>>), DatabaseError> {
+        // To avoid borrowing conflicts, read the existing document in its own scope
+        let existing_document = match self.read(&key) {
+            Ok(existing_document) => existing_document,
+            // An unconditional write replaces a corrupt document, starting over at version 1
+            Err(error) if condition.is_none() => {
+                report_error(&error);
+                None
+            }
+            Err(error) => return Err(error),
+        };
+
+        // Reject the write before touching anything if a condition or unique index fails
+        check_condition(&key, existing_document.as_ref(), condition)?;
+        self.check_unique_indexes(&key, &data)?;
+
+        let document = Document {
+            partition_key: key.partition_key.clone(),
+            sort_key: key.sort_key.clone(),
+            version: existing_document
+                .as_ref()
+                .map_or(1, |existing| existing.version + 1),
+            updated_at: (self.clock)(),
+            schema_version: self.schema_version,
+            data,
+        };
+        // Encode before touching anything, so an oversized document leaves no trace
+        let value = self.encode(&document)?;
+        self.replace(&key, existing_document.as_ref(), Some((&document, value)));
+
+        Ok((document, existing_document))
+    }
+
+    // Delete a document if the condition holds, returning it
+    pub(crate) fn remove(
+        &self,
+        key: &CompositeKey,
+        condition: Option<&WriteCondition>,
+    ) -> Result<Document<T>, DatabaseError> {
+        let existing_document = self.read(key)?;
+        check_condition(key, existing_document.as_ref(), condition)?;
+
+        let document = existing_document.ok_or_else(|| DatabaseError::DocumentNotFound {
+            partition_key: key.partition_key.clone(),
+            sort_key: key.sort_key.clone(),
+        })?;
+        self.replace(key, Some(&document), None);
+
+        Ok(document)
+    }
+
+    // Put back a document exactly as it was before a write, or drop it if there was none
+    pub(crate) fn restore(&self, key: &CompositeKey, previous: Option<Document<T>>) {
+        let current = self.read(key).ok().flatten();
+        // The previous document was stored before, so it is written back without a size check
+        let value = previous
+            .as_ref()
+            .and_then(|document| document.to_bytes().ok());
+        self.replace(key, current.as_ref(), previous.as_ref().zip(value));
+    }
+
+    // Helper method swapping the stored document of a key, keeping the indexes in sync
+    fn replace(
+        &self,
+        key: &CompositeKey,
+        existing_document: Option<&Document<T>>,
+        document: Option<(&Document<T>, Vec<u8>)>,
+    ) {
+        // If the document exists, drop its key from the indexes
+        if let Some(existing_document) = existing_document {
+            self.remove_from_indexes(key, &existing_document.data);
+        }
+
+        // Explicitly remove the old document and its chunks to free memory
+        self.erase(key);
+
+        // Store and index the new document under its current keys
+        if let Some((document, value)) = document {
+            self.write(key, value);
+            self.add_to_indexes(key, &document.data);
+        }
+    }
+
+    // Helper method encoding a document, checking its size unless it can be chunked
+    fn encode(&self, document: &Document<T>) -> Result<Vec<u8>, DatabaseError> {
+        let value = document.to_bytes().map_err(DatabaseError::Serialization)?;
+        if self.chunks.is_none() && value.len() > self.max_value_size as usize {
+            return Err(DatabaseError::ValueTooLarge {
+                size: value.len(),
+                max_size: self.max_value_size,
+            });
+        }
+        Ok(value)
+    }
+
+    // Helper method storing an encoded document, split into chunks when over the size bound
+    fn write(&self, key: &CompositeKey, value: Vec<u8>) {
+        let chunk_size = self.max_value_size as usize;
+        match &self.chunks {
+            Some(chunks) if value.len() > chunk_size => {
+                let mut chunks = chunks.borrow_mut();
+                let mut count = 0;
+                for chunk in value.chunks(chunk_size) {
+                    let chunk_key = ChunkKey {
+                        key: key.clone(),
+                        index: count,
+                    };
+                    chunks.insert(chunk_key, StoredValue(chunk.to_vec()));
+                    count += 1;
+                }
+                self.map
+                    .borrow_mut()
+                    .insert(key.clone(), StoredValue::chunk_header(count));
+            }
+            _ => {
+                self.map
+                    .borrow_mut()
+                    .insert(key.clone(), StoredValue(value));
+            }
+        }
+    }
+
+    // Helper method removing the entry of a key along with its chunks
+    fn erase(&self, key: &CompositeKey) {
+        let count = self
+            .map
+            .borrow_mut()
+            .remove(key)
+            .and_then(|value| value.chunk_count());
+        if let (Some(count), Some(chunks)) = (count, &self.chunks) {
+            let mut chunks = chunks.borrow_mut();
+            for index in 0..count {
+                chunks.remove(&ChunkKey {
+                    key: key.clone(),
+                    index,
+                });
+            }
+        }
+    }
+
+    // Helper method reading the document stored under a key
+    fn read(&self, key: &CompositeKey) -> Result<Option<Document<T>>, DatabaseError> {
+        let value = self.map.borrow().get(key);
+        value.map(|value| self.load(key, value)).transpose()
+    }
+
+    // Helper method reading the document stored under a key, skipping it if corrupt
+    fn read_or_report(&self, key: &CompositeKey) -> Option<Document<T>> {
+        self.read(key).unwrap_or_else(|error| {
+            report_error(&error);
+            None
+        })
+    }
+
+    // Helper method decoding a primary entry, reassembling its chunks if it has any
+    fn load(&self, key: &CompositeKey, value: StoredValue) -> Result<Document<T>, DatabaseError> {
+        let bytes = self.assemble(key, value)?;
+        self.decode(key, &bytes)
+    }
+
+    // Helper method for the bytes of a primary entry, reassembled from its chunks if it has any
+    fn assemble(&self, key: &CompositeKey, value: StoredValue) -> Result<Vec<u8>, DatabaseError> {
+        let corrupt = |error: String| corrupt_document(key, error);
+        let bytes = match (value.chunk_count(), &self.chunks) {
+            (Some(count), Some(chunks)) => {
+                let chunks = chunks.borrow();
+                let mut bytes = Vec::new();
+                for index in 0..count {
+                    let chunk = chunks
+                        .get(&ChunkKey {
+                            key: key.clone(),
+                            index,
+                        })
+                        .ok_or_else(|| {
+                            corrupt(format!("Chunk {} of {} is missing", index, count))
+                        })?;
+                    bytes.extend(chunk.into_bytes());
+                }
+                bytes
+            }
+            (Some(_), None) => {
+                return Err(corrupt(
+                    "Document is chunked but no chunk memory is configured".to_string(),
+                ))
+            }
+            (None, _) => value.into_bytes(),
+        };
+        Ok(bytes)
+    }
+
+    // Helper method decoding a stored document, migrating it if it has an older schema version
+    fn decode(&self, key: &CompositeKey, bytes: &[u8]) -> Result<Document<T>, DatabaseError> {
+        let decoded = Document::<T>::from_bytes(bytes);
+        if let Ok(document) = &decoded {
+            if document.schema_version >= self.schema_version {
+                return decoded.map_err(|error| corrupt_document(key, error));
+            }
+        }
+
+        // Read the keys and versions whatever the layout of the data, to pick the migration
+        let header = Document::<Reserved>::from_bytes(bytes)
+            .map_err(|error| corrupt_document(key, error))?;
+        let migration = self
+            .migrations
+            .iter()
+            .find(|migration| migration.from_version() == header.schema_version);
+        match migration {
+            Some(migration) => Ok(Document {
+                partition_key: header.partition_key,
+                sort_key: header.sort_key,
+                version: header.version,
+                updated_at: header.updated_at,
+                schema_version: self.schema_version,
+                data: migration
+                    .migrate(bytes)
+                    .map_err(|error| corrupt_document(key, error))?,
+            }),
+            // Without a migration, a document still decoding as the current model is kept
+            None => decoded.map_err(|error| corrupt_document(key, error)),
+        }
+    }
+
+    // Rewrite the next batch of documents of older schema versions at the current one
+    pub(crate) fn migrate_batch(&self, progress: &mut MigrationProgress, batch_size: usize) {
+        // Collect the batch first, the map cannot be modified while iterating
+        let start = match &progress.cursor {
+            Some(cursor) => Bound::Excluded(cursor.clone()),
+            None => Bound::Unbounded,
+        };
+        let entries: Vec<(CompositeKey, StoredValue)> = self
+            .map
+            .borrow()
+            .range((start, Bound::Unbounded))
+            .take(batch_size)
+            .collect();
+        progress.completed = entries.len() < batch_size;
+
+        for (key, value) in entries {
+            match self.migrate_entry(&key, value) {
+                Ok(true) => progress.migrated += 1,
+                Ok(false) => {}
+                Err(error) => {
+                    report_error(&error);
+                    progress.failed += 1;
+                }
+            }
+            progress.cursor = Some(key);
+        }
+    }
+
+    // Helper method rewriting a document at the current schema version, if it is older
+    //
+    // Versions and write times are kept, a migration is not a write of the document.
+    fn migrate_entry(&self, key: &CompositeKey, value: StoredValue) -> Result<bool, DatabaseError> {
+        let bytes = self.assemble(key, value)?;
+        let header = Document::<Reserved>::from_bytes(&bytes)
+            .map_err(|error| corrupt_document(key, error))?;
+        if header.schema_version >= self.schema_version {
+            return Ok(false);
+        }
+
+        let mut document = self.decode(key, &bytes)?;
+        document.schema_version = self.schema_version;
+        let value = self.encode(&document)?;
+        self.replace(key, Some(&document), Some((&document, value)));
+        Ok(true)
+    }
+
+    // Helper method decoding a primary entry, skipping it if corrupt
+    fn load_or_report(&self, key: &CompositeKey, value: StoredValue) -> Option<Document<T>> {
+        self.load(key, value)
+            .map_err(|error| report_error(&error))
+            .ok()
+    }
+
+    // Helper method streaming the documents of a partition, or of the whole database
+    pub(crate) fn with_documents<R>(
+        &self,
+        partition_key: Option<&str>,
+        f: impl FnOnce(&mut dyn Iterator<Item = Document<T>>) -> R,
+    ) -> R {
+        let map = self.map.borrow();
+        match partition_key {
+            Some(partition_key) => f(&mut map
+                .range(Self::partition_range(partition_key))
+                .filter_map(|(key, value)| self.load_or_report(&key, value))),
+            None => f(&mut map
+                .iter()
+                .filter_map(|(key, value)| self.load_or_report(&key, value))),
+        }
+    }
+
+    // Helper method checking that no other document holds a unique key of the data
+    fn check_unique_indexes(&self, key: &CompositeKey, data: &T) -> Result<(), DatabaseError> {
+        for (name, index) in self.indexes.iter().filter(|(_, index)| index.unique) {
+            let index_map = index.map.borrow();
+            for index_key in (index.get_keys)(data) {
+                let taken = index_entry(&index_map, &index_key).is_some_and(|keys| {
+                    // Keys of quarantined documents no longer hold the value
+                    keys.0
+                        .iter()
+                        .any(|k| k != key && self.map.borrow().contains_key(k))
+                });
+                if taken {
+                    return Err(DatabaseError::UniqueConstraintViolation {
+                        index: name.clone(),
+                        key: index_key,
+                    });
+                }
+            }
+        }
+        Ok(())
+    }
+
+    // Helper method adding a primary key to the index entries of its document
+    fn add_to_indexes(&self, key: &CompositeKey, data: &T) {
+        if let (Some(secondary_index), Some(get_secondary_key)) =
+            (&self.secondary_index, &self.get_secondary_key)
+        {
+            if let Some(secondary_key) = get_secondary_key(data) {
+                add_to_index(&mut secondary_index.borrow_mut(), secondary_key, key);
+            }
+        }
+        for index in self.indexes.values() {
+            let mut index_map = index.map.borrow_mut();
+            for index_key in (index.get_keys)(data) {
+                add_to_index(&mut index_map, index_key, key);
+            }
+        }
+    }
+
+    // Helper method removing a primary key from the index entries of its document
+    fn remove_from_indexes(&self, key: &CompositeKey, data: &T) {
+        if let (Some(secondary_index), Some(get_secondary_key)) =
+            (&self.secondary_index, &self.get_secondary_key)
+        {
+            if let Some(secondary_key) = get_secondary_key(data) {
+                remove_from_index(&mut secondary_index.borrow_mut(), &secondary_key, key);
+            }
+        }
+        for index in self.indexes.values() {
+            let mut index_map = index.map.borrow_mut();
+            for index_key in (index.get_keys)(data) {
+                remove_from_index(&mut index_map, &index_key, key);
+            }
+        }
+    }
+
+    // Helper method for the range of keys belonging to a partition
+    fn partition_range(partition_key: &str) -> std::ops::RangeInclusive<CompositeKey> {
+        let range_start = CompositeKey {
+            partition_key: partition_key.to_string(),
+            sort_key: None,
+        };
+        let range_end = CompositeKey {
+            partition_key: partition_key.to_string(),
+            sort_key: Some(String::from("\u{10FFFF}")), // Maximum Unicode value as range end
+        };
+        range_start..=range_end
+    }
+
+    // Helper method for the range of keys of a partition matching a sort key condition
+    fn sort_key_bounds(
+        partition_key: &str,
+        condition: Option<&SortKeyCondition>,
+    ) -> (Bound<CompositeKey>, Bound<CompositeKey>) {
+        let key = |sort_key: Option<String>| CompositeKey {
+            partition_key: partition_key.to_string(),
+            sort_key,
+        };
+        // Maximum Unicode value, sorting after any sort key of the partition
+        let max = |prefix: &str| Some(format!("{}\u{10FFFF}", prefix));
+
+        match condition {
+            None => (Bound::Included(key(None)), Bound::Included(key(max("")))),
+            Some(SortKeyCondition::BeginsWith(prefix)) => (
+                Bound::Included(key(Some(prefix.clone()))),
+                Bound::Included(key(max(prefix))),
+            ),
+            Some(SortKeyCondition::Between(low, high)) => (
+                Bound::Included(key(Some(low.clone()))),
+                Bound::Included(key(Some(high.clone()))),
+            ),
+            Some(SortKeyCondition::GreaterThan(low)) => (
+                Bound::Excluded(key(Some(low.clone()))),
+                Bound::Included(key(max(""))),
+            ),
+            Some(SortKeyCondition::LessThan(high)) => (
+                Bound::Excluded(key(None)),
+                Bound::Excluded(key(Some(high.clone()))),
+            ),
+        }
+    }
+
     // Helper method for querying by partition key
     fn query_by_partition_key(
         &self,
@@ -183,20 +948,10 @@ where
         // Get all entries from the primary map
         let map = self.map.borrow();
 
-        // Create a range for the given partition key to find all matching entries
-        let range_start = CompositeKey {
-            partition_key: partition_key.to_string(),
-            sort_key: None,
-        };
-        let range_end = CompositeKey {
-            partition_key: partition_key.to_string(),
-            sort_key: Some(String::from("\u{10FFFF}")), // Maximum Unicode value as range end
-        };
-
-        // Collect matching documents within the range
+        // Collect matching documents within the range of the partition key
         let matching_documents: Vec<Document<T>> = map
-            .range(range_start..=range_end)
-            .map(|(_, doc)| doc.clone())
+            .range(Self::partition_range(partition_key))
+            .filter_map(|(key, value)| self.load_or_report(&key, value))
             .collect();
 
         // Check if any documents were found
@@ -207,33 +962,7 @@ where
             ));
         }
 
-        // Apply pagination
-        let start_index = (page_number - 1) * page_size;
-
-        // Check if the requested page exists
-        if start_index >= matching_documents.len() {
-            return Err(format!(
-                "Page {} does not exist. Total documents: {}, Page size: {}",
-                page_number,
-                matching_documents.len(),
-                page_size
-            ));
-        }
-
-        // Calculate total pages
-        let total_pages = (matching_documents.len() + page_size - 1) / page_size;
-
-        // Return the paginated subset
-        Ok(QueryResponse {
-            page_number,
-            page_size,
-            total_pages,
-            results: matching_documents
-                .into_iter()
-                .skip(start_index)
-                .take(page_size)
-                .collect(),
-        })
+        paginate(matching_documents, page_size, page_number)
     }
 
     // Helper method for querying by secondary key
@@ -250,45 +979,17 @@ where
         };
 
         // Retrieve keys matching the secondary index
-        let keys = secondary_index
-            .borrow()
-            .get(&secondary_key)
+        let keys = index_entry(&secondary_index.borrow(), &secondary_key)
             .ok_or("No entries found for the given secondary key.".to_string())?;
 
         // Get all matching documents
         let matching_documents: Vec<Document<T>> = keys
             .0
             .iter()
-            .filter_map(|key| self.map.borrow().get(key))
+            .filter_map(|key| self.read_or_report(key))
             .collect();
 
-        // Calculate pagination indices
-        let start_index = (page_number - 1) * page_size;
-
-        // Check if the requested page exists
-        if start_index >= matching_documents.len() {
-            return Err(format!(
-                "Page {} does not exist. Total documents: {}, Page size: {}",
-                page_number,
-                matching_documents.len(),
-                page_size
-            ));
-        }
-
-        // Calculate total pages
-        let total_pages = (matching_documents.len() + page_size - 1) / page_size;
-
-        // Return the paginated subset
-        Ok(QueryResponse {
-            page_number,
-            page_size,
-            total_pages,
-            results: matching_documents
-                .into_iter()
-                .skip(start_index)
-                .take(page_size)
-                .collect(),
-        })
+        paginate(matching_documents, page_size, page_number)
     }
 
     // Helper method for querying by both partition key and secondary key
@@ -305,22 +1006,19 @@ where
             None => return Err("Secondary index not configured.".to_string()),
         };
 
-        let keys = secondary_index
-            .borrow()
-            .get(&secondary_key)
-            .ok_or_else(|| {
-                format!(
-                    "No entries found for partition key '{}' and secondary key.",
-                    partition_key
-                )
-            })?;
+        let keys = index_entry(&secondary_index.borrow(), &secondary_key).ok_or_else(|| {
+            format!(
+                "No entries found for partition key '{}' and secondary key.",
+                partition_key
+            )
+        })?;
 
         // Get all matching documents filtered by partition key
         let matching_documents: Vec<Document<T>> = keys
             .0
             .iter()
             .filter(|key| key.partition_key == partition_key)
-            .filter_map(|key| self.map.borrow().get(key))
+            .filter_map(|key| self.read_or_report(key))
             .collect();
 
         if matching_documents.is_empty() {
@@ -330,34 +1028,146 @@ where
             ));
         }
 
-        // Calculate pagination indices
-        let start_index = (page_number - 1) * page_size;
+        paginate(matching_documents, page_size, page_number)
+    }
+}
 
-        // Check if the requested page exists
-        if start_index >= matching_documents.len() {
-            return Err(format!(
-                "Page {} does not exist. Total documents: {}, Page size: {}",
-                page_number,
-                matching_documents.len(),
-                page_size
-            ));
+// Error for a stored document under the key that cannot be decoded
+fn corrupt_document(key: &CompositeKey, error: String) -> DatabaseError {
+    DatabaseError::CorruptDocument {
+        partition_key: key.partition_key.clone(),
+        sort_key: key.sort_key.clone(),
+        error,
+    }
+}
+
+// Check a write condition against the document currently stored under the key
+fn check_condition<T>(
+    key: &CompositeKey,
+    existing_document: Option<&Document<T>>,
+    condition: Option<&WriteCondition>,
+) -> Result<(), DatabaseError> {
+    let holds = match condition {
+        None => true,
+        Some(WriteCondition::NotExists) => existing_document.is_none(),
+        Some(WriteCondition::VersionEquals(version)) => {
+            existing_document.is_some_and(|document| document.version == *version)
         }
+    };
+    if holds {
+        Ok(())
+    } else {
+        Err(DatabaseError::ConditionFailed {
+            partition_key: key.partition_key.clone(),
+            sort_key: key.sort_key.clone(),
+        })
+    }
+}
 
-        // Calculate total pages
-        let total_pages = (matching_documents.len() + page_size - 1) / page_size;
+// Read the primary keys under an index key, reporting a corrupt entry and skipping it
+fn index_entry<K>(
+    index_map: &StableBTreeMap<K, StoredValue, Memory>,
+    index_key: &K,
+) -> Option<CompositeKeys>
+where
+    K: Storable + Ord + Clone,
+{
+    let value = index_map.get(index_key)?;
+    CompositeKeys::from_value(&value)
+        .map_err(|error| report_error(&format!("Corrupt index entry: {}", error)))
+        .ok()
+}
 
-        // Return the paginated subset
-        Ok(QueryResponse {
-            page_number,
-            page_size,
-            total_pages,
-            results: matching_documents
-                .into_iter()
-                .skip(start_index)
-                .take(page_size)
-                .collect(),
+// List the entries of an index that cannot be decoded
+pub(crate) fn corrupt_index_entries<K>(
+    index_map: &StableBTreeMap<K, StoredValue, Memory>,
+    index: Option<&str>,
+) -> Vec<CorruptIndexEntry>
+where
+    K: Storable + Ord + Clone,
+{
+    index_map
+        .iter()
+        .filter_map(|(index_key, value)| {
+            let error = CompositeKeys::from_value(&value).err()?;
+            Some(CorruptIndexEntry {
+                index: index.map(str::to_string),
+                key: index_key.to_bytes().into_owned(),
+                error,
+            })
         })
+        .collect()
+}
+
+// Add a primary key to the entry of an index key, once
+//
+// A corrupt entry is started over with the key, as its other keys can no longer be read.
+fn add_to_index<K>(
+    index_map: &mut StableBTreeMap<K, StoredValue, Memory>,
+    index_key: K,
+    key: &CompositeKey,
+) where
+    K: Storable + Ord + Clone,
+{
+    let mut composite_keys = index_entry(index_map, &index_key).unwrap_or_default();
+    if !composite_keys.0.contains(key) {
+        composite_keys.0.push(key.clone());
+        index_map.insert(index_key, composite_keys.to_value());
+    }
+}
+
+// Remove a primary key from the entry of an index key, dropping the entry once it is empty
+//
+// A corrupt entry is dropped, as none of its keys can be read anymore.
+fn remove_from_index<K>(
+    index_map: &mut StableBTreeMap<K, StoredValue, Memory>,
+    index_key: &K,
+    key: &CompositeKey,
+) where
+    K: Storable + Ord + Clone,
+{
+    if !index_map.contains_key(index_key) {
+        return;
+    }
+    let mut composite_keys = index_entry(index_map, index_key).unwrap_or_default();
+    composite_keys.0.retain(|k| k != key);
+    if composite_keys.0.is_empty() {
+        index_map.remove(index_key);
+    } else {
+        index_map.insert(index_key.clone(), composite_keys.to_value());
+    }
+}
+
+// Slice a page out of the matching documents, pages past the end are errors
+fn paginate<T>(
+    matching_documents: Vec<Document<T>>,
+    page_size: usize,
+    page_number: usize,
+) -> Result<QueryResponse<T>, String> {
+    // Calculate pagination indices
+    let start_index = (page_number - 1) * page_size;
+
+    // Check if the requested page exists
+    if start_index >= matching_documents.len() {
+        return Err(format!(
+            "Page {} does not exist. Total documents: {}, Page size: {}",
+            page_number,
+            matching_documents.len(),
+            page_size
+        ));
     }
+
+    // Return the paginated subset
+    Ok(QueryResponse {
+        page_number,
+        page_size,
+        total_pages: matching_documents.len().div_ceil(page_size),
+        results: matching_documents
+            .into_iter()
+            .skip(start_index)
+            .take(page_size)
+            .collect(),
+    })
 }
 
 #[cfg(test)]
@@ -482,4 +1292,600 @@ mod tests {
         assert_eq!(results.len(), 1);
         assert_eq!(results[0].data.id, "1");
     }
+
+    #[test]
+    fn test_scan_all_documents() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(2))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None);
+
+        for id in 1..=5 {
+            let account = TestAccountStruct {
+                id: id.to_string(),
+                owner: Principal::anonymous(),
+                balance: id * 100,
+                status: AccountStatus::Active,
+            };
+            db.insert(format!("user_{}", id), None, account).unwrap();
+        }
+
+        let first_page = db.scan(2, 1).unwrap();
+        assert_eq!(first_page.total_pages, 3);
+        assert_eq!(first_page.results.len(), 2);
+        assert_eq!(first_page.results[0].partition_key, "user_1");
+
+        let last_page = db.scan(2, 3).unwrap();
+        assert_eq!(last_page.results.len(), 1);
+        assert_eq!(last_page.results[0].partition_key, "user_5");
+
+        // Pages past the end are empty rather than an error
+        assert!(db.scan(2, 4).unwrap().results.is_empty());
+        assert!(db.scan(0, 1).is_err());
+    }
+
+    #[test]
+    fn test_delete_cleans_secondary_index() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(3))),
+        ));
+        let secondary_index = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(4))),
+        ));
+        let db: Database<TestAccountStruct, AccountStatus> = Database::new(
+            map,
+            Some(secondary_index),
+            Some(Box::new(|account: &TestAccountStruct| {
+                Some(account.status.clone())
+            })),
+        );
+
+        for (partition, id, status) in [
+            ("user_1", "1", AccountStatus::Active),
+            ("user_1", "2", AccountStatus::Suspended),
+            ("user_2", "3", AccountStatus::Active),
+        ] {
+            let account = TestAccountStruct {
+                id: id.to_string(),
+                owner: Principal::anonymous(),
+                balance: 100,
+                status,
+            };
+            db.insert(partition.to_string(), Some(id.to_string()), account)
+                .unwrap();
+        }
+
+        let deleted = db.delete("user_2", Some("3".to_string())).unwrap();
+        assert_eq!(deleted.data.id, "3");
+        assert!(db.get("user_2", Some("3".to_string())).is_err());
+        assert!(db.delete("user_2", Some("3".to_string())).is_err());
+        let active = db.query(None, Some(AccountStatus::Active), 10, 1).unwrap();
+        assert_eq!(active.results.len(), 1);
+        assert_eq!(active.results[0].data.id, "1");
+
+        // Emptied secondary index entries are dropped
+        assert_eq!(db.delete_partition("user_1").unwrap(), 2);
+        assert_eq!(db.delete_partition("user_1").unwrap(), 0);
+        assert!(db.query(None, Some(AccountStatus::Active), 10, 1).is_err());
+        assert!(db.secondary_index.as_ref().unwrap().borrow().is_empty());
+        assert!(db.scan(10, 1).unwrap().results.is_empty());
+    }
+
+    #[test]
+    fn test_query_named_indexes() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(5))),
+        ));
+        let status_index = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(6))),
+        ));
+        let balance_index = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(7))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None)
+            .with_index(
+                "status",
+                status_index,
+                Box::new(|account: &TestAccountStruct| vec![format!("{:?}", account.status)]),
+            )
+            // Accounts above a threshold are indexed under every threshold they pass
+            .with_index(
+                "balance",
+                balance_index,
+                Box::new(|account: &TestAccountStruct| {
+                    [100, 1000]
+                        .iter()
+                        .filter(|threshold| account.balance >= **threshold)
+                        .map(|threshold| threshold.to_string())
+                        .collect()
+                }),
+            );
+
+        for (id, balance, status) in [
+            ("1", 50, AccountStatus::Active),
+            ("2", 500, AccountStatus::Active),
+            ("3", 5000, AccountStatus::Suspended),
+        ] {
+            let account = TestAccountStruct {
+                id: id.to_string(),
+                owner: Principal::anonymous(),
+                balance,
+                status,
+            };
+            db.insert(id.to_string(), None, account).unwrap();
+        }
+
+        let ids = |name: &str, key: &str| -> Vec<String> {
+            db.query_index(name, key, 10, 1)
+                .map(|response| {
+                    response
+                        .results
+                        .into_iter()
+                        .map(|doc| doc.data.id)
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        assert_eq!(ids("status", "Active"), vec!["1", "2"]);
+        assert_eq!(ids("balance", "100"), vec!["2", "3"]);
+        assert_eq!(ids("balance", "1000"), vec!["3"]);
+
+        // Updates and deletes move the document between index keys
+        let mut account = db.get("2", None).unwrap().data;
+        account.status = AccountStatus::Inactive;
+        account.balance = 10;
+        db.insert("2".to_string(), None, account).unwrap();
+        db.delete("3", None).unwrap();
+        assert_eq!(ids("status", "Active"), vec!["1"]);
+        assert_eq!(ids("status", "Inactive"), vec!["2"]);
+        assert!(ids("balance", "100").is_empty());
+
+        let page = db.query_index("status", "Active", 1, 1).unwrap();
+        assert_eq!(page.total_pages, 1);
+        assert!(db.query_index("status", "Active", 1, 2).is_err());
+        assert!(db.query_index("owner", "anyone", 1, 1).is_err());
+    }
+
+    #[test]
+    fn test_unique_index_rejects_conflicts() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(8))),
+        ));
+        let owner_index = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(9))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None).with_unique_index(
+            "owner",
+            owner_index,
+            Box::new(|account: &TestAccountStruct| vec![account.owner.to_text()]),
+        );
+
+        let alice = Principal::from_slice(&[1]);
+        let bob = Principal::from_slice(&[2]);
+        let account = |id: &str, owner: Principal, balance: u64| TestAccountStruct {
+            id: id.to_string(),
+            owner,
+            balance,
+            status: AccountStatus::Active,
+        };
+
+        db.insert("1".to_string(), None, account("1", alice, 10))
+            .unwrap();
+        db.insert("2".to_string(), None, account("2", bob, 20))
+            .unwrap();
+
+        // Another document cannot take a key that is already held
+        let result = db.insert("3".to_string(), None, account("3", alice, 30));
+        assert_eq!(
+            result.unwrap_err(),
+            DatabaseError::UniqueConstraintViolation {
+                index: "owner".to_string(),
+                key: alice.to_text(),
+            }
+        );
+        assert!(db.get("3", None).is_err());
+
+        // Neither can an update of an existing document, which is left unchanged
+        assert!(db
+            .insert("2".to_string(), None, account("2", alice, 25))
+            .is_err());
+        assert_eq!(db.get("2", None).unwrap().data.balance, 20);
+        assert_eq!(
+            db.get_by_unique("owner", &bob.to_text()).unwrap().data.id,
+            "2"
+        );
+
+        // An update keeping its own key is allowed
+        db.insert("1".to_string(), None, account("1", alice, 15))
+            .unwrap();
+        let found = db.get_by_unique("owner", &alice.to_text()).unwrap();
+        assert_eq!(found.data.balance, 15);
+
+        // Keys are released by deletes and updates
+        db.delete("1", None).unwrap();
+        assert!(db.get_by_unique("owner", &alice.to_text()).is_err());
+        db.insert("3".to_string(), None, account("3", alice, 30))
+            .unwrap();
+        assert_eq!(
+            db.get_by_unique("owner", &alice.to_text()).unwrap().data.id,
+            "3"
+        );
+
+        assert!(db.get_by_unique("status", "Active").is_err());
+    }
+
+    #[test]
+    fn test_query_partition_with_cursor_and_conditions() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(10))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None);
+
+        for (partition, sort_key) in [
+            ("user", "2024-01"),
+            ("user", "2024-02"),
+            ("user", "2024-03"),
+            ("user", "2025-01"),
+            ("user", "2025-02"),
+            ("other", "2024-01"),
+        ] {
+            let account = TestAccountStruct {
+                id: sort_key.to_string(),
+                owner: Principal::anonymous(),
+                balance: 0,
+                status: AccountStatus::Active,
+            };
+            db.insert(partition.to_string(), Some(sort_key.to_string()), account)
+                .unwrap();
+        }
+
+        // Follow cursors until the partition is exhausted
+        let collect = |condition: Option<SortKeyCondition>, order: SortOrder| -> Vec<String> {
+            let mut ids = Vec::new();
+            let mut cursor = None;
+            loop {
+                let page = db
+                    .query_partition("user", condition.clone(), order, 2, cursor)
+                    .unwrap();
+                assert!(page.results.len() <= 2);
+                ids.extend(page.results.into_iter().map(|doc| doc.data.id));
+                match page.next_cursor {
+                    Some(next) => cursor = Some(next),
+                    None => return ids,
+                }
+            }
+        };
+
+        assert_eq!(
+            collect(None, SortOrder::Ascending),
+            vec!["2024-01", "2024-02", "2024-03", "2025-01", "2025-02"]
+        );
+        assert_eq!(
+            collect(None, SortOrder::Descending),
+            vec!["2025-02", "2025-01", "2024-03", "2024-02", "2024-01"]
+        );
+        assert_eq!(
+            collect(
+                Some(SortKeyCondition::BeginsWith("2024".to_string())),
+                SortOrder::Descending
+            ),
+            vec!["2024-03", "2024-02", "2024-01"]
+        );
+        assert_eq!(
+            collect(
+                Some(SortKeyCondition::Between(
+                    "2024-02".to_string(),
+                    "2025-01".to_string()
+                )),
+                SortOrder::Ascending
+            ),
+            vec!["2024-02", "2024-03", "2025-01"]
+        );
+        assert_eq!(
+            collect(
+                Some(SortKeyCondition::GreaterThan("2024-03".to_string())),
+                SortOrder::Ascending
+            ),
+            vec!["2025-01", "2025-02"]
+        );
+        assert_eq!(
+            collect(
+                Some(SortKeyCondition::LessThan("2024-03".to_string())),
+                SortOrder::Descending
+            ),
+            vec!["2024-02", "2024-01"]
+        );
+
+        // A page that ends exactly at the last document has no cursor
+        let page = db
+            .query_partition("user", None, SortOrder::Ascending, 5, None)
+            .unwrap();
+        assert_eq!(page.results.len(), 5);
+        assert!(page.next_cursor.is_none());
+
+        // Cursors are bound to their partition
+        let page = db
+            .query_partition("user", None, SortOrder::Ascending, 1, None)
+            .unwrap();
+        let result = db.query_partition("other", None, SortOrder::Ascending, 1, page.next_cursor);
+        assert!(result.is_err());
+
+        assert!(db
+            .query_partition("user", None, SortOrder::Ascending, 0, None)
+            .is_err());
+        assert!(db
+            .query_partition(
+                "user",
+                Some(SortKeyCondition::Between("b".to_string(), "a".to_string())),
+                SortOrder::Ascending,
+                1,
+                None
+            )
+            .is_err());
+        assert!(db
+            .query_partition("missing", None, SortOrder::Ascending, 1, None)
+            .unwrap()
+            .results
+            .is_empty());
+    }
+
+    #[test]
+    fn test_versions_and_legacy_documents() {
+        let db = create_test_db().with_clock(|| 42);
+        let account = TestAccountStruct {
+            id: "versioned".to_string(),
+            owner: Principal::anonymous(),
+            balance: 1,
+            status: AccountStatus::Active,
+        };
+
+        // Every write bumps the version and stamps the time
+        let first = db
+            .insert("versioned".to_string(), None, account.clone())
+            .unwrap();
+        let second = db
+            .insert("versioned".to_string(), None, account.clone())
+            .unwrap();
+        assert_eq!((first.version, second.version), (1, 2));
+        assert_eq!(second.updated_at, 42);
+
+        // Documents stored before versioning still decode
+        #[derive(CandidType, Serialize)]
+        struct LegacyDocument {
+            partition_key: String,
+            sort_key: Option<String>,
+            data: TestAccountStruct,
+        }
+        let bytes = Encode!(&LegacyDocument {
+            partition_key: "legacy".to_string(),
+            sort_key: None,
+            data: account.clone(),
+        })
+        .unwrap();
+        let document = Document::<TestAccountStruct>::from_bytes(&bytes).unwrap();
+        assert_eq!((document.version, document.updated_at), (0, 0));
+        assert_eq!(document.data, account);
+
+        // So do documents stored with a version but no update time
+        #[derive(CandidType, Serialize)]
+        struct VersionedDocument {
+            partition_key: String,
+            sort_key: Option<String>,
+            version: u64,
+            data: TestAccountStruct,
+        }
+        let bytes = Encode!(&VersionedDocument {
+            partition_key: "versioned".to_string(),
+            sort_key: None,
+            version: 7,
+            data: account.clone(),
+        })
+        .unwrap();
+        let document = Document::<TestAccountStruct>::from_bytes(&bytes).unwrap();
+        assert_eq!((document.version, document.updated_at), (7, 0));
+    }
+
+    #[test]
+    fn test_compare_and_put_detects_lost_updates() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(11))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None);
+        let account = |balance: u64| TestAccountStruct {
+            id: "1".to_string(),
+            owner: Principal::anonymous(),
+            balance,
+            status: AccountStatus::Active,
+        };
+
+        // Creation expects no document
+        let created = db
+            .compare_and_put("1".to_string(), None, None, account(10))
+            .unwrap();
+        assert_eq!(created.version, 1);
+        assert!(db
+            .compare_and_put("1".to_string(), None, None, account(0))
+            .is_err());
+
+        // Two writers read version 1, only the first one wins
+        db.compare_and_put("1".to_string(), None, Some(1), account(20))
+            .unwrap();
+        let lost = db.compare_and_put("1".to_string(), None, Some(1), account(30));
+        assert_eq!(
+            lost.unwrap_err(),
+            DatabaseError::ConditionFailed {
+                partition_key: "1".to_string(),
+                sort_key: None,
+            }
+        );
+        let stored = db.get("1", None).unwrap();
+        assert_eq!((stored.version, stored.data.balance), (2, 20));
+    }
+
+    #[test]
+    fn test_size_bound_and_chunked_documents() {
+        let account = |id: String| TestAccountStruct {
+            id,
+            owner: Principal::anonymous(),
+            balance: 0,
+            status: AccountStatus::Active,
+        };
+        let large_id = "x".repeat(1000);
+
+        // Without chunks, an oversized document is rejected and nothing is stored
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(12))),
+        ));
+        let bounded: Database<TestAccountStruct> =
+            Database::new(map, None, None).with_max_value_size(200);
+        let result = bounded.insert("1".to_string(), None, account(large_id.clone()));
+        assert!(matches!(
+            result,
+            Err(DatabaseError::ValueTooLarge { max_size: 200, .. })
+        ));
+        assert!(bounded.get("1", None).is_err());
+
+        // With chunks, it is split across continuation entries and read back whole
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(13))),
+        ));
+        let chunks = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(14))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None)
+            .with_max_value_size(200)
+            .with_chunks(chunks);
+        let chunk_count = || db.chunks.as_ref().unwrap().borrow().len();
+
+        db.insert("1".to_string(), None, account(large_id.clone()))
+            .unwrap();
+        assert!(chunk_count() >= 3);
+        assert_eq!(db.get("1", None).unwrap().data.id, large_id);
+        assert_eq!(db.scan(10, 1).unwrap().results[0].data.id, large_id);
+
+        // Shrinking or deleting the document drops its chunks
+        db.insert("1".to_string(), None, account("small".to_string()))
+            .unwrap();
+        assert_eq!(chunk_count(), 0);
+        assert_eq!(db.get("1", None).unwrap().data.id, "small");
+
+        db.insert("1".to_string(), None, account(large_id.clone()))
+            .unwrap();
+        assert_eq!(db.delete("1", None).unwrap().data.id, large_id);
+        assert_eq!(chunk_count(), 0);
+
+        // Continuation entries no document refers to are listed by an integrity scan
+        let orphan = ChunkKey {
+            key: CompositeKey {
+                partition_key: "2".to_string(),
+                sort_key: None,
+            },
+            index: 0,
+        };
+        db.chunks
+            .as_ref()
+            .unwrap()
+            .borrow_mut()
+            .insert(orphan.clone(), StoredValue(b"chunk".to_vec()));
+        assert_eq!(db.verify_integrity().orphaned_chunks, vec![orphan]);
+    }
+
+    #[test]
+    fn test_corrupt_documents_are_skipped_and_quarantined() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(15))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None);
+        for id in ["1", "2", "3"] {
+            let account = TestAccountStruct {
+                id: id.to_string(),
+                owner: Principal::anonymous(),
+                balance: 0,
+                status: AccountStatus::Active,
+            };
+            db.insert("accounts".to_string(), Some(id.to_string()), account)
+                .unwrap();
+        }
+        let key = CompositeKey {
+            partition_key: "accounts".to_string(),
+            sort_key: Some("2".to_string()),
+        };
+        db.map
+            .borrow_mut()
+            .insert(key.clone(), StoredValue(b"garbage".to_vec()));
+
+        // Reads of the corrupt document fail, scans skip it instead of trapping
+        let error = db.get("accounts", Some("2".to_string())).unwrap_err();
+        assert!(error.contains("is corrupt"));
+        assert_eq!(db.scan(10, 1).unwrap().results.len(), 2);
+        let page = db
+            .query_partition("accounts", None, SortOrder::Ascending, 10, None)
+            .unwrap();
+        assert_eq!(page.results.len(), 2);
+        let first = db.scan_from(1, None).unwrap();
+        let rest = db.scan_from(1, first.next_cursor).unwrap();
+        assert_eq!(rest.results[0].data.id, "3");
+        assert!(rest.next_cursor.is_none());
+        assert!(db.delete("accounts", Some("2".to_string())).is_err());
+
+        let report = db.verify_integrity();
+        assert_eq!(report.checked, 3);
+        assert_eq!(report.corrupt.len(), 1);
+        assert_eq!(report.corrupt[0].key, key);
+
+        // Quarantining hands back the raw bytes and leaves a clean database
+        assert!(db.quarantine("accounts", Some("1".to_string())).is_err());
+        let bytes = db.quarantine("accounts", Some("2".to_string())).unwrap();
+        assert_eq!(bytes, b"garbage");
+        assert!(db.verify_integrity().corrupt.is_empty());
+        assert!(db.get("accounts", Some("2".to_string())).is_err());
+    }
+
+    #[test]
+    fn test_corrupt_index_entries_are_skipped() {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(16))),
+        ));
+        let owner_index = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(17))),
+        ));
+        let db: Database<TestAccountStruct> = Database::new(map, None, None).with_unique_index(
+            "owner",
+            owner_index,
+            Box::new(|account: &TestAccountStruct| vec![account.owner.to_text()]),
+        );
+        let alice = Principal::from_slice(&[1]);
+        let account = |id: &str, balance: u64| TestAccountStruct {
+            id: id.to_string(),
+            owner: alice,
+            balance,
+            status: AccountStatus::Active,
+        };
+        db.insert("1".to_string(), None, account("1", 10)).unwrap();
+        db.indexes["owner"]
+            .map
+            .borrow_mut()
+            .insert(alice.to_text(), StoredValue(b"garbage".to_vec()));
+
+        // Lookups skip the corrupt entry instead of trapping, an integrity scan lists it
+        assert!(db.get_by_unique("owner", &alice.to_text()).is_err());
+        assert!(db.query_index("owner", &alice.to_text(), 10, 1).is_err());
+        let report = db.verify_integrity();
+        assert!(report.corrupt.is_empty());
+        assert_eq!(report.corrupt_index_entries.len(), 1);
+        assert_eq!(
+            report.corrupt_index_entries[0].index.as_deref(),
+            Some("owner")
+        );
+        assert_eq!(
+            report.corrupt_index_entries[0].key,
+            alice.to_text().into_bytes()
+        );
+
+        // The next write to the index key starts the entry over
+        db.insert("1".to_string(), None, account("1", 20)).unwrap();
+        let found = db.get_by_unique("owner", &alice.to_text()).unwrap();
+        assert_eq!(found.data.balance, 20);
+        assert!(db.verify_integrity().corrupt_index_entries.is_empty());
+    }
 }
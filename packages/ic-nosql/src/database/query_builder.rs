@@ -0,0 +1,263 @@
+use candid::CandidType;
+use ic_stable_structures::Storable;
+use serde::{Deserialize, Serialize};
+
+use super::nosql_db::Database;
+use super::types::{Document, Page, QueryResponse};
+use crate::traits::Query;
+
+/// Predicate a document must satisfy to match a query
+pub type FilterFn<'a, T> = Box<dyn Fn(&T) -> bool + 'a>;
+
+/// Builder for filtered, paginated queries over a database
+///
+/// Filters are evaluated while the documents are scanned, so every page holds up to
+/// `page_size` matches and `total_pages` counts matching documents only.
+pub struct QueryBuilder<'a, T, SecondaryKey = ()>
+where
+    T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    SecondaryKey: Clone + Ord + Storable,
+{
+    database: &'a Database<T, SecondaryKey>,
+    partition_key: Option<String>,
+    filters: Vec<FilterFn<'a, T>>,
+    limit: Option<usize>,
+    page_size: usize,
+    page_number: usize,
+}
+
+impl<'a, T, SecondaryKey> QueryBuilder<'a, T, SecondaryKey>
+where
+    T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    SecondaryKey: Clone + Ord + Storable,
+{
+    /// Create a query over every document of the database, 10 per page
+    pub fn new(database: &'a Database<T, SecondaryKey>) -> Self {
+        QueryBuilder {
+            database,
+            partition_key: None,
+            filters: Vec::new(),
+            limit: None,
+            page_size: 10,
+            page_number: 1,
+        }
+    }
+
+    /// Only match documents satisfying the predicate, on top of the previous filters
+    pub fn filter(mut self, predicate: impl Fn(&T) -> bool + 'a) -> Self {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    /// Stop scanning once this many documents matched
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Count the matching documents without reading any page
+    pub fn count(self) -> Result<usize, String> {
+        let limit = self.limit.unwrap_or(usize::MAX);
+        let filters = &self.filters;
+        Ok(self
+            .database
+            .with_documents(self.partition_key.as_deref(), |documents| {
+                documents
+                    .filter(|document| filters.iter().all(|filter| filter(&document.data)))
+                    .take(limit)
+                    .count()
+            }))
+    }
+
+    /// Execute the query, mapping each document of the page with the projection
+    pub fn project<U>(self, projection: impl Fn(Document<T>) -> U) -> Result<Page<U>, String> {
+        // Validate page params
+        if self.page_size == 0 {
+            return Err("Page size must be greater than 0.".to_string());
+        }
+        if self.page_number == 0 {
+            return Err("Page number must be greater than 0.".to_string());
+        }
+
+        let limit = self.limit.unwrap_or(usize::MAX);
+        let start_index = (self.page_number - 1) * self.page_size;
+        let filters = &self.filters;
+
+        // Walk every match to count them, keeping only the ones on the requested page
+        let (total_matches, results) =
+            self.database
+                .with_documents(self.partition_key.as_deref(), |documents| {
+                    let mut results = Vec::new();
+                    let mut total_matches = 0;
+                    for document in documents
+                        .filter(|document| filters.iter().all(|filter| filter(&document.data)))
+                        .take(limit)
+                    {
+                        if total_matches >= start_index && results.len() < self.page_size {
+                            results.push(projection(document));
+                        }
+                        total_matches += 1;
+                    }
+                    (total_matches, results)
+                });
+
+        Ok(Page {
+            page_number: self.page_number,
+            page_size: self.page_size,
+            total_pages: total_matches.div_ceil(self.page_size),
+            results,
+        })
+    }
+}
+
+impl<T, SecondaryKey> Query<T> for QueryBuilder<'_, T, SecondaryKey>
+where
+    T: CandidType + Serialize + for<'de> Deserialize<'de> + Clone,
+    SecondaryKey: Clone + Ord + Storable,
+{
+    type Error = String;
+
+    fn filter_by_partition_key(mut self, key: &str) -> Self {
+        self.partition_key = Some(key.to_string());
+        self
+    }
+
+    fn page_size(mut self, size: usize) -> Self {
+        self.page_size = size;
+        self
+    }
+
+    fn page_number(mut self, number: usize) -> Self {
+        self.page_number = number;
+        self
+    }
+
+    /// Execute the query, a page past the end yields an empty result
+    fn execute(self) -> Result<QueryResponse<T>, Self::Error> {
+        let page = self.project(|document| document)?;
+        Ok(QueryResponse {
+            page_number: page.page_number,
+            page_size: page.page_size,
+            total_pages: page.total_pages,
+            results: page.results,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ic_stable_structures::memory_manager::{MemoryId, MemoryManager};
+    use ic_stable_structures::{DefaultMemoryImpl, StableBTreeMap};
+    use std::cell::RefCell;
+
+    #[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq)]
+    struct Order {
+        id: u64,
+        customer: String,
+        amount: u64,
+    }
+
+    thread_local! {
+        static MEMORY_MANAGER: RefCell<MemoryManager<DefaultMemoryImpl>> =
+            RefCell::new(MemoryManager::init(DefaultMemoryImpl::default()));
+    }
+
+    // Helper function creating 20 orders, alternating between two customers
+    fn create_test_db() -> Database<Order> {
+        let map = RefCell::new(StableBTreeMap::init(
+            MEMORY_MANAGER.with(|m| m.borrow().get(MemoryId::new(0))),
+        ));
+        let db = Database::new(map, None, None);
+        for id in 1..=20u64 {
+            let customer = if id.is_multiple_of(2) { "alice" } else { "bob" };
+            let order = Order {
+                id,
+                customer: customer.to_string(),
+                amount: id * 10,
+            };
+            db.insert(customer.to_string(), Some(format!("{:03}", id)), order)
+                .unwrap();
+        }
+        db
+    }
+
+    #[test]
+    fn test_filtered_pages_are_full_and_counted() {
+        let db = create_test_db();
+
+        // 7 orders above 130, spread over both partitions
+        let page = QueryBuilder::new(&db)
+            .filter(|order: &Order| order.amount > 130)
+            .page_size(3)
+            .page_number(2)
+            .execute()
+            .unwrap();
+        assert_eq!(page.total_pages, 3);
+        assert_eq!(page.results.len(), 3);
+        assert!(page.results.iter().all(|doc| doc.data.amount > 130));
+
+        let last_page = QueryBuilder::new(&db)
+            .filter(|order: &Order| order.amount > 130)
+            .page_size(3)
+            .page_number(3)
+            .execute()
+            .unwrap();
+        assert_eq!(last_page.results.len(), 1);
+
+        let past_end = QueryBuilder::new(&db)
+            .filter(|order: &Order| order.amount > 130)
+            .page_size(3)
+            .page_number(4)
+            .execute()
+            .unwrap();
+        assert!(past_end.results.is_empty());
+        assert_eq!(past_end.total_pages, 3);
+
+        assert!(QueryBuilder::new(&db).page_size(0).execute().is_err());
+        assert!(QueryBuilder::new(&db).page_number(0).execute().is_err());
+    }
+
+    #[test]
+    fn test_partition_filters_limit_count_and_projection() {
+        let db = create_test_db();
+
+        let count = QueryBuilder::new(&db)
+            .filter_by_partition_key("alice")
+            .filter(|order: &Order| order.amount >= 100)
+            .filter(|order: &Order| order.id.is_multiple_of(4))
+            .count()
+            .unwrap();
+        assert_eq!(count, 3);
+
+        let limited = QueryBuilder::new(&db)
+            .filter_by_partition_key("bob")
+            .limit(4)
+            .page_size(3)
+            .execute()
+            .unwrap();
+        assert_eq!(limited.total_pages, 2);
+        assert_eq!(
+            QueryBuilder::new(&db)
+                .filter_by_partition_key("bob")
+                .limit(4)
+                .count(),
+            Ok(4)
+        );
+
+        let ids = QueryBuilder::new(&db)
+            .filter_by_partition_key("alice")
+            .page_size(4)
+            .project(|document| document.data.id)
+            .unwrap();
+        assert_eq!(ids.results, vec![2, 4, 6, 8]);
+        assert_eq!(ids.total_pages, 3);
+
+        assert_eq!(
+            QueryBuilder::new(&db)
+                .filter_by_partition_key("carol")
+                .count(),
+            Ok(0)
+        );
+    }
+}
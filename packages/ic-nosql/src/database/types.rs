@@ -1,12 +1,23 @@
-use candid::{CandidType, Decode, Encode};
+use candid::CandidType;
 use ic_stable_structures::storable::Bound;
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::fmt;
 
-const MAX_VALUE_SIZE: u32 = 4096;
+use crate::utils::serialization::{
+    decode_storable, deserialize_from_bytes, encode_storable, serialize_to_bytes,
+};
+
+/// Default size bound of a single stored document, in bytes
+pub const DEFAULT_MAX_VALUE_SIZE: u32 = 4096;
+
+// Marks a primary entry whose document is split across continuation entries
+const CHUNK_HEADER_MAGIC: &[u8; 4] = b"CHNK";
 
 /// Composite key for the database, consisting of a partition key and optional sort key
-#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(
+    Clone, Debug, Default, Serialize, Deserialize, CandidType, PartialEq, Eq, PartialOrd, Ord,
+)]
 pub struct CompositeKey {
     pub partition_key: String,
     pub sort_key: Option<String>,
@@ -14,33 +25,36 @@ pub struct CompositeKey {
 
 impl ic_stable_structures::Storable for CompositeKey {
     fn to_bytes(&self) -> Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
+        encode_storable(self)
     }
 
+    // Keys are decoded inside the map, a corrupt key traps rather than resolving elsewhere
     fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), Self).unwrap()
+        decode_storable(bytes.as_ref())
     }
 
     const BOUND: Bound = Bound::Unbounded;
 }
 
 /// Collection of composite keys, used for secondary indexes
-#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq, PartialOrd, Ord)]
+///
+/// Index entries are stored as raw bytes and decoded fallibly, so a corrupt entry is
+/// reported instead of trapping every query of its index.
+#[derive(
+    Clone, Debug, Default, Serialize, Deserialize, CandidType, PartialEq, Eq, PartialOrd, Ord,
+)]
 pub struct CompositeKeys(pub Vec<CompositeKey>);
 
-impl ic_stable_structures::Storable for CompositeKeys {
-    fn to_bytes(&self) -> Cow<[u8]> {
-        // Encode the vector of CompositeKeys as bytes
-        Cow::Owned(Encode!(&self.0).unwrap())
+impl CompositeKeys {
+    // Stored entry of an index key, keys are strings so encoding them cannot fail
+    pub(crate) fn to_value(&self) -> StoredValue {
+        StoredValue(encode_storable(&self.0).into_owned())
     }
 
-    fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        // Decode bytes back into a vector of CompositeKeys
-        CompositeKeys(Decode!(bytes.as_ref(), Vec<CompositeKey>).unwrap())
+    // Decode the stored entry of an index key
+    pub(crate) fn from_value(value: &StoredValue) -> Result<Self, String> {
+        deserialize_from_bytes(&value.0).map(CompositeKeys)
     }
-
-    const BOUND: ic_stable_structures::storable::Bound =
-        ic_stable_structures::storable::Bound::Unbounded;
 }
 
 /// Document structure containing the data and its keys
@@ -48,25 +62,251 @@ impl ic_stable_structures::Storable for CompositeKeys {
 pub struct Document<T> {
     pub partition_key: String,
     pub sort_key: Option<String>,
+    /// Number of times the document was written, starting at 1
+    pub version: u64,
+    /// Time of the last write in nanoseconds
+    pub updated_at: u64,
+    /// Schema version of the model the data was written with
+    pub schema_version: u32,
     pub data: T,
 }
 
-impl<T> ic_stable_structures::Storable for Document<T>
-where
-    T: Serialize + for<'de> Deserialize<'de> + CandidType,
-{
-    fn to_bytes(&self) -> Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
+/// Stored layout of documents, tolerating the fields older documents were written without
+#[derive(Deserialize, CandidType)]
+struct StoredDocument<T> {
+    partition_key: String,
+    sort_key: Option<String>,
+    version: Option<u64>,
+    updated_at: Option<u64>,
+    schema_version: Option<u32>,
+    data: T,
+}
+
+// Documents are not `Storable`: the database stores their raw bytes and decodes them
+// fallibly, so a corrupt document is reported instead of trapping every read
+impl<T> Document<T> {
+    /// Encode the document as stored
+    pub fn to_bytes(&self) -> Result<Vec<u8>, String>
+    where
+        T: CandidType + Serialize,
+    {
+        serialize_to_bytes(self)
+    }
+
+    /// Decode a stored document
+    ///
+    /// Decoding with `candid::Reserved` as data reads the keys and versions of a document
+    /// whatever the layout of its data.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, String>
+    where
+        T: CandidType + for<'de> Deserialize<'de>,
+    {
+        // Documents written before versioning decode as version 0, last written at 0,
+        // and documents written before schema versions as schema version 0
+        let stored: StoredDocument<T> = deserialize_from_bytes(bytes)?;
+        Ok(Document {
+            partition_key: stored.partition_key,
+            sort_key: stored.sort_key,
+            version: stored.version.unwrap_or_default(),
+            updated_at: stored.updated_at.unwrap_or_default(),
+            schema_version: stored.schema_version.unwrap_or_default(),
+            data: stored.data,
+        })
+    }
+}
+
+/// Raw bytes of a primary or continuation entry
+///
+/// A primary entry either holds an encoded document or a chunk header giving the number
+/// of continuation entries the document is split across.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredValue(pub(crate) Vec<u8>);
+
+impl StoredValue {
+    // Header of a document split across `count` continuation entries
+    pub(crate) fn chunk_header(count: u32) -> Self {
+        let mut bytes = CHUNK_HEADER_MAGIC.to_vec();
+        bytes.extend_from_slice(&count.to_be_bytes());
+        StoredValue(bytes)
+    }
+
+    // Number of continuation entries if this is a chunk header
+    pub(crate) fn chunk_count(&self) -> Option<u32> {
+        // Candid values start with "DIDL", so a header is never mistaken for a document
+        let count = self.0.strip_prefix(CHUNK_HEADER_MAGIC.as_slice())?;
+        Some(u32::from_be_bytes(count.try_into().ok()?))
+    }
+
+    pub(crate) fn into_bytes(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+impl ic_stable_structures::Storable for StoredValue {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.0)
+    }
+
+    fn from_bytes(bytes: Cow<[u8]>) -> Self {
+        StoredValue(bytes.into_owned())
+    }
+
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Key of a continuation entry, the chunk at `index` of the document stored under `key`
+#[derive(
+    Clone, Debug, Default, Serialize, Deserialize, CandidType, PartialEq, Eq, PartialOrd, Ord,
+)]
+pub struct ChunkKey {
+    pub key: CompositeKey,
+    pub index: u32,
+}
+
+impl ic_stable_structures::Storable for ChunkKey {
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        encode_storable(self)
     }
 
+    // Keys are decoded inside the map, a corrupt key traps rather than resolving elsewhere
     fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), Self).unwrap()
+        decode_storable(bytes.as_ref())
     }
 
-    const BOUND: Bound = Bound::Bounded {
-        max_size: MAX_VALUE_SIZE,
-        is_fixed_size: false,
-    };
+    const BOUND: Bound = Bound::Unbounded;
+}
+
+/// Condition on the sort keys of a partition query
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub enum SortKeyCondition {
+    /// Sort keys starting with the prefix
+    BeginsWith(String),
+    /// Sort keys between both values, inclusive
+    Between(String, String),
+    /// Sort keys strictly greater than the value
+    GreaterThan(String),
+    /// Sort keys strictly less than the value
+    LessThan(String),
+}
+
+/// Order in which a partition query walks the sort keys
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub enum SortOrder {
+    #[default]
+    Ascending,
+    Descending,
+}
+
+/// Opaque position in a partition query, continuing after the last returned document
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub struct Cursor {
+    last_key: CompositeKey,
+}
+
+impl Cursor {
+    pub(crate) fn new(last_key: CompositeKey) -> Self {
+        Cursor { last_key }
+    }
+
+    /// Cursor continuing after a key, for callers keeping their position as a key
+    pub fn after(partition_key: &str, sort_key: Option<String>) -> Self {
+        Cursor::new(CompositeKey {
+            partition_key: partition_key.to_string(),
+            sort_key,
+        })
+    }
+
+    pub(crate) fn last_key(&self) -> &CompositeKey {
+        &self.last_key
+    }
+}
+
+/// Response structure for cursor-based queries
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType)]
+pub struct CursorResponse<T> {
+    pub results: Vec<Document<T>>,
+    /// Cursor for the next page, `None` once there are no more documents
+    pub next_cursor: Option<Cursor>,
+}
+
+/// Optimistic condition a write only applies under
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub enum WriteCondition {
+    /// No document is stored under the key
+    NotExists,
+    /// The stored document has exactly this version
+    VersionEquals(u64),
+}
+
+/// Errors returned by database writes
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub enum DatabaseError {
+    /// Another document already holds the key in a unique index
+    UniqueConstraintViolation { index: String, key: String },
+    /// The write condition does not hold for the stored document
+    ConditionFailed {
+        partition_key: String,
+        sort_key: Option<String>,
+    },
+    /// No document is stored under the key
+    DocumentNotFound {
+        partition_key: String,
+        sort_key: Option<String>,
+    },
+    /// The encoded document exceeds the size bound of its database and chunking is disabled
+    ValueTooLarge { size: usize, max_size: u32 },
+    /// The document could not be encoded
+    Serialization(String),
+    /// The stored document could not be decoded
+    CorruptDocument {
+        partition_key: String,
+        sort_key: Option<String>,
+        error: String,
+    },
+}
+
+impl fmt::Display for DatabaseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DatabaseError::UniqueConstraintViolation { index, key } => write!(
+                f,
+                "Unique constraint violation: key '{}' already exists in index '{}'.",
+                key, index
+            ),
+            DatabaseError::ConditionFailed {
+                partition_key,
+                sort_key,
+            } => write!(
+                f,
+                "Write condition failed for document '{}' ({:?}).",
+                partition_key, sort_key
+            ),
+            DatabaseError::DocumentNotFound { .. } => write!(f, "Document not found."),
+            DatabaseError::ValueTooLarge { size, max_size } => write!(
+                f,
+                "Document of {} bytes exceeds the maximum size of {} bytes.",
+                size, max_size
+            ),
+            DatabaseError::Serialization(error) => write!(f, "Serialization error: {}", error),
+            DatabaseError::CorruptDocument {
+                partition_key,
+                sort_key,
+                error,
+            } => write!(
+                f,
+                "Document '{}' ({:?}) is corrupt: {}",
+                partition_key, sort_key, error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for DatabaseError {}
+
+impl From<DatabaseError> for String {
+    fn from(error: DatabaseError) -> Self {
+        error.to_string()
+    }
 }
 
 /// Response structure for paginated queries
@@ -77,3 +317,78 @@ pub struct QueryResponse<T> {
     pub total_pages: usize,
     pub results: Vec<Document<T>>,
 }
+
+/// Paginated query results mapped by a projection
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType)]
+pub struct Page<U> {
+    pub page_number: usize,
+    pub page_size: usize,
+    pub total_pages: usize,
+    pub results: Vec<U>,
+}
+
+/// Stored document that could not be decoded, found by an integrity scan
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub struct CorruptEntry {
+    pub key: CompositeKey,
+    pub error: String,
+}
+
+/// Index entry that could not be decoded, found by an integrity scan
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub struct CorruptIndexEntry {
+    /// Name of the index, `None` for the secondary index
+    pub index: Option<String>,
+    /// Stored bytes of the index key
+    pub key: Vec<u8>,
+    pub error: String,
+}
+
+/// Result of an integrity scan over every document of a database
+#[derive(Clone, Debug, Default, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub struct IntegrityReport {
+    /// Number of documents scanned
+    pub checked: u64,
+    pub corrupt: Vec<CorruptEntry>,
+    pub corrupt_index_entries: Vec<CorruptIndexEntry>,
+    /// Continuation entries that belong to no stored document
+    pub orphaned_chunks: Vec<ChunkKey>,
+    /// Error decoding the schema metadata of the model, when checked through the manager
+    pub corrupt_metadata: Option<String>,
+}
+
+/// Schema metadata of a model, kept in the metadata region of the database manager
+#[derive(Clone, Debug, Default, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub struct SchemaMetadata {
+    /// Schema version every stored document of the model has been migrated to
+    pub schema_version: u32,
+    /// Latest migration of the model, kept once completed
+    pub migration: Option<MigrationProgress>,
+}
+
+/// Progress of the migration of a model between two schema versions
+#[derive(Clone, Debug, Serialize, Deserialize, CandidType, PartialEq, Eq)]
+pub struct MigrationProgress {
+    pub from_version: u32,
+    pub to_version: u32,
+    /// Key of the last document processed, the next batch continues after it
+    pub cursor: Option<CompositeKey>,
+    /// Number of documents rewritten at the new schema version
+    pub migrated: u64,
+    /// Number of documents that could not be migrated, left as they are
+    pub failed: u64,
+    pub completed: bool,
+}
+
+impl MigrationProgress {
+    pub(crate) fn new(from_version: u32, to_version: u32) -> Self {
+        MigrationProgress {
+            from_version,
+            to_version,
+            cursor: None,
+            migrated: 0,
+            failed: 0,
+            completed: false,
+        }
+    }
+}
@@ -8,9 +8,17 @@
 //!
 //! - **Multiple Model Support**: Register and manage different data models in a single canister
 //! - **Secondary Indexes**: Optional secondary indexes for efficient querying
+//! - **Named Indexes**: Any number of named indexes per model, each in its own memory
+//! - **Unique Indexes**: Named indexes rejecting writes that would duplicate a key
 //! - **Memory Management**: Automatic memory allocation with conflict prevention
 //! - **Type Safety**: Compile-time type checking for all database operations
 //! - **Pagination**: Built-in pagination support for large result sets
+//! - **Range Scans**: Cursor-based partition queries with sort key conditions in either order
+//! - **Query Builder**: Filters, limits, counts and projections evaluated while scanning
+//! - **Write Batches**: Atomic puts and deletes across models with optimistic conditions
+//! - **Large Documents**: Per-model size bounds, with documents over the bound split into chunks
+//! - **Corruption Tolerance**: Corrupt documents are skipped and reported, never trapping a query
+//! - **Schema Migrations**: Versioned models, migrated on read and in resumable timer batches
 //! - **Macros**: Easy model definition with the `define_model!` macro
 //!
 //! ## Quick Start
@@ -51,9 +59,13 @@ pub mod traits;
 pub mod utils;
 
 // Re-export core types and functionality for easy access
-pub use database::{Database, DatabaseManager};
+pub use database::{
+    schedule_migration, Cursor, CursorResponse, Database, DatabaseError, DatabaseManager,
+    IntegrityReport, MigrationProgress, Page, QueryBuilder, SchemaMetadata, SortKeyCondition,
+    SortOrder, WriteBatch, WriteCondition,
+};
 pub use memory::{MemoryId, MemoryManager};
-pub use traits::{Model, Query, Repository};
+pub use traits::{Index, Migration, Model, Query, Repository};
 //pub use macros::define_model;
 
 // Re-export commonly used external types
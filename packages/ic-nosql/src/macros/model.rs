@@ -2,7 +2,12 @@
 ///
 /// This macro automatically implements the `Model` trait and `Storable` trait for your struct,
 /// making it ready to use with ic-nosql database operations. It requires explicit specification
-/// of the primary key for deterministic behavior.
+/// of the primary key for deterministic behavior, and optionally takes a secondary key and
+/// named indexes.
+///
+/// Databases store models inside documents, decoded fallibly so that a corrupt record is
+/// skipped and reported. A model stored directly through its `Storable` implementation has no
+/// value to fall back to, so decoding corrupt bytes reports the error and traps the message.
 ///
 /// # Examples
 ///
@@ -10,7 +15,6 @@
 /// ```
 /// use ic_nosql::{define_model, CandidType};
 /// use serde::{Deserialize, Serialize};
-/// use candid::{Decode, Encode};
 ///
 /// define_model! {
 ///     #[derive(Debug, Clone, CandidType, Deserialize, Serialize)]
@@ -28,7 +32,6 @@
 /// ```
 /// use ic_nosql::{define_model, CandidType};
 /// use serde::{Deserialize, Serialize};
-/// use candid::{Decode, Encode};
 ///
 /// define_model! {
 ///     #[derive(Debug, Clone, CandidType, Deserialize, Serialize)]
@@ -44,66 +47,71 @@
 /// }
 /// ```
 ///
+/// Model with named indexes, each extracting any number of keys from a document:
+/// ```
+/// use ic_nosql::{define_model, CandidType, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// define_model! {
+///     #[derive(Debug, Clone, CandidType, Deserialize, Serialize)]
+///     pub struct Account {
+///         pub id: String,
+///         pub owner: String,
+///         pub tags: Vec<String>,
+///     }
+///
+///     primary_key: id -> String,
+///     secondary_key: owner -> String,
+///     indexes: {
+///         owner: |account: &Account| vec![account.owner.clone()],
+///         tag: |account: &Account| account.tags.clone(),
+///     },
+/// }
+///
+/// assert_eq!(Account::indexes().len(), 2);
+/// ```
+///
+/// Model with a unique index, rejecting two documents with the same key:
+/// ```
+/// use ic_nosql::{define_model, CandidType, Model};
+/// use serde::{Deserialize, Serialize};
+///
+/// define_model! {
+///     #[derive(Debug, Clone, CandidType, Deserialize, Serialize)]
+///     pub struct User {
+///         pub id: String,
+///         pub username: String,
+///     }
+///
+///     primary_key: id -> String,
+///     unique_indexes: {
+///         username: |user: &User| vec![user.username.clone()],
+///     },
+/// }
+///
+/// assert!(User::indexes()[0].unique);
+/// ```
+///
 /// # Key Requirements
 ///
 /// - **Primary key is required**: You must explicitly specify which field serves as the primary key
 /// - **Secondary key is optional**: You can optionally specify a secondary key for indexed queries
+/// - **Indexes are optional**: Each index, unique or not, must be registered with `DatabaseManager::register_index`
 /// - **Field types**: The specified fields must implement `Clone` and match the declared types
 /// - **Deterministic behavior**: No ambiguity about which field is the primary key
 #[macro_export]
 macro_rules! define_model {
-    // Model with only primary key (no secondary key)
-    (
-        $(#[$attr:meta])*
-        $vis:vis struct $name:ident {
-            $(
-                $(#[$field_attr:meta])*
-                $field_vis:vis $field_name:ident: $field_type:ty
-            ),* $(,)?
-        }
+    // Secondary key type, `()` for models without one
+    (@secondary_type $secondary_type:ty) => { $secondary_type };
+    (@secondary_type) => { () };
 
-        primary_key: $primary_field:ident -> $primary_type:ty,
-    ) => {
-        $(#[$attr])*
-        $vis struct $name {
-            $(
-                $(#[$field_attr])*
-                $field_vis $field_name: $field_type,
-            )*
-        }
-
-        impl $crate::traits::Model for $name {
-            type PrimaryKey = $primary_type;
-            type SecondaryKey = ();
-
-            fn get_primary_key(&self) -> Self::PrimaryKey {
-                self.$primary_field.clone()
-            }
-
-            fn get_secondary_key(&self) -> Option<Self::SecondaryKey> {
-                None
-            }
-
-            fn model_name() -> &'static str {
-                stringify!($name)
-            }
-        }
-
-        impl ic_stable_structures::Storable for $name {
-            fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
-               std::borrow::Cow::Owned(candid::Encode!(self).expect("Failed to encode model for storage"))
-            }
-
-            fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
-               candid::Decode!(bytes.as_ref(), Self).expect("Failed to decode model from storage")
-            }
-
-            const BOUND: ic_stable_structures::storable::Bound =
-                ic_stable_structures::storable::Bound::Unbounded;
-        }
+    // Secondary key of a model instance
+    (@secondary_key $model:ident, $secondary_field:ident) => {
+        Some($model.$secondary_field.clone())
     };
+    (@secondary_key $model:ident) => { None };
 
-    // Model with custom primary key and secondary key
+    // Model with a primary key and optional secondary key, named indexes and unique indexes
     (
         $(#[$attr:meta])*
         $vis:vis struct $name:ident {
@@ -114,7 +122,13 @@ macro_rules! define_model {
         }
 
         primary_key: $primary_field:ident -> $primary_type:ty,
-        secondary_key: $secondary_field:ident -> $secondary_type:ty,
+        $(secondary_key: $secondary_field:ident -> $secondary_type:ty,)?
+        $(indexes: {
+            $($index_name:ident: $index_keys:expr),* $(,)?
+        } $(,)?)?
+        $(unique_indexes: {
+            $($unique_name:ident: $unique_keys:expr),* $(,)?
+        } $(,)?)?
     ) => {
         $(#[$attr])*
         $vis struct $name {
@@ -126,14 +140,21 @@ macro_rules! define_model {
 
         impl $crate::traits::Model for $name {
             type PrimaryKey = $primary_type;
-            type SecondaryKey = $secondary_type;
+            type SecondaryKey = $crate::define_model!(@secondary_type $($secondary_type)?);
 
             fn get_primary_key(&self) -> Self::PrimaryKey {
                 self.$primary_field.clone()
             }
 
             fn get_secondary_key(&self) -> Option<Self::SecondaryKey> {
-                Some(self.$secondary_field.clone())
+                $crate::define_model!(@secondary_key self $(, $secondary_field)?)
+            }
+
+            fn indexes() -> Vec<$crate::traits::Index<Self>> {
+                vec![
+                    $($($crate::traits::Index::new(stringify!($index_name), $index_keys),)*)?
+                    $($($crate::traits::Index::unique(stringify!($unique_name), $unique_keys),)*)?
+                ]
             }
 
             fn model_name() -> &'static str {
@@ -143,11 +164,11 @@ macro_rules! define_model {
 
         impl ic_stable_structures::Storable for $name {
             fn to_bytes(&self) -> std::borrow::Cow<[u8]> {
-                std::borrow::Cow::Owned(candid::Encode!(self).expect("Failed to encode model for storage"))
+                $crate::utils::serialization::encode_storable(self)
             }
 
             fn from_bytes(bytes: std::borrow::Cow<[u8]>) -> Self {
-                candid::Decode!(bytes.as_ref(), Self).expect("Failed to decode model from storage")
+                $crate::utils::serialization::decode_storable(bytes.as_ref())
             }
 
             const BOUND: ic_stable_structures::storable::Bound =
@@ -1,12 +1,14 @@
 //! Traits module providing database abstractions
 //!
 //! This module defines the core traits that provide abstractions for database operations,
-//! model definitions, and repository patterns.
+//! model definitions, schema migrations and repository patterns.
 
 pub use self::database::{Database as DatabaseTrait, Query};
-pub use self::model::Model;
+pub use self::migration::Migration;
+pub use self::model::{Index, IndexKeyFn, Model, SecondaryKeyFn};
 pub use self::repository::Repository;
 
 pub mod database;
+pub mod migration;
 pub mod model;
 pub mod repository;
@@ -0,0 +1,41 @@
+/// Conversion of documents stored with an older schema version of a model
+///
+/// A database at schema version N keeps one migration per older version it still reads,
+/// each turning the stored bytes of a document straight into the current model.
+///
+/// ```
+/// use ic_nosql::traits::Migration;
+/// use ic_nosql::database::Document;
+/// use ic_nosql::{CandidType, Deserialize};
+///
+/// #[derive(CandidType, Deserialize)]
+/// struct UserV0 {
+///     name: String,
+/// }
+///
+/// struct User {
+///     name: String,
+///     email: Option<String>,
+/// }
+///
+/// struct AddEmail;
+///
+/// impl Migration<User> for AddEmail {
+///     fn from_version(&self) -> u32 {
+///         0
+///     }
+///
+///     fn migrate(&self, bytes: &[u8]) -> Result<User, String> {
+///         let user = Document::<UserV0>::from_bytes(bytes)?.data;
+///         Ok(User { name: user.name, email: None })
+///     }
+/// }
+/// ```
+pub trait Migration<T> {
+    /// Schema version of the documents this migration reads
+    #[allow(clippy::wrong_self_convention)] // Names a version, not a conversion
+    fn from_version(&self) -> u32;
+
+    /// Decode a stored document of `from_version` into the current model
+    fn migrate(&self, bytes: &[u8]) -> Result<T, String>;
+}
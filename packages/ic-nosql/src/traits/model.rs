@@ -17,6 +17,46 @@ pub trait Model: CandidType + Serialize + for<'de> Deserialize<'de> + Clone {
         None
     }
 
+    /// Get the named indexes of this model (if any)
+    fn indexes() -> Vec<Index<Self>> {
+        Vec::new()
+    }
+
     /// Get the model name for database registration
     fn model_name() -> &'static str;
 }
+
+/// Function deriving the secondary key of a document, if it has one
+pub type SecondaryKeyFn<T, K> = Box<dyn Fn(&T) -> Option<K>>;
+
+/// Function extracting the keys a document is indexed under
+///
+/// A document can have any number of keys in an index, including none.
+pub type IndexKeyFn<T> = Box<dyn Fn(&T) -> Vec<String>>;
+
+/// A named index of a model and its key extractor
+pub struct Index<T> {
+    pub name: &'static str,
+    pub get_keys: IndexKeyFn<T>,
+    /// Whether each key may belong to at most one document
+    pub unique: bool,
+}
+
+impl<T> Index<T> {
+    /// Create an index from its name and key extractor
+    pub fn new(name: &'static str, get_keys: impl Fn(&T) -> Vec<String> + 'static) -> Self {
+        Index {
+            name,
+            get_keys: Box::new(get_keys),
+            unique: false,
+        }
+    }
+
+    /// Create a unique index, rejecting documents whose keys belong to another document
+    pub fn unique(name: &'static str, get_keys: impl Fn(&T) -> Vec<String> + 'static) -> Self {
+        Index {
+            unique: true,
+            ..Index::new(name, get_keys)
+        }
+    }
+}
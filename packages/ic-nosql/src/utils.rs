@@ -1,8 +1,10 @@
 //! Utilities module providing helper functions
 //!
 //! This module contains utility functions for serialization, deserialization,
-//! and other common operations used throughout the database system.
+//! time keeping and other common operations used throughout the database system.
 
 pub use self::serialization::{deserialize_from_bytes, serialize_to_bytes};
+pub use self::time::Clock;
 
 pub mod serialization;
+pub mod time;
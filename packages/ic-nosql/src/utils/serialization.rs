@@ -1,6 +1,7 @@
 use candid::{CandidType, Decode, Encode};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
+use std::fmt::Display;
 
 /// Serialize data to bytes using Candid encoding
 pub fn serialize_to_bytes<T>(data: &T) -> Result<Vec<u8>, String>
@@ -35,3 +36,65 @@ where
 {
     Decode!(bytes.as_ref(), T).map_err(|e| format!("Deserialization error: {}", e))
 }
+
+/// Encode a value for a `Storable` implementation
+///
+/// Candid only fails to encode values it cannot represent. Writing anything in their place
+/// would lose the value, so the error is reported and the message traps instead.
+pub fn encode_storable<T>(data: &T) -> Cow<'static, [u8]>
+where
+    T: CandidType + Serialize,
+{
+    match serialize_to_bytes(data) {
+        Ok(bytes) => Cow::Owned(bytes),
+        Err(error) => trap_storable(&error),
+    }
+}
+
+/// Decode a value for a `Storable` implementation, reporting corrupt bytes and falling back
+pub fn decode_storable_or<T>(bytes: &[u8], fallback: impl FnOnce() -> T) -> T
+where
+    T: CandidType + for<'de> Deserialize<'de>,
+{
+    deserialize_from_bytes(bytes).unwrap_or_else(|error| {
+        report_error(&error);
+        fallback()
+    })
+}
+
+/// Decode a value for a `Storable` implementation that has no fallback, trapping if corrupt
+///
+/// The error is reported before trapping, so the corrupt bytes can be found from the log.
+pub fn decode_storable<T>(bytes: &[u8]) -> T
+where
+    T: CandidType + for<'de> Deserialize<'de>,
+{
+    deserialize_from_bytes(bytes).unwrap_or_else(|error| trap_storable(&error))
+}
+
+/// Report an error that cannot be recovered from and trap, rolling back the current message
+pub fn trap_storable(error: &impl Display) -> ! {
+    report_error(error);
+    #[cfg(target_arch = "wasm32")]
+    {
+        ic_cdk::trap(&format!("ic-nosql: {}", error))
+    }
+    // Off the canister, panic so that tests can observe the failure instead of the process
+    // aborting
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        panic!("ic-nosql: {}", error)
+    }
+}
+
+/// Report an error that is recovered from, on the canister debug log or stderr elsewhere
+pub fn report_error(error: &impl Display) {
+    #[cfg(target_arch = "wasm32")]
+    {
+        ic_cdk::println!("ic-nosql: {}", error);
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        eprintln!("ic-nosql: {}", error);
+    }
+}
@@ -0,0 +1,17 @@
+/// Source of the current time in nanoseconds, stamped on written documents
+pub type Clock = fn() -> u64;
+
+/// Current time in nanoseconds: the IC time inside a canister, the system time elsewhere
+pub fn now() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        ic_cdk::api::time()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|duration| duration.as_nanos() as u64)
+            .unwrap_or_default()
+    }
+}
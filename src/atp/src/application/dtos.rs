@@ -1,3 +1,20 @@
 pub mod account_messages;
 pub mod account_reply;
+pub mod audit_event_reply;
+pub mod audit_messages;
+pub mod canister_config_messages;
+pub mod canister_config_reply;
 pub mod eip1559;
+pub mod fee_messages;
+pub mod fee_reply;
+pub mod job_messages;
+pub mod job_reply;
+pub mod registry;
+pub mod registry_messages;
+pub mod registry_reply;
+pub mod role_messages;
+pub mod role_reply;
+pub mod signing_proposal_messages;
+pub mod signing_proposal_reply;
+pub mod signing_session_messages;
+pub mod signing_session_reply;
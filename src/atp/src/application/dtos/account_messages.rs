@@ -1,10 +1,15 @@
-use crate::application::dtos::account_reply::AccountReply;
+use crate::application::dtos::account_reply::{
+    AccountReply, ChainCapabilityReply, KeyTypeReply, SweepPlanEntryReply,
+};
 use crate::application::dtos::eip1559::Eip1559TransactionRequestDTO;
+use crate::domain::models::account_metadata::MetadataTransferPolicy;
 use crate::domain::models::signer::SignatureAlgorithm;
+use crate::domain::models::signing_session::SigningMethod;
 use atp_caip::chain_id::ChainId;
 use atp_caip::curve::Curve;
 use candid::{CandidType, Principal};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
 pub struct CreateAccountRequest {
@@ -49,6 +54,92 @@ pub struct ActivateAccountResponse {
     pub account: AccountReply,
 }
 
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SetGuardiansRequest {
+    pub account_id: String,
+    pub guardians: Vec<Principal>,
+    pub threshold: u8,
+    pub recovery_time_lock_seconds: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SetGuardiansResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ApproveGuardianChangeRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ApproveGuardianChangeResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct StartRecoveryRequest {
+    pub account_id: String,
+    pub new_owner: Principal,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct StartRecoveryResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ConfirmRecoveryRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ConfirmRecoveryResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CancelRecoveryRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CancelRecoveryResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CompleteRecoveryRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CompleteRecoveryResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SetInheritanceRequest {
+    pub account_id: String,
+    pub beneficiary: Option<Principal>,
+    pub inactivity_period_seconds: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SetInheritanceResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ClaimInheritanceRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ClaimInheritanceResponse {
+    pub account: AccountReply,
+}
+
 #[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
 pub struct GetAccountRequest {
     pub account_id: String,
@@ -63,6 +154,8 @@ pub struct GetAccountResponse {
 pub struct SignRequest {
     pub account_id: String,
     pub message_hex: String,
+    pub chain_id: Option<ChainId>,
+    pub key_version: Option<u32>,
 }
 
 #[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
@@ -74,6 +167,7 @@ pub struct SignResponse {
 pub struct SignEip1559TransactionRequest {
     pub account_id: String,
     pub tx_request: Eip1559TransactionRequestDTO,
+    pub key_version: Option<u32>,
 }
 
 #[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
@@ -101,3 +195,61 @@ pub struct GenerateAddressRequest {
 pub struct GenerateAddressResponse {
     pub address: String,
 }
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RotateKeyRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RotateKeyResponse {
+    pub account: AccountReply,
+    pub previous_key_version: u32,
+    pub sweep_plan: Vec<SweepPlanEntryReply>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetCapabilitiesResponse {
+    pub key_types: Vec<KeyTypeReply>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetAccountCapabilitiesRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetAccountCapabilitiesResponse {
+    pub account_id: String,
+    pub algorithm: SignatureAlgorithm,
+    pub curve: Curve,
+    pub signing_methods: Vec<SigningMethod>,
+    pub chains: Vec<ChainCapabilityReply>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SetAccountMetadataRequest {
+    pub account_id: String,
+    pub label: Option<String>,
+    pub description: Option<String>,
+    pub attributes: BTreeMap<String, String>,
+    pub tags: Vec<String>,
+    pub transfer_policy: Option<MetadataTransferPolicy>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SetAccountMetadataResponse {
+    pub account: AccountReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListAccountsRequest {
+    pub tag: Option<String>,
+    pub page_size: u64,
+    pub page: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListAccountsResponse {
+    pub accounts: Vec<AccountReply>,
+}
@@ -1,8 +1,12 @@
-use crate::domain::models::account::AccountState;
+use crate::domain::models::account::{Account, AccountState};
+use crate::domain::models::account_metadata::MetadataTransferPolicy;
+use crate::domain::models::capability::AddressFormat;
 use crate::domain::models::signer::SignatureAlgorithm;
+use crate::domain::models::signing_session::SigningMethod;
 use atp_caip::curve::Curve;
 use candid::CandidType;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 
 #[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
 pub struct AccountReply {
@@ -13,4 +17,157 @@ pub struct AccountReply {
     pub curve: Curve,
     pub account_state: AccountState,
     pub approved_address: String,
+    pub guardians: Vec<String>,
+    pub guardian_threshold: u8,
+    pub pending_guardian_change: Option<GuardianChangeReply>,
+    pub recovery_time_lock_seconds: u64,
+    pub pending_recovery: Option<RecoveryReply>,
+    pub last_activity: Option<u64>,
+    pub inheritance: Option<InheritanceReply>,
+    pub key_version: u32,
+    pub metadata: AccountMetadataReply,
+}
+
+/// Funds to move from the address of the retired key to the address of the new key
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SweepPlanEntryReply {
+    pub chain_id: String,
+    pub from_address: String,
+    pub to_address: String,
+}
+
+/// A key type accounts can be created with and the signing methods it allows
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct KeyTypeReply {
+    pub algorithm: SignatureAlgorithm,
+    pub curve: Curve,
+    pub signing_methods: Vec<SigningMethod>,
+}
+
+/// A registered chain an account can generate addresses for
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ChainCapabilityReply {
+    pub chain_id: String,
+    pub name: String,
+    pub is_testnet: bool,
+    pub address_format: AddressFormat,
+}
+
+/// Owner-defined label, description, attributes and tags of an account
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AccountMetadataReply {
+    pub label: Option<String>,
+    pub description: Option<String>,
+    pub attributes: BTreeMap<String, String>,
+    pub tags: Vec<String>,
+    pub transfer_policy: MetadataTransferPolicy,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GuardianChangeReply {
+    pub guardians: Vec<String>,
+    pub threshold: u8,
+    pub approvals: Vec<String>,
+    pub proposed_at: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RecoveryReply {
+    pub new_owner: String,
+    pub initiator: String,
+    pub confirmations: Vec<String>,
+    pub initiated_at: u64,
+    pub unlocks_at: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct InheritanceReply {
+    pub beneficiary: String,
+    pub inactivity_period_seconds: u64,
+    pub claimable_at: Option<u64>,
+    pub notified_at: Option<u64>,
+}
+
+impl From<&Account> for AccountReply {
+    fn from(account: &Account) -> Self {
+        AccountReply {
+            id: account.id().clone(),
+            owner: account.owner().to_string(),
+            public_key_hex: hex::encode(account.public_key()),
+            algorithm: account.algorithm().clone(),
+            curve: account.curve().clone(),
+            account_state: account.account_state().clone(),
+            approved_address: match account.approved_address() {
+                Some(address) => address.to_string(),
+                None => "".to_string(),
+            },
+            guardians: match account.guardians() {
+                Some(guardian_set) => guardian_set
+                    .guardians()
+                    .iter()
+                    .map(|guardian| guardian.to_string())
+                    .collect(),
+                None => vec![],
+            },
+            guardian_threshold: match account.guardians() {
+                Some(guardian_set) => *guardian_set.threshold(),
+                None => 0,
+            },
+            pending_guardian_change: account.pending_guardian_change().as_ref().map(|change| {
+                GuardianChangeReply {
+                    guardians: match change.guardians() {
+                        Some(guardian_set) => guardian_set
+                            .guardians()
+                            .iter()
+                            .map(|guardian| guardian.to_string())
+                            .collect(),
+                        None => vec![],
+                    },
+                    threshold: match change.guardians() {
+                        Some(guardian_set) => *guardian_set.threshold(),
+                        None => 0,
+                    },
+                    approvals: change
+                        .approvals()
+                        .iter()
+                        .map(|guardian| guardian.to_string())
+                        .collect(),
+                    proposed_at: *change.proposed_at(),
+                }
+            }),
+            recovery_time_lock_seconds: account.recovery_time_lock(),
+            pending_recovery: account
+                .pending_recovery()
+                .as_ref()
+                .map(|recovery| RecoveryReply {
+                    new_owner: recovery.new_owner().to_string(),
+                    initiator: recovery.initiator().to_string(),
+                    confirmations: recovery
+                        .confirmations()
+                        .iter()
+                        .map(|guardian| guardian.to_string())
+                        .collect(),
+                    initiated_at: *recovery.initiated_at(),
+                    unlocks_at: *recovery.unlocks_at(),
+                }),
+            last_activity: *account.last_activity(),
+            inheritance: account.inheritance().as_ref().map(|plan| InheritanceReply {
+                beneficiary: plan.beneficiary().to_string(),
+                inactivity_period_seconds: *plan.inactivity_period_seconds(),
+                claimable_at: account.inheritance_claimable_at(),
+                notified_at: *plan.notified_at(),
+            }),
+            key_version: account.key_version(),
+            metadata: {
+                let metadata = account.metadata();
+                AccountMetadataReply {
+                    label: metadata.label().clone(),
+                    description: metadata.description().clone(),
+                    attributes: metadata.attributes().clone(),
+                    tags: metadata.tags().clone(),
+                    transfer_policy: metadata.transfer_policy().clone(),
+                }
+            },
+        }
+    }
 }
@@ -0,0 +1,13 @@
+use crate::domain::models::audit_event::AuditEventKind;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AuditEventReply {
+    pub id: String,
+    pub account_id: String,
+    pub kind: AuditEventKind,
+    pub actor: String,
+    pub timestamp: u64,
+    pub message: String,
+}
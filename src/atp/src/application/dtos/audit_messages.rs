@@ -0,0 +1,15 @@
+use crate::application::dtos::audit_event_reply::AuditEventReply;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListAuditEventsRequest {
+    pub account_id: String,
+    pub page_size: u64,
+    pub page: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListAuditEventsResponse {
+    pub events: Vec<AuditEventReply>,
+}
@@ -0,0 +1,43 @@
+use crate::application::dtos::canister_config_reply::CanisterConfigReply;
+use crate::domain::models::canister_config::FeeSettings;
+use crate::domain::models::rate_limit::RateLimitSettings;
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+/// The argument passed when installing or upgrading the canister
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum CanisterArgs {
+    Init(InitArgs),
+    Upgrade(Option<UpgradeArgs>),
+}
+
+/// Settings of a freshly installed canister, missing fields fall back to the build defaults
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, Default)]
+pub struct InitArgs {
+    /// Threshold key used for key derivation and signing
+    pub key_id: Option<String>,
+    /// Chain registry in TOML, replacing the default registry
+    pub registry_seed: Option<String>,
+    /// Principals granted the Admin role besides the installer
+    pub admins: Option<Vec<Principal>>,
+    pub fee_settings: Option<FeeSettings>,
+    pub rate_limits: Option<RateLimitSettings>,
+}
+
+/// Settings changed by an upgrade, missing fields keep their stored value
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug, Default)]
+pub struct UpgradeArgs {
+    /// Threshold key used for key derivation and signing
+    pub key_id: Option<String>,
+    /// Chain registry in TOML, replacing the stored registry
+    pub registry_seed: Option<String>,
+    /// Principals granted the Admin role
+    pub admins: Option<Vec<Principal>>,
+    pub fee_settings: Option<FeeSettings>,
+    pub rate_limits: Option<RateLimitSettings>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetCanisterConfigResponse {
+    pub config: CanisterConfigReply,
+}
@@ -0,0 +1,12 @@
+use crate::domain::models::canister_config::FeeSettings;
+use crate::domain::models::rate_limit::RateLimitSettings;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CanisterConfigReply {
+    pub key_id: String,
+    pub fee_settings: FeeSettings,
+    pub rate_limits: RateLimitSettings,
+    pub updated_at: u64,
+}
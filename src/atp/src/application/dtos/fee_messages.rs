@@ -0,0 +1,42 @@
+use crate::application::dtos::fee_reply::{FeeBalanceReply, FeeReply};
+use crate::domain::models::fee::{FeeAsset, FeeLedger};
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetFeesResponse {
+    pub fees: Vec<FeeReply>,
+    pub ledger: Option<FeeLedger>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetFeeBalanceResponse {
+    pub balance: FeeBalanceReply,
+    pub deposit_owner: Principal,
+    pub deposit_subaccount: Vec<u8>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct DepositFeesResponse {
+    pub credited: String,
+    pub balance: FeeBalanceReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetCollectedFeesResponse {
+    pub cycles: FeeBalanceReply,
+    pub tokens: Option<FeeBalanceReply>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct WithdrawFeesRequest {
+    pub asset: FeeAsset,
+    pub amount: String,
+    pub to: Principal,
+    pub to_subaccount: Option<Vec<u8>>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct WithdrawFeesResponse {
+    pub collected: FeeBalanceReply,
+}
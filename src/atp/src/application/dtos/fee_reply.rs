@@ -0,0 +1,32 @@
+use crate::domain::models::fee::{FeeAccount, FeeOperation};
+use atp_caip::curve::Curve;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct FeeReply {
+    pub operation: FeeOperation,
+    pub curve: Curve,
+    pub cycles: u64,
+}
+
+/// A fee balance in raw base units
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct FeeBalanceReply {
+    pub amount: String,
+    pub decimals: u8,
+    pub updated_at: u64,
+}
+
+impl From<&FeeAccount> for FeeBalanceReply {
+    fn from(account: &FeeAccount) -> Self {
+        FeeBalanceReply {
+            amount: account
+                .balance()
+                .map(|balance| balance.raw_amount().to_string())
+                .unwrap_or_else(|_| "0".to_string()),
+            decimals: *account.decimals(),
+            updated_at: *account.updated_at(),
+        }
+    }
+}
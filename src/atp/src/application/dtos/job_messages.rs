@@ -0,0 +1,8 @@
+use crate::application::dtos::job_reply::JobReply;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListJobsResponse {
+    pub jobs: Vec<JobReply>,
+}
@@ -0,0 +1,15 @@
+use crate::domain::models::job::JobSchedule;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct JobReply {
+    pub name: String,
+    pub schedule: JobSchedule,
+    pub registered_at: u64,
+    pub last_run_at: Option<u64>,
+    pub last_success_at: Option<u64>,
+    pub last_error: Option<String>,
+    pub run_count: u64,
+    pub failure_count: u64,
+}
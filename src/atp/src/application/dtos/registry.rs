@@ -0,0 +1,169 @@
+use atp_caip::{AssetId, AssetIdBase, Curve, TokenPair};
+use atp_chain_registry::{AssetConfig, ChainConfig};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// A metadata entry, the value being the JSON encoding of a TOML value (e.g. `12`, `"ETH"`, `true`)
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct MetadataEntryDTO {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ChainConfigDTO {
+    pub chain_id: String,
+    pub name: String,
+    pub native_asset: String,
+    pub rpc_endpoints: Vec<String>,
+    pub explorer_url: Option<String>,
+    pub cryptographic_curve: Vec<Curve>,
+    pub is_testnet: bool,
+    pub assets: Vec<String>,
+    pub metadata: Vec<MetadataEntryDTO>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AssetConfigDTO {
+    pub asset_id_base: String,
+    pub symbol: String,
+    pub name: String,
+    pub is_native: bool,
+    pub decimals: u8,
+    pub metadata: Vec<MetadataEntryDTO>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct TokenPairDTO {
+    pub from_asset: AssetId,
+    pub to_asset: AssetId,
+    pub enabled: bool,
+    pub min_trade_amount: Option<String>,
+    pub max_trade_amount: Option<String>,
+    pub fee_percentage: Option<f64>,
+}
+
+// Convert metadata to entries sorted by key
+fn metadata_to_dto(metadata: &HashMap<String, toml::Value>) -> Vec<MetadataEntryDTO> {
+    let mut entries: Vec<MetadataEntryDTO> = metadata
+        .iter()
+        .map(|(key, value)| MetadataEntryDTO {
+            key: key.clone(),
+            value: serde_json::to_string(value).unwrap_or_default(),
+        })
+        .collect();
+    entries.sort_by(|a, b| a.key.cmp(&b.key));
+    entries
+}
+
+fn metadata_from_dto(
+    entries: Vec<MetadataEntryDTO>,
+) -> Result<HashMap<String, toml::Value>, String> {
+    entries
+        .into_iter()
+        .map(|entry| {
+            let value = serde_json::from_str::<toml::Value>(&entry.value)
+                .map_err(|e| format!("Invalid metadata value for {}: {}", entry.key, e))?;
+            Ok((entry.key, value))
+        })
+        .collect()
+}
+
+impl From<&ChainConfig> for ChainConfigDTO {
+    fn from(chain: &ChainConfig) -> Self {
+        ChainConfigDTO {
+            chain_id: chain.chain_id.clone(),
+            name: chain.name.clone(),
+            native_asset: chain.native_asset.clone(),
+            rpc_endpoints: chain.rpc_endpoints.clone(),
+            explorer_url: chain.explorer_url.clone(),
+            cryptographic_curve: chain.cryptographic_curve.clone(),
+            is_testnet: chain.is_testnet,
+            assets: chain.assets.iter().map(|asset| asset.to_string()).collect(),
+            metadata: metadata_to_dto(&chain.metadata),
+        }
+    }
+}
+
+impl TryFrom<ChainConfigDTO> for ChainConfig {
+    type Error = String;
+
+    fn try_from(dto: ChainConfigDTO) -> Result<Self, Self::Error> {
+        let assets = dto
+            .assets
+            .iter()
+            .map(|asset| {
+                AssetIdBase::from_str(asset).map_err(|e| format!("Invalid asset {}: {}", asset, e))
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(ChainConfig {
+            chain_id: dto.chain_id,
+            name: dto.name,
+            native_asset: dto.native_asset,
+            rpc_endpoints: dto.rpc_endpoints,
+            explorer_url: dto.explorer_url,
+            cryptographic_curve: dto.cryptographic_curve,
+            is_testnet: dto.is_testnet,
+            assets,
+            metadata: metadata_from_dto(dto.metadata)?,
+        })
+    }
+}
+
+impl From<&AssetConfig> for AssetConfigDTO {
+    fn from(asset: &AssetConfig) -> Self {
+        AssetConfigDTO {
+            asset_id_base: asset.asset_id_base.to_string(),
+            symbol: asset.symbol.clone(),
+            name: asset.name.clone(),
+            is_native: asset.is_native,
+            decimals: asset.decimals,
+            metadata: metadata_to_dto(&asset.metadata),
+        }
+    }
+}
+
+impl TryFrom<AssetConfigDTO> for AssetConfig {
+    type Error = String;
+
+    fn try_from(dto: AssetConfigDTO) -> Result<Self, Self::Error> {
+        Ok(AssetConfig {
+            asset_id_base: AssetIdBase::from_str(&dto.asset_id_base)
+                .map_err(|e| format!("Invalid asset {}: {}", dto.asset_id_base, e))?,
+            symbol: dto.symbol,
+            name: dto.name,
+            is_native: dto.is_native,
+            decimals: dto.decimals,
+            metadata: metadata_from_dto(dto.metadata)?,
+        })
+    }
+}
+
+impl From<&TokenPair> for TokenPairDTO {
+    fn from(pair: &TokenPair) -> Self {
+        TokenPairDTO {
+            from_asset: pair.from_asset.clone(),
+            to_asset: pair.to_asset.clone(),
+            enabled: pair.enabled,
+            min_trade_amount: pair.min_trade_amount.clone(),
+            max_trade_amount: pair.max_trade_amount.clone(),
+            fee_percentage: pair.fee_percentage,
+        }
+    }
+}
+
+impl From<TokenPairDTO> for TokenPair {
+    fn from(dto: TokenPairDTO) -> Self {
+        TokenPair {
+            from_asset: dto.from_asset,
+            to_asset: dto.to_asset,
+            enabled: dto.enabled,
+            min_trade_amount: dto.min_trade_amount,
+            max_trade_amount: dto.max_trade_amount,
+            fee_percentage: dto.fee_percentage,
+        }
+    }
+}
@@ -0,0 +1,67 @@
+use crate::application::dtos::registry::{AssetConfigDTO, ChainConfigDTO, TokenPairDTO};
+use crate::application::dtos::registry_reply::{RegistryReply, SupportedChainReply};
+use atp_caip::AssetId;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetRegistryResponse {
+    pub registry: RegistryReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListSupportedChainsResponse {
+    pub chains: Vec<SupportedChainReply>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AddChainRequest {
+    pub chain: ChainConfigDTO,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct UpdateChainRequest {
+    pub chain: ChainConfigDTO,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RemoveChainRequest {
+    pub chain_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AddAssetRequest {
+    pub asset: AssetConfigDTO,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct UpdateAssetRequest {
+    pub asset: AssetConfigDTO,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RemoveAssetRequest {
+    pub asset_id_base: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AddTokenPairRequest {
+    pub token_pair: TokenPairDTO,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct UpdateTokenPairRequest {
+    pub token_pair: TokenPairDTO,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RemoveTokenPairRequest {
+    pub from_asset: AssetId,
+    pub to_asset: AssetId,
+}
+
+/// Every registry change replies with the resulting registry
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct UpdateRegistryResponse {
+    pub registry: RegistryReply,
+}
@@ -0,0 +1,20 @@
+use crate::application::dtos::registry::{AssetConfigDTO, ChainConfigDTO, TokenPairDTO};
+use atp_caip::curve::Curve;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RegistryReply {
+    pub chains: Vec<ChainConfigDTO>,
+    pub assets: Vec<AssetConfigDTO>,
+    pub token_pairs: Vec<TokenPairDTO>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SupportedChainReply {
+    pub chain_id: String,
+    pub name: String,
+    pub native_asset: String,
+    pub cryptographic_curve: Vec<Curve>,
+    pub is_testnet: bool,
+}
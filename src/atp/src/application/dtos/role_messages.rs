@@ -0,0 +1,36 @@
+use crate::application::dtos::role_reply::RoleAssignmentReply;
+use crate::domain::models::role::Role;
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GrantRoleRequest {
+    pub principal: Principal,
+    pub role: Role,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GrantRoleResponse {
+    pub assignment: RoleAssignmentReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RevokeRoleRequest {
+    pub principal: Principal,
+    pub role: Role,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RevokeRoleResponse {
+    pub assignment: RoleAssignmentReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListRoleAssignmentsResponse {
+    pub assignments: Vec<RoleAssignmentReply>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetMyRolesResponse {
+    pub roles: Vec<Role>,
+}
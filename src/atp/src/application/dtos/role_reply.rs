@@ -0,0 +1,10 @@
+use crate::domain::models::role::Role;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RoleAssignmentReply {
+    pub principal: String,
+    pub roles: Vec<Role>,
+    pub updated_at: u64,
+}
@@ -0,0 +1,82 @@
+use crate::application::dtos::signing_proposal_reply::SigningProposalReply;
+use crate::domain::models::signing_proposal::SigningPayload;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CreateSigningProposalRequest {
+    pub account_id: String,
+    pub payload: SigningPayload,
+    pub ttl_seconds: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CreateSigningProposalResponse {
+    pub proposal: SigningProposalReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ApproveSigningProposalRequest {
+    pub account_id: String,
+    pub proposal_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ApproveSigningProposalResponse {
+    pub proposal: SigningProposalReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ExecuteSigningProposalRequest {
+    pub account_id: String,
+    pub proposal_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ExecuteSigningProposalResponse {
+    pub proposal: SigningProposalReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CancelSigningProposalRequest {
+    pub account_id: String,
+    pub proposal_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CancelSigningProposalResponse {
+    pub proposal: SigningProposalReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ExpireSigningProposalsRequest {
+    pub account_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ExpireSigningProposalsResponse {
+    pub expired: Vec<SigningProposalReply>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetSigningProposalRequest {
+    pub account_id: String,
+    pub proposal_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetSigningProposalResponse {
+    pub proposal: SigningProposalReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListSigningProposalsRequest {
+    pub account_id: String,
+    pub page_size: u64,
+    pub page: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListSigningProposalsResponse {
+    pub proposals: Vec<SigningProposalReply>,
+}
@@ -0,0 +1,17 @@
+use crate::domain::models::signing_proposal::{SigningPayload, SigningProposalStatus};
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SigningProposalReply {
+    pub id: String,
+    pub account_id: String,
+    pub proposer: String,
+    pub payload: SigningPayload,
+    pub approvals: Vec<String>,
+    pub threshold: u8,
+    pub status: SigningProposalStatus,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub signature: Option<String>,
+}
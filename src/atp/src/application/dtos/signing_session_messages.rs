@@ -0,0 +1,55 @@
+use crate::application::dtos::signing_session_reply::SigningSessionReply;
+use crate::domain::models::signing_session::SigningMethod;
+use atp_caip::chain_id::ChainId;
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GrantSigningSessionRequest {
+    pub account_id: String,
+    pub delegate: Principal,
+    pub ttl_seconds: u64,
+    pub max_signatures: u32,
+    pub allowed_chains: Vec<ChainId>,
+    pub allowed_methods: Vec<SigningMethod>,
+    pub max_value: Option<String>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GrantSigningSessionResponse {
+    pub session: SigningSessionReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RevokeSigningSessionRequest {
+    pub account_id: String,
+    pub session_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RevokeSigningSessionResponse {
+    pub session: SigningSessionReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetSigningSessionRequest {
+    pub account_id: String,
+    pub session_id: String,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct GetSigningSessionResponse {
+    pub session: SigningSessionReply,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListSigningSessionsRequest {
+    pub account_id: String,
+    pub page_size: u64,
+    pub page: u64,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct ListSigningSessionsResponse {
+    pub sessions: Vec<SigningSessionReply>,
+}
@@ -0,0 +1,19 @@
+use crate::domain::models::signing_session::SigningMethod;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SigningSessionReply {
+    pub id: String,
+    pub account_id: String,
+    pub delegate: String,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub max_signatures: u32,
+    pub signatures_used: u32,
+    pub allowed_chains: Vec<String>,
+    pub allowed_methods: Vec<SigningMethod>,
+    pub max_value: Option<String>,
+    pub revoked: bool,
+    pub active: bool,
+}
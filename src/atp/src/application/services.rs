@@ -1 +1,11 @@
 pub mod account_service;
+pub mod audit_service;
+pub mod canister_config_service;
+pub mod fee_service;
+pub mod inheritance_service;
+pub mod job_service;
+pub mod rate_limit_service;
+pub mod registry_service;
+pub mod role_service;
+pub mod signing_proposal_service;
+pub mod signing_session_service;
@@ -1,47 +1,69 @@
+use atp_caip::chain_id::ChainId;
 use atp_caip::curve::Curve;
 use candid::Principal;
 use ethers_core::types::transaction::eip1559::Eip1559TransactionRequest;
+use ethers_core::types::U256;
+use std::str::FromStr;
 
 use crate::application::dtos::account_messages::*;
-use crate::application::dtos::account_reply::AccountReply;
+use crate::application::dtos::account_reply::{
+    AccountReply, ChainCapabilityReply, KeyTypeReply, SweepPlanEntryReply,
+};
+use crate::application::services::fee_service::{FeeCharge, FeeService};
+use crate::application::services::rate_limit_service::RateLimitService;
 use crate::domain::models::account::{Account, AccountState};
+use crate::domain::models::account_metadata::{normalize_tag, AccountMetadata};
+use crate::domain::models::capability::{
+    address_format, signing_methods, supported_key_types, validate_key_type,
+};
+use crate::domain::models::fee::FeeOperation;
+use crate::domain::models::guardian::GuardianSet;
+use crate::domain::models::rate_limit::RateLimitOperation;
+use crate::domain::models::registry::{find_chain, registry_config};
 use crate::domain::models::signer::SignatureAlgorithm;
+use crate::domain::models::signing_session::{SigningMethod, SigningScope, SigningSession};
 use crate::domain::repositories::account_repository::IAccountRepository;
+use crate::domain::repositories::registry_repository::IRegistryRepository;
 use crate::domain::repositories::signer_repository::ISignerRepository;
-use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
-use crate::infrastructure::repositories::signer_repository_impl::SignerRepositoryImpl;
-use crate::utils::config::get_chain_registry;
+use crate::domain::repositories::signing_session_repository::ISigningSessionRepository;
+use crate::infrastructure::repositories::registry_repository_impl::RegistryRepositoryImpl;
+use crate::infrastructure::repositories::signing_session_repository_impl::SigningSessionRepositoryImpl;
 use crate::utils::eth_utils::sha256;
+use crate::utils::ic::api::get_ic_api;
 
-pub struct AccountService {
-    account_repository: AccountRepositoryImpl,
-    signer_repository: SignerRepositoryImpl,
+/// Page size used when looking up the sessions of an account
+const SESSION_SCAN_PAGE_SIZE: usize = 100;
+
+pub struct AccountService<A: IAccountRepository, S: ISignerRepository> {
+    account_repository: A,
+    signer_repository: S,
+    signing_session_repository: SigningSessionRepositoryImpl,
+    registry_repository: RegistryRepositoryImpl,
+    fee_service: FeeService,
+    rate_limit_service: RateLimitService,
 }
 
-impl AccountService {
+impl<A: IAccountRepository, S: ISignerRepository> AccountService<A, S> {
     pub fn new(
-        account_repository: AccountRepositoryImpl,
-        signer_repository: SignerRepositoryImpl,
+        account_repository: A,
+        signer_repository: S,
+        signing_session_repository: SigningSessionRepositoryImpl,
+        registry_repository: RegistryRepositoryImpl,
+        fee_service: FeeService,
+        rate_limit_service: RateLimitService,
     ) -> Self {
         Self {
             account_repository,
             signer_repository,
+            signing_session_repository,
+            registry_repository,
+            fee_service,
+            rate_limit_service,
         }
     }
     // Convert domain model to DTO
     pub fn to_account_reply(&self, account: &Account) -> AccountReply {
-        AccountReply {
-            id: account.id().clone(),
-            owner: account.owner().to_string(),
-            public_key_hex: hex::encode(account.public_key()),
-            algorithm: account.algorithm().clone(),
-            curve: account.curve().clone(),
-            account_state: account.account_state().clone(),
-            approved_address: match account.approved_address() {
-                Some(address) => address.to_string(),
-                None => "".to_string(),
-            },
-        }
+        AccountReply::from(account)
     }
 
     pub async fn create_account(
@@ -49,20 +71,50 @@ impl AccountService {
         request: CreateAccountRequest,
         owner: Principal,
     ) -> Result<CreateAccountResponse, String> {
+        validate_key_type(&request.algorithm, &request.curve)?;
+        // Throttle the owner before paying for a threshold key call
+        self.rate_limit_service.check_caller(&owner)?;
+        if let Some(max_accounts) = self.rate_limit_service.max_accounts_per_owner() {
+            // The owner index errors for owners without accounts
+            let owned = self
+                .account_repository
+                .find_by_owner(&owner.to_string(), max_accounts as usize, 1)
+                .map(|accounts| accounts.len())
+                .unwrap_or(0);
+            if owned >= max_accounts as usize {
+                return Err(format!(
+                    "Owners cannot hold more than {} accounts",
+                    max_accounts
+                ));
+            }
+        }
+        self.rate_limit_service
+            .acquire(owner, RateLimitOperation::CreateAccount)?;
+
         // Generate a unique account ID
-        let principal = ic_cdk::api::caller().to_string();
-        let timestamp = ic_cdk::api::time();
+        let ic_api = get_ic_api();
+        let principal = ic_api.caller().to_string();
+        let timestamp = ic_api.time();
         let id_string = format!("{}{}", principal, timestamp);
         let id = hex::encode(sha256(&id_string));
 
-        // Generate a new public key
+        // Generate a new public key, charging the caller for it
+        let charge = self
+            .fee_service
+            .charge(FeeOperation::CreateAccount, &request.curve)?;
         let public_key = self
             .signer_repository
-            .generate_public_key(request.algorithm.clone(), request.curve.clone(), id.clone())
-            .await?;
+            .generate_public_key(
+                request.algorithm.clone(),
+                request.curve.clone(),
+                // New accounts start at key version 0, derived from the account ID alone
+                vec![id.as_bytes().to_vec()],
+            )
+            .await;
+        let public_key = self.fee_service.complete(charge, public_key)?;
 
         // Create a new account
-        let account = Account::new(
+        let mut account = Account::new(
             id,
             owner,
             public_key.public_key,
@@ -70,6 +122,7 @@ impl AccountService {
             request.curve.clone(),
             request.approved_address,
         );
+        account.record_activity();
 
         let created_account = self.account_repository.insert(account.clone())?;
         Ok(CreateAccountResponse {
@@ -115,6 +168,7 @@ impl AccountService {
         let mut account = self.account_repository.get(&request.account_id)?;
         // unlock the account
         account.activate()?;
+        account.record_activity();
         // update the account in the repository
         let updated_account = self.account_repository.insert(account.clone())?;
         Ok(ActivateAccountResponse {
@@ -122,6 +176,155 @@ impl AccountService {
         })
     }
 
+    pub fn set_guardians(
+        &self,
+        request: SetGuardiansRequest,
+    ) -> Result<SetGuardiansResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        // An empty guardian list restores the single owner default
+        let guardians = if request.guardians.is_empty() {
+            None
+        } else {
+            Some(GuardianSet::new(request.guardians, request.threshold)?)
+        };
+        account.set_guardians(guardians)?;
+        if let Some(time_lock_seconds) = request.recovery_time_lock_seconds {
+            account.set_recovery_time_lock(time_lock_seconds)?;
+        }
+        account.record_activity();
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+        Ok(SetGuardiansResponse {
+            account: self.to_account_reply(&updated_account),
+        })
+    }
+
+    pub fn approve_guardian_change(
+        &self,
+        request: ApproveGuardianChangeRequest,
+    ) -> Result<ApproveGuardianChangeResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        account.approve_guardian_change()?;
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+        Ok(ApproveGuardianChangeResponse {
+            account: self.to_account_reply(&updated_account),
+        })
+    }
+
+    pub fn start_recovery(
+        &self,
+        request: StartRecoveryRequest,
+    ) -> Result<StartRecoveryResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        account.start_recovery(request.new_owner)?;
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+        Ok(StartRecoveryResponse {
+            account: self.to_account_reply(&updated_account),
+        })
+    }
+
+    pub fn confirm_recovery(
+        &self,
+        request: ConfirmRecoveryRequest,
+    ) -> Result<ConfirmRecoveryResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        account.confirm_recovery()?;
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+        Ok(ConfirmRecoveryResponse {
+            account: self.to_account_reply(&updated_account),
+        })
+    }
+
+    pub fn cancel_recovery(
+        &self,
+        request: CancelRecoveryRequest,
+    ) -> Result<CancelRecoveryResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        account.cancel_recovery()?;
+        account.record_activity();
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+        Ok(CancelRecoveryResponse {
+            account: self.to_account_reply(&updated_account),
+        })
+    }
+
+    pub fn complete_recovery(
+        &self,
+        request: CompleteRecoveryRequest,
+    ) -> Result<CompleteRecoveryResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        account.complete_recovery()?;
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+        Ok(CompleteRecoveryResponse {
+            account: self.to_account_reply(&updated_account),
+        })
+    }
+
+    pub fn set_account_metadata(
+        &self,
+        request: SetAccountMetadataRequest,
+    ) -> Result<SetAccountMetadataResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        let metadata = AccountMetadata::new(
+            request.label,
+            request.description,
+            request.attributes,
+            request.tags,
+            request.transfer_policy.unwrap_or_default(),
+        )?;
+        account.set_metadata(metadata)?;
+        account.record_activity();
+        // update the account and its tag index in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+        Ok(SetAccountMetadataResponse {
+            account: self.to_account_reply(&updated_account),
+        })
+    }
+
+    // List the accounts of the caller, optionally only the ones carrying a tag
+    pub fn list_accounts(
+        &self,
+        request: ListAccountsRequest,
+    ) -> Result<ListAccountsResponse, String> {
+        let owner = get_ic_api().caller().to_string();
+        let page_size = request.page_size as usize;
+        let page = request.page as usize;
+        if page_size == 0 || page == 0 {
+            return Err("Page size and page number must be greater than 0".to_string());
+        }
+        let accounts = match request.tag {
+            Some(tag) => self.account_repository.find_by_tag(
+                &owner,
+                &normalize_tag(&tag)?,
+                page_size,
+                page,
+            )?,
+            // Owner queries fail for owners without accounts and pages past the end
+            None => self
+                .account_repository
+                .find_by_owner(&owner, page_size, page)
+                .unwrap_or_default(),
+        };
+        Ok(ListAccountsResponse {
+            accounts: accounts
+                .iter()
+                .map(|account| self.to_account_reply(account))
+                .collect(),
+        })
+    }
+
     pub fn get_account(&self, request: GetAccountRequest) -> Result<GetAccountResponse, String> {
         let account = self.account_repository.get(&request.account_id)?;
         Ok(GetAccountResponse {
@@ -131,33 +334,40 @@ impl AccountService {
 
     pub async fn sign(&self, request: SignRequest) -> Result<SignResponse, String> {
         // Check if the account exists
-        let account = self.account_repository.get(&request.account_id)?;
-
-        // Check if the account is active
-        if account.account_state().clone() != AccountState::Active {
-            return Err("Account is not activated".to_string());
-        }
+        let mut account = self.account_repository.get(&request.account_id)?;
         let message_bytes = match hex::decode(&request.message_hex) {
             Ok(bytes) => bytes,
             Err(_) => return Err("Invalid hex string".to_string()),
         };
-        // Check if the caller is the owner of the account
-        if account.is_owner(ic_cdk::api::caller()) {
-            let signature = self
-                .signer_repository
-                .sign(
-                    account.algorithm().clone(),
-                    account.curve().clone(),
-                    message_bytes,
-                    account.id().clone(),
-                )
-                .await?;
-            Ok(SignResponse {
-                signature: hex::encode(signature.signature),
-            })
-        } else {
-            Err("Caller is not the owner of the account".to_string())
-        }
+        let (key_version, session) = self.authorize_signing_key(
+            &account,
+            request.key_version,
+            SigningScope {
+                method: SigningMethod::Message,
+                chain_id: request.chain_id,
+                value: None,
+            },
+        )?;
+        self.rate_limit_service
+            .acquire(get_ic_api().caller(), RateLimitOperation::Sign)?;
+        self.record_owner_activity(&mut account)?;
+        let charge = self
+            .fee_service
+            .charge(FeeOperation::Sign, account.curve())?;
+        let charge = self.save_session(session, charge)?;
+        let signature = self
+            .signer_repository
+            .sign(
+                account.algorithm().clone(),
+                account.curve().clone(),
+                message_bytes,
+                account.derivation_path(key_version),
+            )
+            .await;
+        let signature = self.fee_service.complete(charge, signature)?;
+        Ok(SignResponse {
+            signature: hex::encode(signature.signature),
+        })
     }
 
     pub async fn sign_eip1559_transaction(
@@ -165,7 +375,7 @@ impl AccountService {
         request: SignEip1559TransactionRequest,
     ) -> Result<SignEip1559TransactionResponse, String> {
         // Check if the account exists
-        let account = self.account_repository.get(&request.account_id)?;
+        let mut account = self.account_repository.get(&request.account_id)?;
         // Check if the signature algorithm is ECDSA
         if account.algorithm().clone() != SignatureAlgorithm::Ecdsa {
             return Err("Signature algorithm is not ECDSA".to_string());
@@ -175,21 +385,264 @@ impl AccountService {
             return Err("Curve is not secp256k1".to_string());
         }
 
+        let tx = Eip1559TransactionRequest::try_from(request.tx_request)?;
+        // Scope the transaction by its chain and value for session holders
+        let chain_id = match tx.chain_id {
+            Some(chain_id) => Some(
+                ChainId::new("eip155", chain_id.to_string())
+                    .map_err(|e| format!("Invalid chain ID {}: {}", chain_id, e))?,
+            ),
+            None => None,
+        };
+        let value = tx.value.map(|value| {
+            if value > U256::from(u128::MAX) {
+                u128::MAX
+            } else {
+                value.as_u128()
+            }
+        });
+        let (key_version, session) = self.authorize_signing_key(
+            &account,
+            request.key_version,
+            SigningScope {
+                method: SigningMethod::Eip1559Transaction,
+                chain_id,
+                value,
+            },
+        )?;
+        self.rate_limit_service
+            .acquire(get_ic_api().caller(), RateLimitOperation::Sign)?;
+        self.record_owner_activity(&mut account)?;
+        let charge = self
+            .fee_service
+            .charge(FeeOperation::Sign, account.curve())?;
+        let charge = self.save_session(session, charge)?;
+        let signature = self
+            .signer_repository
+            .sign_eip1559_transaction(tx, account.derivation_path(key_version))
+            .await;
+        let signature = self.fee_service.complete(charge, signature)?;
+        Ok(SignEip1559TransactionResponse { signature })
+    }
+
+    /// Move the account to a new key and plan the sweep of the funds held by the old key
+    ///
+    /// The plan lists the old and new address on every registered chain supporting the
+    /// account curve. The owner signs the sweep with the previous `key_version`.
+    pub async fn rotate_key(&self, request: RotateKeyRequest) -> Result<RotateKeyResponse, String> {
+        // Check if the account exists
+        let account = self.account_repository.get(&request.account_id)?;
+        let previous_key_version = account.key_version();
+        let version = account.next_key_version()?;
+        // Throttle the owner before paying for a threshold key call
+        self.rate_limit_service
+            .acquire(get_ic_api().caller(), RateLimitOperation::RotateKey)?;
+
+        // Derive the key of the next version, charging the caller for it
+        let charge = self
+            .fee_service
+            .charge(FeeOperation::RotateKey, account.curve())?;
+        let public_key = self
+            .signer_repository
+            .generate_public_key(
+                account.algorithm().clone(),
+                account.curve().clone(),
+                account.derivation_path(version),
+            )
+            .await;
+        let public_key = self.fee_service.complete(charge, public_key)?.public_key;
+
+        // Reload the account so changes made while deriving the key are not overwritten
+        let mut account = self.account_repository.get(&request.account_id)?;
+        let previous_public_key = account.public_key().clone();
+        account.rotate_key(version, public_key)?;
+        account.record_activity();
+        let updated_account = self.account_repository.insert(account.clone())?;
+
+        let sweep_plan = self.sweep_plan(&updated_account, &previous_public_key)?;
+        Ok(RotateKeyResponse {
+            account: self.to_account_reply(&updated_account),
+            previous_key_version,
+            sweep_plan,
+        })
+    }
+
+    // Pair the addresses of the previous and current key on every registered chain
+    fn sweep_plan(
+        &self,
+        account: &Account,
+        previous_public_key: &[u8],
+    ) -> Result<Vec<SweepPlanEntryReply>, String> {
+        let registry = registry_config(&self.registry_repository.find_all()?)?;
+        let mut sweep_plan = Vec::new();
+        for chain_config in registry.chains.values() {
+            if !chain_config.is_supported_curve(account.curve()) {
+                continue;
+            }
+            let chain_id = ChainId::from_str(&chain_config.chain_id)
+                .map_err(|e| format!("Invalid chain ID {}: {}", chain_config.chain_id, e))?;
+            // Wildcard chains do not identify a network to hold funds on
+            if chain_id.reference() == "*" {
+                continue;
+            }
+            let from_address = atp_chain_utils::address::generate_address(
+                hex::encode(previous_public_key),
+                chain_id.clone(),
+            )
+            .map_err(|e| format!("Failed to generate address: {}", e))?;
+            let to_address = atp_chain_utils::address::generate_address(
+                hex::encode(account.public_key()),
+                chain_id,
+            )
+            .map_err(|e| format!("Failed to generate address: {}", e))?;
+            sweep_plan.push(SweepPlanEntryReply {
+                chain_id: chain_config.chain_id.clone(),
+                from_address,
+                to_address,
+            });
+        }
+        sweep_plan.sort_by(|a, b| a.chain_id.cmp(&b.chain_id));
+        Ok(sweep_plan)
+    }
+
+    // Check that the caller can sign with the requested key version, returning the version to use
+    //
+    // The current key follows the usual rules, a retired key can only be used by the owner
+    // to sweep the funds it still holds.
+    fn authorize_signing_key(
+        &self,
+        account: &Account,
+        key_version: Option<u32>,
+        scope: SigningScope,
+    ) -> Result<(u32, Option<SigningSession>), String> {
+        if let Some(version) = key_version {
+            if version != account.key_version() {
+                account.authorize_retired_key(version)?;
+                return Ok((version, None));
+            }
+        }
+
         // Check if the account is active
         if account.account_state().clone() != AccountState::Active {
             return Err("Account is not activated".to_string());
         }
-        // Check if the caller is the owner of the account
-        if account.is_owner(ic_cdk::api::caller()) {
-            let tx = Eip1559TransactionRequest::try_from(request.tx_request)?;
-            let signature = self
-                .signer_repository
-                .sign_eip1559_transaction(tx, account.id().clone())
-                .await?;
-            Ok(SignEip1559TransactionResponse { signature })
-        } else {
-            Err("Caller is not the owner of the account".to_string())
+        // Guarded accounts can only sign through approved signing proposals
+        if account.requires_guardian_approval() {
+            return Err(
+                "Account requires guardian approval, create a signing proposal".to_string(),
+            );
+        }
+        // Check if the caller is the owner of the account or holds a session
+        let session = self.authorize_signer(account, scope)?;
+        Ok((account.key_version(), session))
+    }
+
+    // Persist the last activity when the owner is the caller
+    fn record_owner_activity(&self, account: &mut Account) -> Result<(), String> {
+        if account.record_activity() {
+            *account = self.account_repository.insert(account.clone())?;
+        }
+        Ok(())
+    }
+
+    // Allow the owner, or consume a signature from a session the caller holds on the account
+    //
+    // The session is returned unsaved, `save_session` stores it once the call is paid for.
+    fn authorize_signer(
+        &self,
+        account: &Account,
+        scope: SigningScope,
+    ) -> Result<Option<SigningSession>, String> {
+        let caller = get_ic_api().caller();
+        if account.is_owner(caller) {
+            return Ok(None);
+        }
+
+        let now = get_ic_api().time();
+        let mut last_error = "Caller is not the owner of the account".to_string();
+        let mut page = 1;
+        // The partition query errors once no further page exists
+        while let Ok(sessions) = self.signing_session_repository.find_by_account(
+            account.id(),
+            SESSION_SCAN_PAGE_SIZE,
+            page,
+        ) {
+            for mut session in sessions {
+                if session.delegate() != &caller || !session.is_active(account, now) {
+                    continue;
+                }
+                match session.authorize(account, &scope) {
+                    Ok(()) => return Ok(Some(session)),
+                    Err(e) => last_error = e,
+                }
+            }
+            page += 1;
+        }
+        Err(last_error)
+    }
+
+    // Save the session signature before signing, so concurrent calls cannot exceed its limits
+    //
+    // The fee is refunded if the session cannot be saved.
+    fn save_session(
+        &self,
+        session: Option<SigningSession>,
+        charge: FeeCharge,
+    ) -> Result<FeeCharge, String> {
+        if let Some(session) = session {
+            if let Err(e) = self.signing_session_repository.insert(session) {
+                return self.fee_service.complete(charge, Err(e));
+            }
         }
+        Ok(charge)
+    }
+
+    /// List the key types accounts can be created with
+    pub fn get_capabilities(&self) -> Result<GetCapabilitiesResponse, String> {
+        let key_types = supported_key_types()
+            .into_iter()
+            .map(|key_type| KeyTypeReply {
+                signing_methods: signing_methods(&key_type.algorithm, &key_type.curve),
+                algorithm: key_type.algorithm,
+                curve: key_type.curve,
+            })
+            .collect();
+        Ok(GetCapabilitiesResponse { key_types })
+    }
+
+    /// List the signing methods of an account and the registered chains it has addresses on
+    pub fn get_account_capabilities(
+        &self,
+        request: GetAccountCapabilitiesRequest,
+    ) -> Result<GetAccountCapabilitiesResponse, String> {
+        let account = self.account_repository.get(&request.account_id)?;
+        let registry = registry_config(&self.registry_repository.find_all()?)?;
+        let mut chains = Vec::new();
+        for chain_config in registry.chains.values() {
+            if !chain_config.is_supported_curve(account.curve()) {
+                continue;
+            }
+            let chain_id = ChainId::from_str(&chain_config.chain_id)
+                .map_err(|e| format!("Invalid chain ID {}: {}", chain_config.chain_id, e))?;
+            let Some(address_format) = address_format(chain_id.namespace()) else {
+                continue;
+            };
+            chains.push(ChainCapabilityReply {
+                chain_id: chain_config.chain_id.clone(),
+                name: chain_config.name.clone(),
+                is_testnet: chain_config.is_testnet,
+                address_format,
+            });
+        }
+        chains.sort_by(|a, b| a.chain_id.cmp(&b.chain_id));
+
+        Ok(GetAccountCapabilitiesResponse {
+            account_id: account.id().clone(),
+            algorithm: account.algorithm().clone(),
+            curve: account.curve().clone(),
+            signing_methods: signing_methods(account.algorithm(), account.curve()),
+            chains,
+        })
     }
 
     /// Generate a blockchain address for any supported chain
@@ -202,17 +655,9 @@ impl AccountService {
     ) -> Result<GenerateAddressResponse, String> {
         // Check if the account exists
         let account = self.account_repository.get(&request.account_id)?;
-        // Generate a wildcard chain ID
-        let chain_id_wildcard = request
-            .chain_id
-            .to_wildcard()
-            .map_err(|e| format!("Invalid chain ID {}: {}", request.chain_id, e))?;
-
-        // Check curve compatibility
-        let registry = get_chain_registry()?;
-        let chain_config = registry
-            .get_chain(&chain_id_wildcard)
-            .map_err(|e| format!("Chain {} not found: {}", request.chain_id, e))?;
+        // Check if the chain is registered and the curve is compatible
+        let registry = registry_config(&self.registry_repository.find_all()?)?;
+        let chain_config = find_chain(&registry, &request.chain_id)?;
         if !chain_config.is_supported_curve(account.curve()) {
             return Err(format!(
                 "Curve {} is not supported for chain {}",
@@ -231,3 +676,602 @@ impl AccountService {
         Ok(GenerateAddressResponse { address })
     }
 }
+
+#[cfg(test)]
+mod account_service_tests {
+    use std::collections::BTreeMap;
+    use std::rc::Rc;
+
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+
+    use super::*;
+    use crate::application::dtos::eip1559::Eip1559TransactionRequestDTO;
+    use crate::application::services::registry_service::RegistryService;
+    use crate::domain::models::canister_config::{CanisterConfig, FeeSettings};
+    use crate::domain::models::capability::AddressFormat;
+    use crate::domain::models::rate_limit::{RateLimit, RateLimitSettings};
+    use crate::domain::models::signing_session::SessionLimits;
+    use crate::domain::repositories::canister_config_repository::ICanisterConfigRepository;
+    use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+    use crate::infrastructure::repositories::fee_account_repository_impl::FeeAccountRepositoryImpl;
+    use crate::infrastructure::repositories::in_memory_account_repository_impl::InMemoryAccountRepositoryImpl;
+    use crate::infrastructure::repositories::local_signer_repository_impl::LocalSignerRepositoryImpl;
+    use crate::infrastructure::repositories::payment_repository_impl::PaymentRepositoryImpl;
+    use crate::infrastructure::repositories::rate_limit_repository_impl::RateLimitRepositoryImpl;
+    use crate::utils::config::registry_seed_config;
+    use crate::utils::eth_utils::generate_eth_address_from_sec1;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn set_caller(caller: Principal) {
+        set_ic_api(Rc::new(MockIcApi::new().with_caller(caller).with_time(1)));
+    }
+
+    type TestAccountService =
+        AccountService<InMemoryAccountRepositoryImpl, LocalSignerRepositoryImpl>;
+
+    // Helper function to set up a service keeping accounts in memory and signing locally
+    fn setup() -> TestAccountService {
+        set_caller(principal(1));
+        SigningSessionRepositoryImpl::init().expect("Failed to initialize repository");
+        RegistryRepositoryImpl::init().expect("Failed to initialize repository");
+        CanisterConfigRepositoryImpl::init().expect("Failed to initialize repository");
+        FeeAccountRepositoryImpl::init().expect("Failed to initialize repository");
+        RegistryService::new(RegistryRepositoryImpl::new())
+            .seed(registry_seed_config(None).unwrap())
+            .unwrap();
+        AccountService::new(
+            InMemoryAccountRepositoryImpl::new(),
+            LocalSignerRepositoryImpl::default(),
+            SigningSessionRepositoryImpl::new(),
+            RegistryRepositoryImpl::new(),
+            FeeService::new(
+                CanisterConfigRepositoryImpl::new(),
+                FeeAccountRepositoryImpl::new(),
+                PaymentRepositoryImpl::new(),
+            ),
+            RateLimitService::new(
+                CanisterConfigRepositoryImpl::new(),
+                RateLimitRepositoryImpl::new(),
+            ),
+        )
+    }
+
+    // Create an active account owned by the caller
+    async fn create_active_account(
+        service: &TestAccountService,
+        algorithm: SignatureAlgorithm,
+        curve: Curve,
+        owner: Principal,
+    ) -> AccountReply {
+        set_caller(owner);
+        let account = service
+            .create_account(
+                CreateAccountRequest {
+                    algorithm,
+                    curve,
+                    approved_address: owner,
+                },
+                owner,
+            )
+            .await
+            .unwrap()
+            .account;
+        assert_eq!(account.account_state, AccountState::Locked);
+        service
+            .unlock_account(UnlockAccountRequest {
+                account_id: account.id.clone(),
+            })
+            .unwrap();
+        service
+            .activate_account(ActivateAccountRequest {
+                account_id: account.id.clone(),
+            })
+            .unwrap()
+            .account
+    }
+
+    fn sign_request(account_id: &str, message: &[u8]) -> SignRequest {
+        SignRequest {
+            account_id: account_id.to_string(),
+            message_hex: hex::encode(message),
+            chain_id: None,
+            key_version: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_generate_address() {
+        let service = setup();
+        let owner = principal(1);
+        let account =
+            create_active_account(&service, SignatureAlgorithm::Ecdsa, Curve::Secp256k1, owner)
+                .await;
+
+        let message = [3u8; 32];
+        let signature = service
+            .sign(sign_request(&account.id, &message))
+            .await
+            .unwrap()
+            .signature;
+        let public_key = hex::decode(&account.public_key_hex).unwrap();
+        let verifying_key = k256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key).unwrap();
+        let signature =
+            k256::ecdsa::Signature::from_slice(&hex::decode(signature).unwrap()).unwrap();
+        assert!(verifying_key.verify_prehash(&message, &signature).is_ok());
+
+        // Only the owner can sign
+        set_caller(principal(2));
+        assert!(service
+            .sign(sign_request(&account.id, &message))
+            .await
+            .is_err());
+
+        let address = service
+            .generate_address(GenerateAddressRequest {
+                account_id: account.id.clone(),
+                chain_id: ChainId::new("eip155", "1").unwrap(),
+            })
+            .unwrap()
+            .address;
+        assert_eq!(
+            address.to_lowercase(),
+            generate_eth_address_from_sec1(public_key)
+                .unwrap()
+                .to_lowercase()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_eip1559_transaction() {
+        let service = setup();
+        let account = create_active_account(
+            &service,
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            principal(1),
+        )
+        .await;
+
+        let request = SignEip1559TransactionRequest {
+            account_id: account.id.clone(),
+            tx_request: Eip1559TransactionRequestDTO {
+                to: Some("0x0000000000000000000000000000000000000001".to_string()),
+                from: None,
+                nonce: Some("0".to_string()),
+                value: Some("1000".to_string()),
+                gas: Some("21000".to_string()),
+                max_priority_fee_per_gas: Some("1".to_string()),
+                max_fee_per_gas: Some("10".to_string()),
+                data: None,
+                chain_id: Some("1".to_string()),
+            },
+            key_version: None,
+        };
+        let signed = service
+            .sign_eip1559_transaction(request.clone())
+            .await
+            .unwrap()
+            .signature;
+        assert!(signed.starts_with("0x02"));
+
+        // The local signer is deterministic
+        let again = service
+            .sign_eip1559_transaction(request)
+            .await
+            .unwrap()
+            .signature;
+        assert_eq!(signed, again);
+    }
+
+    #[tokio::test]
+    async fn test_sign_with_ed25519() {
+        let service = setup();
+        let account = create_active_account(
+            &service,
+            SignatureAlgorithm::Schnorr,
+            Curve::Ed25519,
+            principal(1),
+        )
+        .await;
+
+        let message = b"hello";
+        let signature = service
+            .sign(sign_request(&account.id, message))
+            .await
+            .unwrap()
+            .signature;
+        let public_key: [u8; 32] = hex::decode(&account.public_key_hex)
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let signature =
+            ed25519_dalek::Signature::from_slice(&hex::decode(signature).unwrap()).unwrap();
+        assert!(ed25519_dalek::VerifyingKey::from_bytes(&public_key)
+            .unwrap()
+            .verify_strict(message, &signature)
+            .is_ok());
+    }
+
+    async fn create_locked_account(
+        service: &TestAccountService,
+        owner: Principal,
+        approved_address: Principal,
+    ) -> AccountReply {
+        set_caller(owner);
+        service
+            .create_account(
+                CreateAccountRequest {
+                    algorithm: SignatureAlgorithm::Ecdsa,
+                    curve: Curve::Secp256k1,
+                    approved_address,
+                },
+                owner,
+            )
+            .await
+            .unwrap()
+            .account
+    }
+
+    #[tokio::test]
+    async fn test_transfer_state_machine() {
+        let service = setup();
+        let (owner, dex, buyer) = (principal(1), principal(2), principal(3));
+        let account = create_locked_account(&service, owner, dex).await;
+        let account_id = account.id.clone();
+        let transfer = |to: Principal| {
+            service.transfer_account(TransferAccountRequest {
+                account_id: account_id.clone(),
+                to,
+            })
+        };
+
+        // Only the approved address moves a locked account
+        set_caller(owner);
+        assert!(transfer(owner).is_err());
+        assert!(service
+            .unlock_account(UnlockAccountRequest {
+                account_id: account_id.clone(),
+            })
+            .is_err());
+        set_caller(dex);
+        let transferred = transfer(buyer).unwrap().account;
+        assert_eq!(transferred.owner, buyer.to_string());
+        assert_eq!(transferred.account_state, AccountState::Unlocked);
+        assert_eq!(transferred.approved_address, "");
+
+        // The new owner activates the account, the previous parties lose access
+        assert!(service
+            .activate_account(ActivateAccountRequest {
+                account_id: account_id.clone(),
+            })
+            .is_err());
+        set_caller(buyer);
+        service
+            .activate_account(ActivateAccountRequest {
+                account_id: account_id.clone(),
+            })
+            .unwrap();
+        assert!(service
+            .sign(sign_request(&account_id, &[1; 32]))
+            .await
+            .is_ok());
+        set_caller(owner);
+        assert!(service
+            .sign(sign_request(&account_id, &[1; 32]))
+            .await
+            .is_err());
+        set_caller(dex);
+        assert!(transfer(dex).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_key_keeps_retired_key_for_owner() {
+        let service = setup();
+        let owner = principal(1);
+        let account =
+            create_active_account(&service, SignatureAlgorithm::Ecdsa, Curve::Secp256k1, owner)
+                .await;
+
+        set_caller(principal(2));
+        assert!(service
+            .rotate_key(RotateKeyRequest {
+                account_id: account.id.clone(),
+            })
+            .await
+            .is_err());
+        set_caller(owner);
+        let rotated = service
+            .rotate_key(RotateKeyRequest {
+                account_id: account.id.clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(rotated.previous_key_version, 0);
+        assert_eq!(rotated.account.key_version, 1);
+        assert_ne!(rotated.account.public_key_hex, account.public_key_hex);
+
+        // The retired key still signs for the owner, so the funds it holds can be swept
+        let message = [5u8; 32];
+        let verify = |public_key_hex: &str, signature: String| {
+            let public_key = hex::decode(public_key_hex).unwrap();
+            let signature =
+                k256::ecdsa::Signature::from_slice(&hex::decode(signature).unwrap()).unwrap();
+            k256::ecdsa::VerifyingKey::from_sec1_bytes(&public_key)
+                .unwrap()
+                .verify_prehash(&message, &signature)
+                .is_ok()
+        };
+        let mut request = sign_request(&account.id, &message);
+        let current = service.sign(request.clone()).await.unwrap().signature;
+        assert!(verify(&rotated.account.public_key_hex, current));
+        request.key_version = Some(0);
+        let retired = service.sign(request.clone()).await.unwrap().signature;
+        assert!(verify(&account.public_key_hex, retired));
+        request.key_version = Some(2);
+        assert!(service.sign(request).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rotate_key_is_rate_limited_and_charged() {
+        let service = setup();
+        let owner = principal(1);
+        let account =
+            create_active_account(&service, SignatureAlgorithm::Ecdsa, Curve::Secp256k1, owner)
+                .await;
+        let fee_settings = FeeSettings {
+            rotate_key_cycles: Some(50),
+            ..Default::default()
+        };
+        let mut config = CanisterConfig::new("key".to_string(), fee_settings, 1).unwrap();
+        config
+            .set_rate_limits(
+                RateLimitSettings {
+                    rotate_key: Some(RateLimit {
+                        capacity: 2,
+                        refill_seconds: 3600,
+                    }),
+                    ..Default::default()
+                },
+                1,
+            )
+            .unwrap();
+        CanisterConfigRepositoryImpl::new().insert(config).unwrap();
+        let request = || RotateKeyRequest {
+            account_id: account.id.clone(),
+        };
+
+        // The key call is paid for like an account creation
+        assert!(service.rotate_key(request()).await.is_err());
+        set_ic_api(Rc::new(
+            MockIcApi::new()
+                .with_caller(owner)
+                .with_time(1)
+                .with_cycles(50),
+        ));
+        assert_eq!(
+            service
+                .rotate_key(request())
+                .await
+                .unwrap()
+                .account
+                .key_version,
+            1
+        );
+
+        // Every attempt takes a token, paid or not
+        assert!(service.rotate_key(request()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_account_quota_and_anonymous_owner() {
+        let service = setup();
+        let mut config = CanisterConfig::new("key".to_string(), Default::default(), 1).unwrap();
+        config
+            .set_rate_limits(
+                RateLimitSettings {
+                    max_accounts_per_owner: Some(1),
+                    ..Default::default()
+                },
+                1,
+            )
+            .unwrap();
+        CanisterConfigRepositoryImpl::new().insert(config).unwrap();
+
+        let request = |approved_address: Principal| CreateAccountRequest {
+            algorithm: SignatureAlgorithm::Ecdsa,
+            curve: Curve::Secp256k1,
+            approved_address,
+        };
+        create_locked_account(&service, principal(1), principal(1)).await;
+        assert!(service
+            .create_account(request(principal(1)), principal(1))
+            .await
+            .is_err());
+        create_locked_account(&service, principal(2), principal(2)).await;
+
+        set_caller(Principal::anonymous());
+        assert!(service
+            .create_account(request(principal(1)), Principal::anonymous())
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_refused_delegate_sign_keeps_session_signature() {
+        let service = setup();
+        let owner = principal(1);
+        let account =
+            create_active_account(&service, SignatureAlgorithm::Ecdsa, Curve::Secp256k1, owner)
+                .await;
+        let stored = service.account_repository.get(&account.id).unwrap();
+        let session = SigningSession::new(
+            "session-1".to_string(),
+            &stored,
+            principal(3),
+            1,
+            u64::MAX,
+            SessionLimits {
+                max_signatures: 5,
+                allowed_chains: Vec::new(),
+                allowed_methods: vec![SigningMethod::Message],
+                max_value: None,
+            },
+        );
+        SigningSessionRepositoryImpl::new().insert(session).unwrap();
+
+        let mut config = CanisterConfig::new("key".to_string(), Default::default(), 1).unwrap();
+        config
+            .set_rate_limits(
+                RateLimitSettings {
+                    sign: Some(RateLimit {
+                        capacity: 1,
+                        refill_seconds: 3600,
+                    }),
+                    ..Default::default()
+                },
+                1,
+            )
+            .unwrap();
+        CanisterConfigRepositoryImpl::new().insert(config).unwrap();
+
+        // A signature refused by the rate limit is not taken from the session
+        set_caller(principal(3));
+        let message = [3u8; 32];
+        assert!(service
+            .sign(sign_request(&account.id, &message))
+            .await
+            .is_ok());
+        assert!(service
+            .sign(sign_request(&account.id, &message))
+            .await
+            .is_err());
+        let session = SigningSessionRepositoryImpl::new()
+            .get(&account.id, "session-1")
+            .unwrap();
+        assert_eq!(session.signatures_used(), &1);
+    }
+
+    #[tokio::test]
+    async fn test_capabilities() {
+        let service = setup();
+        let key_types = service.get_capabilities().unwrap().key_types;
+        assert_eq!(key_types.len(), 3);
+
+        // Pairs outside the matrix are rejected before any key is derived
+        set_caller(principal(1));
+        assert!(service
+            .create_account(
+                CreateAccountRequest {
+                    algorithm: SignatureAlgorithm::Ecdsa,
+                    curve: Curve::Ed25519,
+                    approved_address: principal(1),
+                },
+                principal(1),
+            )
+            .await
+            .is_err());
+
+        let account = create_active_account(
+            &service,
+            SignatureAlgorithm::Schnorr,
+            Curve::Ed25519,
+            principal(1),
+        )
+        .await;
+        let capabilities = service
+            .get_account_capabilities(GetAccountCapabilitiesRequest {
+                account_id: account.id.clone(),
+            })
+            .unwrap();
+        assert_eq!(capabilities.signing_methods, vec![SigningMethod::Message]);
+        assert!(!capabilities.chains.is_empty());
+        for chain in &capabilities.chains {
+            assert!(chain.chain_id.starts_with("solana:"));
+            assert_eq!(chain.address_format, AddressFormat::Base58);
+            let chain_id = ChainId::from_str(&chain.chain_id).unwrap();
+            assert!(service
+                .generate_address(GenerateAddressRequest {
+                    account_id: account.id.clone(),
+                    chain_id,
+                })
+                .is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metadata_and_list_accounts_by_tag() {
+        let service = setup();
+        let (owner, dex, buyer) = (principal(1), principal(2), principal(3));
+        let tagged = create_locked_account(&service, owner, dex).await;
+        // Account IDs derive from the creation time
+        set_ic_api(Rc::new(MockIcApi::new().with_caller(owner).with_time(2)));
+        service
+            .create_account(
+                CreateAccountRequest {
+                    algorithm: SignatureAlgorithm::Ecdsa,
+                    curve: Curve::Secp256k1,
+                    approved_address: dex,
+                },
+                owner,
+            )
+            .await
+            .unwrap();
+        let set_metadata = |tags: Vec<&str>| {
+            service.set_account_metadata(SetAccountMetadataRequest {
+                account_id: tagged.id.clone(),
+                label: Some("Savings".to_string()),
+                description: Some("Listed on the dex".to_string()),
+                attributes: BTreeMap::new(),
+                tags: tags.into_iter().map(String::from).collect(),
+                transfer_policy: None,
+            })
+        };
+        let list = |tag: Option<&str>| {
+            service
+                .list_accounts(ListAccountsRequest {
+                    tag: tag.map(String::from),
+                    page_size: 10,
+                    page: 1,
+                })
+                .unwrap()
+                .accounts
+        };
+
+        // Only the owner edits the metadata
+        set_caller(dex);
+        assert!(set_metadata(vec!["cold"]).is_err());
+        set_caller(owner);
+        let account = set_metadata(vec!["Cold", "defi"]).unwrap().account;
+        assert_eq!(account.metadata.label, Some("Savings".to_string()));
+        assert_eq!(account.metadata.tags, vec!["cold", "defi"]);
+
+        assert_eq!(list(None).len(), 2);
+        assert_eq!(list(Some("COLD")).len(), 1);
+        assert!(list(Some("hot")).is_empty());
+
+        // The default policy drops the label and tags but keeps the description
+        set_caller(dex);
+        let transferred = service
+            .transfer_account(TransferAccountRequest {
+                account_id: tagged.id.clone(),
+                to: buyer,
+            })
+            .unwrap()
+            .account;
+        assert_eq!(transferred.metadata.label, None);
+        assert!(transferred.metadata.tags.is_empty());
+        assert_eq!(
+            transferred.metadata.description,
+            Some("Listed on the dex".to_string())
+        );
+        set_caller(owner);
+        assert!(list(Some("cold")).is_empty());
+        set_caller(buyer);
+        assert_eq!(list(None).len(), 1);
+    }
+}
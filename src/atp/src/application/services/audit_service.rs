@@ -0,0 +1,79 @@
+use candid::Principal;
+use std::cell::RefCell;
+
+use crate::application::dtos::audit_event_reply::AuditEventReply;
+use crate::application::dtos::audit_messages::*;
+use crate::domain::models::audit_event::{AuditEvent, AuditEventKind};
+use crate::domain::repositories::audit_event_repository::IAuditEventRepository;
+use crate::infrastructure::repositories::audit_event_repository_impl::AuditEventRepositoryImpl;
+use crate::utils::ic::api::get_ic_api;
+
+thread_local! {
+    // Orders audit events recorded within the same round
+    static AUDIT_SEQUENCE: RefCell<u64> = const { RefCell::new(0) };
+}
+
+pub struct AuditService {
+    audit_event_repository: AuditEventRepositoryImpl,
+}
+
+impl AuditService {
+    pub fn new(audit_event_repository: AuditEventRepositoryImpl) -> Self {
+        Self {
+            audit_event_repository,
+        }
+    }
+
+    // Convert domain model to DTO
+    pub fn to_event_reply(&self, event: &AuditEvent) -> AuditEventReply {
+        AuditEventReply {
+            id: event.id().clone(),
+            account_id: event.account_id().clone(),
+            kind: event.kind().clone(),
+            actor: event.actor().to_string(),
+            timestamp: *event.timestamp(),
+            message: event.message().clone(),
+        }
+    }
+
+    /// Append an event to the audit trail of an account
+    pub fn record(
+        &self,
+        account_id: &str,
+        kind: AuditEventKind,
+        actor: Principal,
+        message: String,
+    ) -> Result<AuditEvent, String> {
+        let sequence = AUDIT_SEQUENCE.with(|sequence| {
+            let mut sequence = sequence.borrow_mut();
+            *sequence += 1;
+            *sequence
+        });
+        let event = AuditEvent::new(
+            account_id.to_string(),
+            kind,
+            actor,
+            get_ic_api().time(),
+            sequence,
+            message,
+        );
+        self.audit_event_repository.insert(event)
+    }
+
+    pub fn list_events(
+        &self,
+        request: ListAuditEventsRequest,
+    ) -> Result<ListAuditEventsResponse, String> {
+        let events = self.audit_event_repository.find_by_account(
+            &request.account_id,
+            request.page_size as usize,
+            request.page as usize,
+        )?;
+        Ok(ListAuditEventsResponse {
+            events: events
+                .iter()
+                .map(|event| self.to_event_reply(event))
+                .collect(),
+        })
+    }
+}
@@ -0,0 +1,136 @@
+use crate::application::dtos::canister_config_messages::*;
+use crate::application::dtos::canister_config_reply::CanisterConfigReply;
+use crate::domain::models::canister_config::CanisterConfig;
+use crate::domain::repositories::canister_config_repository::ICanisterConfigRepository;
+use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+use crate::utils::config::DEFAULT_KEY_ID;
+use crate::utils::ic::api::get_ic_api;
+
+pub struct CanisterConfigService {
+    canister_config_repository: CanisterConfigRepositoryImpl,
+}
+
+impl CanisterConfigService {
+    pub fn new(canister_config_repository: CanisterConfigRepositoryImpl) -> Self {
+        Self {
+            canister_config_repository,
+        }
+    }
+
+    // Convert domain model to DTO
+    pub fn to_config_reply(&self, config: &CanisterConfig) -> CanisterConfigReply {
+        CanisterConfigReply {
+            key_id: config.key_id().clone(),
+            fee_settings: config.fee_settings().clone(),
+            rate_limits: config.rate_limits(),
+            updated_at: *config.updated_at(),
+        }
+    }
+
+    /// Store the configuration of a freshly installed canister
+    pub fn install(&self, args: &InitArgs) -> Result<CanisterConfig, String> {
+        let now = get_ic_api().time();
+        let mut config = CanisterConfig::new(
+            args.key_id
+                .clone()
+                .unwrap_or_else(|| DEFAULT_KEY_ID.to_string()),
+            args.fee_settings.clone().unwrap_or_default(),
+            now,
+        )?;
+        if let Some(rate_limits) = &args.rate_limits {
+            config.set_rate_limits(rate_limits.clone(), now)?;
+        }
+        self.canister_config_repository.insert(config)
+    }
+
+    /// Apply the upgrade arguments to the stored configuration
+    ///
+    /// Canisters installed before the configuration was persisted start from the build defaults.
+    pub fn upgrade(&self, args: &UpgradeArgs) -> Result<CanisterConfig, String> {
+        let now = get_ic_api().time();
+        let mut config = match self.canister_config_repository.get() {
+            Ok(config) => config,
+            Err(_) => CanisterConfig::new(DEFAULT_KEY_ID.to_string(), Default::default(), now)?,
+        };
+
+        if let Some(key_id) = &args.key_id {
+            config.set_key_id(key_id.clone(), now)?;
+        }
+        if let Some(fee_settings) = &args.fee_settings {
+            config.set_fee_settings(fee_settings.clone(), now)?;
+        }
+        if let Some(rate_limits) = &args.rate_limits {
+            config.set_rate_limits(rate_limits.clone(), now)?;
+        }
+        self.canister_config_repository.insert(config)
+    }
+
+    pub fn get_config(&self) -> Result<GetCanisterConfigResponse, String> {
+        let config = self.canister_config_repository.get()?;
+        Ok(GetCanisterConfigResponse {
+            config: self.to_config_reply(&config),
+        })
+    }
+}
+
+#[cfg(test)]
+mod canister_config_service_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::domain::models::canister_config::FeeSettings;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    fn setup() -> CanisterConfigService {
+        set_ic_api(Rc::new(MockIcApi::new().with_time(1)));
+        CanisterConfigRepositoryImpl::init().expect("Failed to initialize repository");
+        CanisterConfigService::new(CanisterConfigRepositoryImpl::new())
+    }
+
+    #[test]
+    fn test_install_falls_back_to_defaults() {
+        let service = setup();
+        service.install(&InitArgs::default()).unwrap();
+
+        let config = service.get_config().unwrap().config;
+        assert_eq!(config.key_id, DEFAULT_KEY_ID);
+        assert_eq!(config.fee_settings, FeeSettings::default());
+
+        let args = InitArgs {
+            key_id: Some(" ".to_string()),
+            ..Default::default()
+        };
+        assert!(service.install(&args).is_err());
+    }
+
+    #[test]
+    fn test_upgrade_keeps_unset_fields() {
+        let service = setup();
+        let fee_settings = FeeSettings {
+            create_account_cycles: 10,
+            sign_cycles: 1,
+            ..Default::default()
+        };
+        service
+            .install(&InitArgs {
+                key_id: Some("test_key_1".to_string()),
+                fee_settings: Some(fee_settings.clone()),
+                ..Default::default()
+            })
+            .unwrap();
+
+        service
+            .upgrade(&UpgradeArgs {
+                key_id: Some("key_1".to_string()),
+                ..Default::default()
+            })
+            .unwrap();
+        let config = service.get_config().unwrap().config;
+        assert_eq!(config.key_id, "key_1");
+        assert_eq!(config.fee_settings, fee_settings);
+
+        service.upgrade(&UpgradeArgs::default()).unwrap();
+        assert_eq!(service.get_config().unwrap().config.key_id, "key_1");
+    }
+}
@@ -0,0 +1,509 @@
+use atp_caip::curve::Curve;
+use atp_caip::money::Money;
+use candid::{Nat, Principal};
+use std::str::FromStr;
+
+use crate::application::dtos::fee_messages::*;
+use crate::application::dtos::fee_reply::{FeeBalanceReply, FeeReply};
+use crate::domain::models::canister_config::FeeSettings;
+use crate::domain::models::fee::{
+    deposit_subaccount, prepaid_account_id, FeeAccount, FeeAsset, FeeLedger, FeeOperation,
+    CYCLES_DECIMALS,
+};
+use crate::domain::repositories::canister_config_repository::ICanisterConfigRepository;
+use crate::domain::repositories::fee_account_repository::IFeeAccountRepository;
+use crate::domain::repositories::payment_repository::{
+    IPaymentRepository, Icrc1Account, Icrc1TransferArg,
+};
+use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+use crate::infrastructure::repositories::fee_account_repository_impl::FeeAccountRepositoryImpl;
+use crate::infrastructure::repositories::payment_repository_impl::PaymentRepositoryImpl;
+use crate::utils::ic::api::get_ic_api;
+
+/// A fee reserved from the caller for an operation in progress
+pub enum FeeCharge {
+    Free,
+    Cycles(u64),
+    Prepaid { owner: Principal, amount: Money },
+}
+
+pub struct FeeService {
+    canister_config_repository: CanisterConfigRepositoryImpl,
+    fee_account_repository: FeeAccountRepositoryImpl,
+    payment_repository: PaymentRepositoryImpl,
+}
+
+impl FeeService {
+    pub fn new(
+        canister_config_repository: CanisterConfigRepositoryImpl,
+        fee_account_repository: FeeAccountRepositoryImpl,
+        payment_repository: PaymentRepositoryImpl,
+    ) -> Self {
+        Self {
+            canister_config_repository,
+            fee_account_repository,
+            payment_repository,
+        }
+    }
+
+    pub fn get_fees(&self) -> Result<GetFeesResponse, String> {
+        let fee_settings = self.fee_settings();
+        let mut fees = Vec::new();
+        for operation in [
+            FeeOperation::CreateAccount,
+            FeeOperation::Sign,
+            FeeOperation::RotateKey,
+        ] {
+            for curve in [Curve::Secp256k1, Curve::Ed25519] {
+                fees.push(FeeReply {
+                    cycles: fee_settings.fee(&operation, &curve),
+                    operation: operation.clone(),
+                    curve,
+                });
+            }
+        }
+        Ok(GetFeesResponse {
+            fees,
+            ledger: fee_settings.ledger,
+        })
+    }
+
+    pub fn get_fee_balance(&self) -> Result<GetFeeBalanceResponse, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+        let ledger = self.ledger()?;
+        let account = self.get_account(prepaid_account_id(&caller), ledger.decimals);
+        Ok(GetFeeBalanceResponse {
+            balance: FeeBalanceReply::from(&account),
+            deposit_owner: ic_api.id(),
+            deposit_subaccount: deposit_subaccount(&caller),
+        })
+    }
+
+    pub fn get_collected_fees(&self) -> Result<GetCollectedFeesResponse, String> {
+        let cycles = self.get_account(FeeAsset::Cycles.collected_account_id(), CYCLES_DECIMALS);
+        let tokens = self.fee_settings().ledger.map(|ledger| {
+            self.get_account(FeeAsset::Tokens.collected_account_id(), ledger.decimals)
+        });
+        Ok(GetCollectedFeesResponse {
+            cycles: FeeBalanceReply::from(&cycles),
+            tokens: tokens.as_ref().map(FeeBalanceReply::from),
+        })
+    }
+
+    /// Reserve the fee of an operation from the caller
+    ///
+    /// Attached cycles are only checked here and accepted by `settle`, so they return to the
+    /// caller if the operation fails. Prepaid fees are debited right away and restored by `refund`.
+    pub fn charge(&self, operation: FeeOperation, curve: &Curve) -> Result<FeeCharge, String> {
+        let ic_api = get_ic_api();
+        let fee_settings = self.fee_settings();
+        let fee = fee_settings.fee(&operation, curve);
+        if fee == 0 {
+            return Ok(FeeCharge::Free);
+        }
+        if ic_api.msg_cycles_available() >= fee as u128 {
+            return Ok(FeeCharge::Cycles(fee));
+        }
+
+        let ledger = match fee_settings.ledger {
+            Some(ledger) => ledger,
+            None => return Err(format!("A fee of {} cycles must be attached", fee)),
+        };
+        let caller = ic_api.caller();
+        let amount = money(ledger.price(fee)?, ledger.decimals)?;
+        let mut account = self.get_account(prepaid_account_id(&caller), ledger.decimals);
+        account
+            .debit(&amount, ic_api.time())
+            .map_err(|e| format!("A fee of {} cycles must be attached or prepaid: {}", fee, e))?;
+        self.fee_account_repository.insert(account)?;
+        Ok(FeeCharge::Prepaid {
+            owner: caller,
+            amount,
+        })
+    }
+
+    /// Collect a reserved fee once the operation succeeded
+    pub fn settle(&self, charge: FeeCharge) -> Result<(), String> {
+        match charge {
+            FeeCharge::Free => Ok(()),
+            FeeCharge::Cycles(fee) => {
+                let accepted = get_ic_api().msg_cycles_accept(fee as u128);
+                self.credit(
+                    FeeAsset::Cycles.collected_account_id(),
+                    &money(accepted, CYCLES_DECIMALS)?,
+                )
+            }
+            FeeCharge::Prepaid { amount, .. } => {
+                self.credit(FeeAsset::Tokens.collected_account_id(), &amount)
+            }
+        }
+    }
+
+    /// Return a reserved fee after the operation failed
+    pub fn refund(&self, charge: FeeCharge) -> Result<(), String> {
+        match charge {
+            FeeCharge::Prepaid { owner, amount } => {
+                self.credit(prepaid_account_id(&owner), &amount)
+            }
+            FeeCharge::Free | FeeCharge::Cycles(_) => Ok(()),
+        }
+    }
+
+    /// Settle the fee if the operation succeeded and refund it otherwise
+    pub fn complete<T>(&self, charge: FeeCharge, result: Result<T, String>) -> Result<T, String> {
+        match result {
+            Ok(value) => {
+                self.settle(charge)?;
+                Ok(value)
+            }
+            Err(e) => {
+                self.refund(charge)?;
+                Err(e)
+            }
+        }
+    }
+
+    /// Credit the tokens the caller sent to its deposit subaccount to its prepaid balance
+    ///
+    /// The deposit is moved to the main account of the canister, paying the ledger fee from it.
+    pub async fn deposit(&self) -> Result<DepositFeesResponse, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+        let ledger = self.ledger()?;
+        let subaccount = deposit_subaccount(&caller);
+
+        let balance = self
+            .payment_repository
+            .balance_of(
+                ledger.canister_id,
+                Icrc1Account {
+                    owner: ic_api.id(),
+                    subaccount: Some(subaccount.clone()),
+                },
+            )
+            .await?;
+        let balance = nat_to_money(&balance, ledger.decimals)?;
+        let transfer_fee = money(ledger.transfer_fee as u128, ledger.decimals)?;
+        if balance.raw_amount() <= transfer_fee.raw_amount() {
+            return Err("Deposit does not cover the ledger transfer fee".to_string());
+        }
+        let credited = balance.sub(&transfer_fee).map_err(|e| e.to_string())?;
+
+        // A concurrent deposit of the same funds fails here, so they are credited once
+        self.payment_repository
+            .transfer(
+                ledger.canister_id,
+                Icrc1TransferArg {
+                    from_subaccount: Some(subaccount),
+                    to: Icrc1Account {
+                        owner: ic_api.id(),
+                        subaccount: None,
+                    },
+                    amount: money_to_nat(&credited)?,
+                    fee: Some(Nat::from(ledger.transfer_fee)),
+                    memo: None,
+                    created_at_time: None,
+                },
+            )
+            .await?;
+        self.credit(prepaid_account_id(&caller), &credited)?;
+
+        let account = self.get_account(prepaid_account_id(&caller), ledger.decimals);
+        Ok(DepositFeesResponse {
+            credited: credited.raw_amount().to_string(),
+            balance: FeeBalanceReply::from(&account),
+        })
+    }
+
+    /// Withdraw collected fees, cycles are deposited to a canister and tokens sent to a ledger account
+    pub async fn withdraw(
+        &self,
+        request: WithdrawFeesRequest,
+    ) -> Result<WithdrawFeesResponse, String> {
+        let ledger = match request.asset {
+            FeeAsset::Cycles => None,
+            FeeAsset::Tokens => Some(self.ledger()?),
+        };
+        let decimals = match &ledger {
+            Some(ledger) => ledger.decimals,
+            None => CYCLES_DECIMALS,
+        };
+        let amount = Money::from_raw(&request.amount, decimals).map_err(|e| e.to_string())?;
+        if amount.is_zero() {
+            return Err("Withdrawal amount must be positive".to_string());
+        }
+
+        // Debit before the call so concurrent withdrawals cannot exceed the collected fees
+        let id = request.asset.collected_account_id();
+        let mut collected = self.get_account(id.clone(), decimals);
+        collected.debit(&amount, get_ic_api().time())?;
+        self.fee_account_repository.insert(collected)?;
+
+        let result = match ledger {
+            None => match u128::try_from(amount.raw_amount()) {
+                Ok(cycles) => {
+                    self.payment_repository
+                        .deposit_cycles(request.to, cycles)
+                        .await
+                }
+                Err(_) => Err("Withdrawal amount is too large".to_string()),
+            },
+            Some(ledger) => self.transfer_tokens(&ledger, &amount, &request).await,
+        };
+        if let Err(e) = result {
+            self.credit(id, &amount)?;
+            return Err(e);
+        }
+
+        let collected = self.get_account(id, decimals);
+        Ok(WithdrawFeesResponse {
+            collected: FeeBalanceReply::from(&collected),
+        })
+    }
+
+    // Send tokens from the main account of the canister, the ledger fee is paid from the amount
+    async fn transfer_tokens(
+        &self,
+        ledger: &FeeLedger,
+        amount: &Money,
+        request: &WithdrawFeesRequest,
+    ) -> Result<(), String> {
+        let transfer_fee = money(ledger.transfer_fee as u128, ledger.decimals)?;
+        if amount.raw_amount() <= transfer_fee.raw_amount() {
+            return Err("Withdrawal does not cover the ledger transfer fee".to_string());
+        }
+        let sent = amount.sub(&transfer_fee).map_err(|e| e.to_string())?;
+        self.payment_repository
+            .transfer(
+                ledger.canister_id,
+                Icrc1TransferArg {
+                    from_subaccount: None,
+                    to: Icrc1Account {
+                        owner: request.to,
+                        subaccount: request.to_subaccount.clone(),
+                    },
+                    amount: money_to_nat(&sent)?,
+                    fee: Some(Nat::from(ledger.transfer_fee)),
+                    memo: None,
+                    created_at_time: None,
+                },
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Fee settings in use, canisters without a stored configuration charge nothing
+    fn fee_settings(&self) -> FeeSettings {
+        self.canister_config_repository
+            .get()
+            .map(|config| config.fee_settings().clone())
+            .unwrap_or_default()
+    }
+
+    fn ledger(&self) -> Result<FeeLedger, String> {
+        self.fee_settings()
+            .ledger
+            .ok_or_else(|| "No fee ledger is configured".to_string())
+    }
+
+    // Load a fee account, starting from an empty balance if it does not exist yet
+    fn get_account(&self, id: String, decimals: u8) -> FeeAccount {
+        match self.fee_account_repository.get(&id) {
+            Ok(account) => account,
+            Err(_) => FeeAccount::new(id, decimals, get_ic_api().time()),
+        }
+    }
+
+    fn credit(&self, id: String, amount: &Money) -> Result<(), String> {
+        let mut account = self.get_account(id, amount.decimals);
+        account.credit(amount, get_ic_api().time())?;
+        self.fee_account_repository.insert(account)?;
+        Ok(())
+    }
+}
+
+fn money(amount: u128, decimals: u8) -> Result<Money, String> {
+    Money::from_raw(&amount.to_string(), decimals).map_err(|e| e.to_string())
+}
+
+fn nat_to_money(amount: &Nat, decimals: u8) -> Result<Money, String> {
+    Money::from_raw(&amount.0.to_str_radix(10), decimals).map_err(|e| e.to_string())
+}
+
+fn money_to_nat(amount: &Money) -> Result<Nat, String> {
+    Nat::from_str(&amount.raw_amount().to_string()).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod fee_service_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::domain::models::canister_config::CanisterConfig;
+    use crate::domain::models::fee::CurveFee;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    // Helper function to set up a service charging 100 cycles per signature, 10 on ed25519
+    fn setup(ledger: Option<FeeLedger>) -> FeeService {
+        set_ic_api(Rc::new(MockIcApi::new().with_time(1)));
+        CanisterConfigRepositoryImpl::init().expect("Failed to initialize repository");
+        FeeAccountRepositoryImpl::init().expect("Failed to initialize repository");
+        let fee_settings = FeeSettings {
+            create_account_cycles: 0,
+            sign_cycles: 100,
+            rotate_key_cycles: None,
+            curve_fees: vec![CurveFee {
+                operation: FeeOperation::Sign,
+                curve: Curve::Ed25519,
+                cycles: 10,
+            }],
+            ledger,
+        };
+        CanisterConfigRepositoryImpl::new()
+            .insert(CanisterConfig::new("key".to_string(), fee_settings, 1).unwrap())
+            .unwrap();
+        FeeService::new(
+            CanisterConfigRepositoryImpl::new(),
+            FeeAccountRepositoryImpl::new(),
+            PaymentRepositoryImpl::new(),
+        )
+    }
+
+    fn cycles_ledger() -> FeeLedger {
+        FeeLedger {
+            canister_id: principal(9),
+            decimals: CYCLES_DECIMALS,
+            transfer_fee: 1,
+            tokens_per_trillion_cycles: Some(1_000_000_000_000),
+        }
+    }
+
+    fn balance(service: &FeeService, id: String) -> String {
+        FeeBalanceReply::from(&service.get_account(id, CYCLES_DECIMALS)).amount
+    }
+
+    #[test]
+    fn test_charge_attached_cycles() {
+        let service = setup(None);
+        let ic_api = Rc::new(MockIcApi::new().with_caller(principal(1)).with_cycles(150));
+        set_ic_api(ic_api.clone());
+
+        assert!(matches!(
+            service
+                .charge(FeeOperation::CreateAccount, &Curve::Secp256k1)
+                .unwrap(),
+            FeeCharge::Free
+        ));
+
+        // Cycles are only accepted once the operation succeeded
+        let charge = service
+            .charge(FeeOperation::Sign, &Curve::Secp256k1)
+            .unwrap();
+        assert_eq!(ic_api.accepted_cycles(), 0);
+        service.settle(charge).unwrap();
+        assert_eq!(ic_api.accepted_cycles(), 100);
+        assert_eq!(
+            balance(&service, FeeAsset::Cycles.collected_account_id()),
+            "100"
+        );
+
+        // Without a ledger the remaining 50 cycles do not cover another signature
+        assert!(service
+            .charge(FeeOperation::Sign, &Curve::Secp256k1)
+            .is_err());
+        let charge = service.charge(FeeOperation::Sign, &Curve::Ed25519).unwrap();
+        service.settle(charge).unwrap();
+        assert_eq!(ic_api.accepted_cycles(), 110);
+    }
+
+    #[test]
+    fn test_charge_prepaid_balance() {
+        let service = setup(Some(cycles_ledger()));
+        let owner = principal(1);
+        set_ic_api(Rc::new(MockIcApi::new().with_caller(owner)));
+        service
+            .credit(
+                prepaid_account_id(&owner),
+                &money(150, CYCLES_DECIMALS).unwrap(),
+            )
+            .unwrap();
+
+        let charge = service
+            .charge(FeeOperation::Sign, &Curve::Secp256k1)
+            .unwrap();
+        assert_eq!(balance(&service, prepaid_account_id(&owner)), "50");
+
+        // A failed operation gives the fee back
+        service.refund(charge).unwrap();
+        assert_eq!(balance(&service, prepaid_account_id(&owner)), "150");
+
+        let charge = service
+            .charge(FeeOperation::Sign, &Curve::Secp256k1)
+            .unwrap();
+        service.settle(charge).unwrap();
+        assert_eq!(
+            balance(&service, FeeAsset::Tokens.collected_account_id()),
+            "100"
+        );
+        assert!(service
+            .charge(FeeOperation::Sign, &Curve::Secp256k1)
+            .is_err());
+        assert_eq!(balance(&service, prepaid_account_id(&owner)), "50");
+    }
+
+    #[test]
+    fn test_charge_prepaid_converts_cycles_to_tokens() {
+        // 0.5 token of 8 decimals per trillion cycles
+        let ledger = FeeLedger {
+            canister_id: principal(9),
+            decimals: 8,
+            transfer_fee: 10_000,
+            tokens_per_trillion_cycles: Some(50_000_000),
+        };
+        let service = setup(Some(ledger));
+        let owner = principal(1);
+        set_ic_api(Rc::new(MockIcApi::new().with_caller(owner)));
+        service
+            .credit(prepaid_account_id(&owner), &money(1_000, 8).unwrap())
+            .unwrap();
+
+        // 100 cycles cost 5e-11 token, rounded up to one base unit
+        let charge = service
+            .charge(FeeOperation::Sign, &Curve::Secp256k1)
+            .unwrap();
+        match &charge {
+            FeeCharge::Prepaid { amount, .. } => assert_eq!(amount, &money(1, 8).unwrap()),
+            _ => panic!("Expected a prepaid charge"),
+        }
+        service.settle(charge).unwrap();
+        assert_eq!(
+            FeeBalanceReply::from(&service.get_account(prepaid_account_id(&owner), 8)).amount,
+            "999"
+        );
+    }
+
+    #[test]
+    fn test_get_fees() {
+        let service = setup(Some(cycles_ledger()));
+        let fees = service.get_fees().unwrap();
+        assert_eq!(fees.fees.len(), 6);
+        assert!(fees
+            .fees
+            .iter()
+            .any(|fee| fee.operation == FeeOperation::Sign
+                && fee.curve == Curve::Ed25519
+                && fee.cycles == 10));
+        // Key rotation costs as much as creating an account unless priced separately
+        assert!(fees
+            .fees
+            .iter()
+            .any(|fee| fee.operation == FeeOperation::RotateKey && fee.cycles == 0));
+        assert_eq!(fees.ledger, Some(cycles_ledger()));
+    }
+}
@@ -0,0 +1,211 @@
+use crate::application::dtos::account_messages::*;
+use crate::application::dtos::account_reply::AccountReply;
+use crate::application::services::audit_service::AuditService;
+use crate::domain::models::audit_event::AuditEventKind;
+use crate::domain::models::inheritance::InheritancePlan;
+use crate::domain::repositories::account_repository::IAccountRepository;
+use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::utils::ic::api::get_ic_api;
+
+/// Page size used when sweeping all accounts for inactive owners
+const ACCOUNT_SCAN_PAGE_SIZE: usize = 100;
+
+pub struct InheritanceService {
+    account_repository: AccountRepositoryImpl,
+    audit_service: AuditService,
+}
+
+impl InheritanceService {
+    pub fn new(account_repository: AccountRepositoryImpl, audit_service: AuditService) -> Self {
+        Self {
+            account_repository,
+            audit_service,
+        }
+    }
+
+    pub fn set_inheritance(
+        &self,
+        request: SetInheritanceRequest,
+    ) -> Result<SetInheritanceResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        // No beneficiary removes the inheritance plan
+        let plan = match request.beneficiary {
+            Some(beneficiary) => Some(InheritancePlan::new(
+                beneficiary,
+                request.inactivity_period_seconds,
+            )?),
+            None => None,
+        };
+        account.set_inheritance(plan.clone())?;
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+
+        let message = match &plan {
+            Some(plan) => format!(
+                "Beneficiary {} can claim the account after {} seconds of owner inactivity",
+                plan.beneficiary(),
+                plan.inactivity_period_seconds()
+            ),
+            None => "Inheritance plan removed".to_string(),
+        };
+        self.audit_service.record(
+            updated_account.id(),
+            AuditEventKind::InheritanceConfigured,
+            get_ic_api().caller(),
+            message,
+        )?;
+
+        Ok(SetInheritanceResponse {
+            account: AccountReply::from(&updated_account),
+        })
+    }
+
+    pub fn claim_inheritance(
+        &self,
+        request: ClaimInheritanceRequest,
+    ) -> Result<ClaimInheritanceResponse, String> {
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        let previous_owner = *account.owner();
+        account.claim_inheritance()?;
+        // update the account in the repository
+        let updated_account = self.account_repository.insert(account.clone())?;
+
+        self.audit_service.record(
+            updated_account.id(),
+            AuditEventKind::InheritanceClaimed,
+            get_ic_api().caller(),
+            format!(
+                "Account claimed from inactive owner {} by beneficiary {}",
+                previous_owner,
+                updated_account.owner()
+            ),
+        )?;
+
+        Ok(ClaimInheritanceResponse {
+            account: AccountReply::from(&updated_account),
+        })
+    }
+
+    /// Record an inactivity notice for every account of a page that became claimable by its
+    /// beneficiary
+    ///
+    /// Sweeping one page per call bounds the work of a single message. Returns the number of
+    /// notices issued and the ID of the last account swept, `None` once the last page was swept
+    /// so the next sweep starts over.
+    pub fn notify_inactive_owners(
+        &self,
+        after: Option<&str>,
+    ) -> Result<(usize, Option<String>), String> {
+        let ic_api = get_ic_api();
+        let now = ic_api.time();
+
+        let accounts = self
+            .account_repository
+            .scan_after(after, ACCOUNT_SCAN_PAGE_SIZE)?;
+        let next = match accounts.last() {
+            Some(last) if accounts.len() == ACCOUNT_SCAN_PAGE_SIZE => Some(last.id().clone()),
+            _ => None,
+        };
+
+        let mut notices = 0;
+        for mut account in accounts {
+            if !account.mark_inactivity_notified(now) {
+                continue;
+            }
+            let updated_account = self.account_repository.insert(account)?;
+            let beneficiary = match updated_account.inheritance() {
+                Some(plan) => plan.beneficiary().to_string(),
+                None => continue,
+            };
+            self.audit_service.record(
+                updated_account.id(),
+                AuditEventKind::InactivityNotice,
+                ic_api.id(),
+                format!(
+                    "Owner {} has been inactive for the inheritance period, beneficiary {} can claim the account",
+                    updated_account.owner(),
+                    beneficiary
+                ),
+            )?;
+            notices += 1;
+        }
+
+        Ok((notices, next))
+    }
+}
+
+#[cfg(test)]
+mod inheritance_service_tests {
+    use atp_caip::curve::Curve;
+    use candid::Principal;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::domain::models::account::Account;
+    use crate::domain::models::inheritance::MIN_INACTIVITY_PERIOD_SECONDS;
+    use crate::domain::models::signer::SignatureAlgorithm;
+    use crate::infrastructure::repositories::audit_event_repository_impl::AuditEventRepositoryImpl;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    const NOW: u64 = 1_000_000;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn set_caller(caller: Principal, time: u64) {
+        set_ic_api(Rc::new(
+            MockIcApi::new().with_caller(caller).with_time(time),
+        ));
+    }
+
+    #[test]
+    fn test_notify_inactive_owners_one_page_at_a_time() {
+        set_caller(principal(1), NOW);
+        AccountRepositoryImpl::init().expect("Failed to initialize repository");
+        AuditEventRepositoryImpl::init().expect("Failed to initialize repository");
+        let service = InheritanceService::new(
+            AccountRepositoryImpl::new(),
+            AuditService::new(AuditEventRepositoryImpl::new()),
+        );
+
+        // One page of accounts without a plan, followed by one with a plan
+        for index in 0..=ACCOUNT_SCAN_PAGE_SIZE {
+            let mut account = Account::new(
+                format!("account-{:03}", index),
+                principal(1),
+                vec![1, 2, 3],
+                SignatureAlgorithm::Ecdsa,
+                Curve::Secp256k1,
+                principal(1),
+            );
+            if index == ACCOUNT_SCAN_PAGE_SIZE {
+                let plan = InheritancePlan::new(principal(2), MIN_INACTIVITY_PERIOD_SECONDS);
+                account.set_inheritance(Some(plan.unwrap())).unwrap();
+            }
+            AccountRepositoryImpl::new().insert(account).unwrap();
+        }
+
+        set_caller(
+            principal(3),
+            NOW + MIN_INACTIVITY_PERIOD_SECONDS * 1_000_000_000,
+        );
+        let last_of_first_page = format!("account-{:03}", ACCOUNT_SCAN_PAGE_SIZE - 1);
+        assert_eq!(
+            service.notify_inactive_owners(None),
+            Ok((0, Some(last_of_first_page.clone())))
+        );
+        // The last page ends the sweep, the next one starts over
+        assert_eq!(
+            service.notify_inactive_owners(Some(&last_of_first_page)),
+            Ok((1, None))
+        );
+        assert_eq!(
+            service.notify_inactive_owners(Some(&last_of_first_page)),
+            Ok((0, None))
+        );
+    }
+}
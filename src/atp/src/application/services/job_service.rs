@@ -0,0 +1,36 @@
+use crate::application::dtos::job_messages::*;
+use crate::application::dtos::job_reply::JobReply;
+use crate::domain::models::job::Job;
+use crate::domain::repositories::job_repository::IJobRepository;
+use crate::infrastructure::repositories::job_repository_impl::JobRepositoryImpl;
+
+pub struct JobService {
+    job_repository: JobRepositoryImpl,
+}
+
+impl JobService {
+    pub fn new(job_repository: JobRepositoryImpl) -> Self {
+        Self { job_repository }
+    }
+
+    // Convert domain model to DTO
+    pub fn to_job_reply(&self, job: &Job) -> JobReply {
+        JobReply {
+            name: job.name().clone(),
+            schedule: job.schedule().clone(),
+            registered_at: *job.registered_at(),
+            last_run_at: *job.last_run_at(),
+            last_success_at: *job.last_success_at(),
+            last_error: job.last_error().clone(),
+            run_count: *job.run_count(),
+            failure_count: *job.failure_count(),
+        }
+    }
+
+    pub fn list_jobs(&self) -> Result<ListJobsResponse, String> {
+        let jobs = self.job_repository.find_all()?;
+        Ok(ListJobsResponse {
+            jobs: jobs.iter().map(|job| self.to_job_reply(job)).collect(),
+        })
+    }
+}
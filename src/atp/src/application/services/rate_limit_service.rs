@@ -0,0 +1,216 @@
+use candid::Principal;
+
+use crate::domain::models::rate_limit::{RateLimitOperation, RateLimitSettings, TokenBucket};
+use crate::domain::repositories::canister_config_repository::ICanisterConfigRepository;
+use crate::domain::repositories::rate_limit_repository::IRateLimitRepository;
+use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+use crate::infrastructure::repositories::rate_limit_repository_impl::RateLimitRepositoryImpl;
+use crate::utils::ic::api::get_ic_api;
+
+pub struct RateLimitService {
+    canister_config_repository: CanisterConfigRepositoryImpl,
+    rate_limit_repository: RateLimitRepositoryImpl,
+}
+
+impl RateLimitService {
+    pub fn new(
+        canister_config_repository: CanisterConfigRepositoryImpl,
+        rate_limit_repository: RateLimitRepositoryImpl,
+    ) -> Self {
+        Self {
+            canister_config_repository,
+            rate_limit_repository,
+        }
+    }
+
+    /// Reject callers that cannot be told apart, as every anonymous call shares one principal
+    pub fn check_caller(&self, caller: &Principal) -> Result<(), String> {
+        if *caller == Principal::anonymous() {
+            return Err("Anonymous callers are not allowed".to_string());
+        }
+        Ok(())
+    }
+
+    /// Take a token from the bucket of the caller for the operation
+    pub fn acquire(&self, caller: Principal, operation: RateLimitOperation) -> Result<(), String> {
+        self.check_caller(&caller)?;
+        let Some(limit) = self.rate_limits().limit(&operation).cloned() else {
+            return Ok(());
+        };
+
+        let now = get_ic_api().time();
+        let mut bucket = self
+            .rate_limit_repository
+            .get(&caller, &operation)
+            .unwrap_or_else(|| TokenBucket::new(&limit, now));
+        let result = bucket.acquire(&limit, now);
+        self.rate_limit_repository.insert(caller, operation, bucket);
+        result
+    }
+
+    /// Check that the caller could take a token, without taking it
+    pub fn check(&self, caller: &Principal, operation: &RateLimitOperation) -> Result<(), String> {
+        self.check_caller(caller)?;
+        let (Some(limit), Some(mut bucket)) = (
+            self.rate_limits().limit(operation).cloned(),
+            self.rate_limit_repository.get(caller, operation),
+        ) else {
+            return Ok(());
+        };
+        bucket.acquire(&limit, get_ic_api().time())
+    }
+
+    /// Accounts a single principal can own, if limited
+    pub fn max_accounts_per_owner(&self) -> Option<u32> {
+        self.rate_limits().max_accounts_per_owner
+    }
+
+    /// Reject ingress messages that would obviously fail, before they are executed
+    ///
+    /// Runs on a single replica, so the buckets are only read.
+    pub fn inspect_ingress(&self, method: &str, caller: &Principal) -> Result<(), String> {
+        self.check_caller(caller)?;
+        match method {
+            "create_account" => self.check(caller, &RateLimitOperation::CreateAccount),
+            "sign" | "sign_eip1559_transaction" => self.check(caller, &RateLimitOperation::Sign),
+            "rotate_key" => self.check(caller, &RateLimitOperation::RotateKey),
+            _ => Ok(()),
+        }
+    }
+
+    /// Drop the buckets that refilled completely, returning how many were dropped
+    pub fn cleanup(&self) -> usize {
+        let rate_limits = self.rate_limits();
+        let now = get_ic_api().time();
+        let mut removed = 0;
+        for (caller, operation, bucket) in self.rate_limit_repository.find_all() {
+            let idle = match rate_limits.limit(&operation) {
+                Some(limit) => bucket.is_full(limit, now),
+                None => true,
+            };
+            if idle {
+                self.rate_limit_repository.remove(&caller, &operation);
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    // Limits in use, canisters without a stored configuration are not limited
+    fn rate_limits(&self) -> RateLimitSettings {
+        self.canister_config_repository
+            .get()
+            .map(|config| config.rate_limits())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod rate_limit_service_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::domain::models::canister_config::CanisterConfig;
+    use crate::domain::models::rate_limit::RateLimit;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    const SECOND: u64 = 1_000_000_000;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    // Helper function to set up a service allowing 2 account creations, one more every minute
+    fn setup() -> RateLimitService {
+        set_ic_api(Rc::new(MockIcApi::new().with_time(0)));
+        CanisterConfigRepositoryImpl::init().expect("Failed to initialize repository");
+        let mut config = CanisterConfig::new("key".to_string(), Default::default(), 0).unwrap();
+        config
+            .set_rate_limits(
+                RateLimitSettings {
+                    create_account: Some(RateLimit {
+                        capacity: 2,
+                        refill_seconds: 60,
+                    }),
+                    sign: None,
+                    rotate_key: Some(RateLimit {
+                        capacity: 1,
+                        refill_seconds: 60,
+                    }),
+                    max_accounts_per_owner: Some(3),
+                },
+                0,
+            )
+            .unwrap();
+        CanisterConfigRepositoryImpl::new().insert(config).unwrap();
+        RateLimitService::new(
+            CanisterConfigRepositoryImpl::new(),
+            RateLimitRepositoryImpl::new(),
+        )
+    }
+
+    #[test]
+    fn test_buckets_are_per_principal_and_operation() {
+        let service = setup();
+        let create = RateLimitOperation::CreateAccount;
+
+        service.acquire(principal(1), create.clone()).unwrap();
+        service.acquire(principal(1), create.clone()).unwrap();
+        assert!(service.acquire(principal(1), create.clone()).is_err());
+        assert!(service
+            .inspect_ingress("create_account", &principal(1))
+            .is_err());
+
+        // Other principals and unlimited operations are not affected
+        service.acquire(principal(2), create.clone()).unwrap();
+        for _ in 0..10 {
+            service
+                .acquire(principal(1), RateLimitOperation::Sign)
+                .unwrap();
+        }
+        assert!(service.inspect_ingress("sign", &principal(1)).is_ok());
+        assert!(service.inspect_ingress("rotate_key", &principal(1)).is_ok());
+        service
+            .acquire(principal(1), RateLimitOperation::RotateKey)
+            .unwrap();
+        assert!(service
+            .inspect_ingress("rotate_key", &principal(1))
+            .is_err());
+
+        // Anonymous callers are always rejected
+        assert!(service
+            .acquire(Principal::anonymous(), RateLimitOperation::Sign)
+            .is_err());
+        assert!(service
+            .inspect_ingress("get_fees", &Principal::anonymous())
+            .is_err());
+    }
+
+    #[test]
+    fn test_cleanup_drops_refilled_buckets() {
+        let service = setup();
+        service
+            .acquire(principal(1), RateLimitOperation::CreateAccount)
+            .unwrap();
+        service
+            .acquire(principal(2), RateLimitOperation::CreateAccount)
+            .unwrap();
+        service
+            .acquire(principal(2), RateLimitOperation::CreateAccount)
+            .unwrap();
+
+        // After one interval only the bucket of the first principal is full again
+        set_ic_api(Rc::new(MockIcApi::new().with_time(60 * SECOND)));
+        assert_eq!(service.cleanup(), 1);
+        assert!(RateLimitRepositoryImpl::new()
+            .get(&principal(1), &RateLimitOperation::CreateAccount)
+            .is_none());
+        assert!(service
+            .acquire(principal(2), RateLimitOperation::CreateAccount)
+            .is_ok());
+        assert!(service
+            .acquire(principal(2), RateLimitOperation::CreateAccount)
+            .is_err());
+    }
+}
@@ -0,0 +1,431 @@
+use atp_caip::ChainId;
+use atp_chain_registry::{AssetConfig, ChainConfig, RegistryConfig};
+use std::str::FromStr;
+
+use crate::application::dtos::registry::{AssetConfigDTO, ChainConfigDTO, TokenPairDTO};
+use crate::application::dtos::registry_messages::*;
+use crate::application::dtos::registry_reply::{RegistryReply, SupportedChainReply};
+use crate::domain::models::registry::{registry_config, registry_entries, RegistryEntry};
+use crate::domain::repositories::registry_repository::IRegistryRepository;
+use crate::infrastructure::repositories::registry_repository_impl::RegistryRepositoryImpl;
+use crate::utils::ic::api::get_ic_api;
+
+pub struct RegistryService {
+    registry_repository: RegistryRepositoryImpl,
+}
+
+impl RegistryService {
+    pub fn new(registry_repository: RegistryRepositoryImpl) -> Self {
+        Self {
+            registry_repository,
+        }
+    }
+
+    // Convert domain model to DTO
+    pub fn to_registry_reply(&self, config: &RegistryConfig) -> RegistryReply {
+        let mut chains: Vec<ChainConfigDTO> = config.chains.values().map(Into::into).collect();
+        chains.sort_by(|a, b| a.chain_id.cmp(&b.chain_id));
+        let mut assets: Vec<AssetConfigDTO> = config.assets.values().map(Into::into).collect();
+        assets.sort_by(|a, b| a.asset_id_base.cmp(&b.asset_id_base));
+
+        RegistryReply {
+            chains,
+            assets,
+            token_pairs: config.token_pairs.iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Store the registry unless one is already persisted
+    pub fn seed(&self, config: RegistryConfig) -> Result<(), String> {
+        if !self.registry_repository.find_all()?.is_empty() {
+            return Ok(());
+        }
+        config
+            .validate()
+            .map_err(|e| format!("Invalid chain registry: {}", e))?;
+        for entry in registry_entries(&config, get_ic_api().time())? {
+            self.registry_repository.insert(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Replace the stored registry, tombstoning the entries missing from the new one
+    pub fn replace(&self, config: RegistryConfig) -> Result<(), String> {
+        config
+            .validate()
+            .map_err(|e| format!("Invalid chain registry: {}", e))?;
+
+        let now = get_ic_api().time();
+        let entries = registry_entries(&config, now)?;
+        for mut stale in self.registry_repository.find_all()? {
+            let replaced = entries
+                .iter()
+                .any(|entry| entry.kind() == stale.kind() && entry.key() == stale.key());
+            if !replaced && !stale.removed() {
+                stale.remove(now);
+                self.registry_repository.insert(stale)?;
+            }
+        }
+        for entry in entries {
+            self.registry_repository.insert(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Load the current registry configuration
+    pub fn load(&self) -> Result<RegistryConfig, String> {
+        registry_config(&self.registry_repository.find_all()?)
+    }
+
+    pub fn get_registry(&self) -> Result<GetRegistryResponse, String> {
+        let config = self.load()?;
+        Ok(GetRegistryResponse {
+            registry: self.to_registry_reply(&config),
+        })
+    }
+
+    pub fn list_supported_chains(&self) -> Result<ListSupportedChainsResponse, String> {
+        let config = self.load()?;
+
+        let mut chains: Vec<SupportedChainReply> = config
+            .chains
+            .values()
+            .map(|chain| SupportedChainReply {
+                chain_id: chain.chain_id.clone(),
+                name: chain.name.clone(),
+                native_asset: chain.native_asset.clone(),
+                cryptographic_curve: chain.cryptographic_curve.clone(),
+                is_testnet: chain.is_testnet,
+            })
+            .collect();
+        chains.sort_by(|a, b| a.chain_id.cmp(&b.chain_id));
+        Ok(ListSupportedChainsResponse { chains })
+    }
+
+    pub fn add_chain(&self, request: AddChainRequest) -> Result<UpdateRegistryResponse, String> {
+        let chain = parse_chain(request.chain)?;
+        self.update(|config, now| {
+            if config.chains.contains_key(&chain.chain_id) {
+                return Err(format!("Chain {} already exists", chain.chain_id));
+            }
+            let entry = RegistryEntry::chain(&chain, now)?;
+            config.chains.insert(chain.chain_id.clone(), chain);
+            Ok(entry)
+        })
+    }
+
+    pub fn update_chain(
+        &self,
+        request: UpdateChainRequest,
+    ) -> Result<UpdateRegistryResponse, String> {
+        let chain = parse_chain(request.chain)?;
+        self.update(|config, now| {
+            if !config.chains.contains_key(&chain.chain_id) {
+                return Err(format!("Chain {} not found", chain.chain_id));
+            }
+            let entry = RegistryEntry::chain(&chain, now)?;
+            config.chains.insert(chain.chain_id.clone(), chain);
+            Ok(entry)
+        })
+    }
+
+    pub fn remove_chain(
+        &self,
+        request: RemoveChainRequest,
+    ) -> Result<UpdateRegistryResponse, String> {
+        self.update(|config, now| {
+            let chain = config
+                .chains
+                .remove(&request.chain_id)
+                .ok_or_else(|| format!("Chain {} not found", request.chain_id))?;
+            // Token pairs would otherwise keep trading on the removed chain
+            if let Some(pair) = config.token_pairs.iter().find(|pair| {
+                pair.from_asset.chain_id().to_string() == request.chain_id
+                    || pair.to_asset.chain_id().to_string() == request.chain_id
+            }) {
+                return Err(format!(
+                    "Chain {} is used by token pair {}",
+                    request.chain_id,
+                    pair.to_pair_string()
+                ));
+            }
+            let mut entry = RegistryEntry::chain(&chain, now)?;
+            entry.remove(now);
+            Ok(entry)
+        })
+    }
+
+    pub fn add_asset(&self, request: AddAssetRequest) -> Result<UpdateRegistryResponse, String> {
+        let asset = AssetConfig::try_from(request.asset)?;
+        self.update(|config, now| {
+            let entry = RegistryEntry::asset(&asset, now)?;
+            if config.assets.contains_key(entry.key()) {
+                return Err(format!("Asset {} already exists", entry.key()));
+            }
+            config.assets.insert(entry.key().clone(), asset);
+            Ok(entry)
+        })
+    }
+
+    pub fn update_asset(
+        &self,
+        request: UpdateAssetRequest,
+    ) -> Result<UpdateRegistryResponse, String> {
+        let asset = AssetConfig::try_from(request.asset)?;
+        self.update(|config, now| {
+            let entry = RegistryEntry::asset(&asset, now)?;
+            if !config.assets.contains_key(entry.key()) {
+                return Err(format!("Asset {} not found", entry.key()));
+            }
+            config.assets.insert(entry.key().clone(), asset);
+            Ok(entry)
+        })
+    }
+
+    pub fn remove_asset(
+        &self,
+        request: RemoveAssetRequest,
+    ) -> Result<UpdateRegistryResponse, String> {
+        self.update(|config, now| {
+            let asset = config
+                .assets
+                .remove(&request.asset_id_base)
+                .ok_or_else(|| format!("Asset {} not found", request.asset_id_base))?;
+            let mut entry = RegistryEntry::asset(&asset, now)?;
+            entry.remove(now);
+            Ok(entry)
+        })
+    }
+
+    pub fn add_token_pair(
+        &self,
+        request: AddTokenPairRequest,
+    ) -> Result<UpdateRegistryResponse, String> {
+        self.update(|config, now| {
+            if find_token_pair(config, &request.token_pair).is_some() {
+                return Err(format!(
+                    "Token pair {}-{} already exists",
+                    request.token_pair.from_asset, request.token_pair.to_asset
+                ));
+            }
+            let pair = request.token_pair.into();
+            let entry = RegistryEntry::token_pair(&pair, now)?;
+            config.token_pairs.push(pair);
+            Ok(entry)
+        })
+    }
+
+    pub fn update_token_pair(
+        &self,
+        request: UpdateTokenPairRequest,
+    ) -> Result<UpdateRegistryResponse, String> {
+        self.update(|config, now| {
+            let index = find_token_pair(config, &request.token_pair).ok_or_else(|| {
+                format!(
+                    "Token pair {}-{} not found",
+                    request.token_pair.from_asset, request.token_pair.to_asset
+                )
+            })?;
+            let pair = request.token_pair.into();
+            let entry = RegistryEntry::token_pair(&pair, now)?;
+            config.token_pairs[index] = pair;
+            Ok(entry)
+        })
+    }
+
+    pub fn remove_token_pair(
+        &self,
+        request: RemoveTokenPairRequest,
+    ) -> Result<UpdateRegistryResponse, String> {
+        self.update(|config, now| {
+            let index = config
+                .token_pairs
+                .iter()
+                .position(|pair| {
+                    pair.from_asset == request.from_asset && pair.to_asset == request.to_asset
+                })
+                .ok_or_else(|| {
+                    format!(
+                        "Token pair {}-{} not found",
+                        request.from_asset, request.to_asset
+                    )
+                })?;
+            let pair = config.token_pairs.remove(index);
+            let mut entry = RegistryEntry::token_pair(&pair, now)?;
+            entry.remove(now);
+            Ok(entry)
+        })
+    }
+
+    // Apply a change to the registry, persisting the changed entry only if the result validates
+    fn update<F>(&self, change: F) -> Result<UpdateRegistryResponse, String>
+    where
+        F: FnOnce(&mut RegistryConfig, u64) -> Result<RegistryEntry, String>,
+    {
+        let mut config = self.load()?;
+        let entry = change(&mut config, get_ic_api().time())?;
+        config
+            .validate()
+            .map_err(|e| format!("Invalid chain registry: {}", e))?;
+
+        self.registry_repository.insert(entry)?;
+        Ok(UpdateRegistryResponse {
+            registry: self.to_registry_reply(&config),
+        })
+    }
+}
+
+// Convert a chain DTO, normalizing its chain ID
+fn parse_chain(dto: ChainConfigDTO) -> Result<ChainConfig, String> {
+    let chain_id = ChainId::from_str(&dto.chain_id)
+        .map_err(|e| format!("Invalid chain ID {}: {}", dto.chain_id, e))?;
+    let mut chain = ChainConfig::try_from(dto)?;
+    chain.chain_id = chain_id.to_string();
+    Ok(chain)
+}
+
+fn find_token_pair(config: &RegistryConfig, token_pair: &TokenPairDTO) -> Option<usize> {
+    config.token_pairs.iter().position(|pair| {
+        pair.from_asset == token_pair.from_asset && pair.to_asset == token_pair.to_asset
+    })
+}
+
+#[cfg(test)]
+mod registry_service_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::application::dtos::registry::MetadataEntryDTO;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+    use atp_chain_registry::{ChainRegistry, DEFAULT_CONFIG};
+
+    fn setup() -> RegistryService {
+        set_ic_api(Rc::new(MockIcApi::new().with_time(1)));
+        RegistryRepositoryImpl::init().expect("Failed to initialize repository");
+        let service = RegistryService::new(RegistryRepositoryImpl::new());
+        service
+            .seed(ChainRegistry::config_from_toml(DEFAULT_CONFIG).unwrap())
+            .unwrap();
+        service
+    }
+
+    fn chain(chain_id: &str) -> ChainConfigDTO {
+        ChainConfigDTO {
+            chain_id: chain_id.to_string(),
+            name: "Polygon".to_string(),
+            native_asset: "slip44:966".to_string(),
+            rpc_endpoints: vec![],
+            explorer_url: None,
+            cryptographic_curve: vec![atp_caip::Curve::Secp256k1],
+            is_testnet: false,
+            assets: vec!["slip44:60".to_string()],
+            metadata: vec![MetadataEntryDTO {
+                key: "block_time".to_string(),
+                value: "2".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_chain_lifecycle() {
+        let service = setup();
+        let chains = service.list_supported_chains().unwrap().chains.len();
+
+        let response = service
+            .add_chain(AddChainRequest {
+                chain: chain("eip155:137"),
+            })
+            .unwrap();
+        assert_eq!(response.registry.chains.len(), chains + 1);
+        assert!(service
+            .add_chain(AddChainRequest {
+                chain: chain("eip155:137"),
+            })
+            .is_err());
+
+        let mut updated = chain("eip155:137");
+        updated.is_testnet = true;
+        service
+            .update_chain(UpdateChainRequest { chain: updated })
+            .unwrap();
+        let registry = service.get_registry().unwrap().registry;
+        let polygon = registry
+            .chains
+            .iter()
+            .find(|chain| chain.chain_id == "eip155:137")
+            .unwrap();
+        assert!(polygon.is_testnet);
+        assert_eq!(polygon.metadata[0].value, "2");
+
+        service
+            .remove_chain(RemoveChainRequest {
+                chain_id: "eip155:137".to_string(),
+            })
+            .unwrap();
+        assert_eq!(
+            service.list_supported_chains().unwrap().chains.len(),
+            chains
+        );
+    }
+
+    #[test]
+    fn test_replace_registry() {
+        let service = setup();
+
+        let mut config = ChainRegistry::config_from_toml(DEFAULT_CONFIG).unwrap();
+        config.token_pairs.clear();
+        config.chains.remove("solana:devnet");
+        service.replace(config).unwrap();
+
+        let registry = service.get_registry().unwrap().registry;
+        assert!(registry.token_pairs.is_empty());
+        assert!(!registry
+            .chains
+            .iter()
+            .any(|chain| chain.chain_id == "solana:devnet"));
+
+        // The registry is only seeded once
+        service
+            .seed(ChainRegistry::config_from_toml(DEFAULT_CONFIG).unwrap())
+            .unwrap();
+        assert!(service
+            .get_registry()
+            .unwrap()
+            .registry
+            .token_pairs
+            .is_empty());
+    }
+
+    #[test]
+    fn test_invalid_change_is_rejected() {
+        let service = setup();
+
+        // The default token pairs reference ETH, so it cannot be removed
+        assert!(service
+            .remove_asset(RemoveAssetRequest {
+                asset_id_base: "slip44:60".to_string(),
+            })
+            .is_err());
+        assert!(service
+            .remove_chain(RemoveChainRequest {
+                chain_id: "eip155:1".to_string(),
+            })
+            .is_err());
+        assert!(service
+            .add_chain(AddChainRequest {
+                chain: chain("not a chain"),
+            })
+            .is_err());
+
+        let registry = service.get_registry().unwrap().registry;
+        assert!(registry
+            .assets
+            .iter()
+            .any(|asset| asset.asset_id_base == "slip44:60"));
+        assert!(registry
+            .chains
+            .iter()
+            .any(|chain| chain.chain_id == "eip155:1"));
+    }
+}
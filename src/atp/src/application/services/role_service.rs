@@ -0,0 +1,180 @@
+use candid::Principal;
+
+use crate::application::dtos::role_messages::*;
+use crate::application::dtos::role_reply::RoleAssignmentReply;
+use crate::domain::models::role::{Role, RoleAssignment};
+use crate::domain::repositories::role_repository::IRoleRepository;
+use crate::infrastructure::repositories::role_repository_impl::RoleRepositoryImpl;
+use crate::utils::ic::api::get_ic_api;
+
+pub struct RoleService {
+    role_repository: RoleRepositoryImpl,
+}
+
+impl RoleService {
+    pub fn new(role_repository: RoleRepositoryImpl) -> Self {
+        Self { role_repository }
+    }
+
+    // Convert domain model to DTO
+    pub fn to_assignment_reply(&self, assignment: &RoleAssignment) -> RoleAssignmentReply {
+        RoleAssignmentReply {
+            principal: assignment.principal().to_string(),
+            roles: assignment.roles().clone(),
+            updated_at: *assignment.updated_at(),
+        }
+    }
+
+    /// Grant the Admin role to the given principals, skipping those that already hold it
+    pub fn seed_admins(&self, principals: Vec<Principal>) -> Result<(), String> {
+        let now = get_ic_api().time();
+        for principal in principals {
+            let mut assignment = self.get_assignment(principal);
+            if assignment.roles().contains(&Role::Admin) {
+                continue;
+            }
+            assignment.grant(Role::Admin, now)?;
+            self.role_repository.insert(assignment)?;
+        }
+        Ok(())
+    }
+
+    /// Check whether the principal holds the role
+    ///
+    /// Canister controllers always hold every role, so they cannot lock themselves out.
+    pub fn has_role(&self, principal: Principal, role: &Role) -> bool {
+        get_ic_api().is_controller(&principal) || self.get_assignment(principal).has_role(role)
+    }
+
+    /// Reject the call unless the caller holds the role
+    pub fn require_role(&self, role: &Role) -> Result<(), String> {
+        let caller = get_ic_api().caller();
+        if self.has_role(caller, role) {
+            Ok(())
+        } else {
+            Err(format!("Caller does not have the {:?} role", role))
+        }
+    }
+
+    pub fn grant_role(&self, request: GrantRoleRequest) -> Result<GrantRoleResponse, String> {
+        self.require_role(&Role::Admin)?;
+        if request.principal == Principal::anonymous() {
+            return Err("Anonymous principal cannot hold a role".to_string());
+        }
+
+        let mut assignment = self.get_assignment(request.principal);
+        assignment.grant(request.role, get_ic_api().time())?;
+        let updated_assignment = self.role_repository.insert(assignment)?;
+        Ok(GrantRoleResponse {
+            assignment: self.to_assignment_reply(&updated_assignment),
+        })
+    }
+
+    pub fn revoke_role(&self, request: RevokeRoleRequest) -> Result<RevokeRoleResponse, String> {
+        self.require_role(&Role::Admin)?;
+
+        let mut assignment = self.get_assignment(request.principal);
+        assignment.revoke(&request.role, get_ic_api().time())?;
+        let updated_assignment = self.role_repository.insert(assignment)?;
+        Ok(RevokeRoleResponse {
+            assignment: self.to_assignment_reply(&updated_assignment),
+        })
+    }
+
+    pub fn list_assignments(&self) -> Result<ListRoleAssignmentsResponse, String> {
+        self.require_role(&Role::Auditor)?;
+
+        let assignments = self.role_repository.find_all()?;
+        Ok(ListRoleAssignmentsResponse {
+            assignments: assignments
+                .iter()
+                .filter(|assignment| !assignment.roles().is_empty())
+                .map(|assignment| self.to_assignment_reply(assignment))
+                .collect(),
+        })
+    }
+
+    pub fn get_my_roles(&self) -> Result<GetMyRolesResponse, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+
+        let mut roles = self.get_assignment(caller).roles().clone();
+        if ic_api.is_controller(&caller) && !roles.contains(&Role::Admin) {
+            roles.push(Role::Admin);
+        }
+        Ok(GetMyRolesResponse { roles })
+    }
+
+    // Load the roles of a principal, defaulting to none
+    fn get_assignment(&self, principal: Principal) -> RoleAssignment {
+        self.role_repository
+            .get(&principal)
+            .unwrap_or_else(|_| RoleAssignment::new(principal, get_ic_api().time()))
+    }
+}
+
+#[cfg(test)]
+mod role_service_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn set_caller(caller: Principal) {
+        set_ic_api(Rc::new(
+            MockIcApi::new()
+                .with_caller(caller)
+                .with_controllers(vec![principal(100)]),
+        ));
+    }
+
+    fn setup() -> RoleService {
+        RoleRepositoryImpl::init().expect("Failed to initialize repository");
+        RoleService::new(RoleRepositoryImpl::new())
+    }
+
+    #[test]
+    fn test_seeded_admin_grants_and_revokes_roles() {
+        let service = setup();
+        set_caller(principal(2));
+        service.seed_admins(vec![principal(1)]).unwrap();
+
+        // Only admins can grant roles
+        let request = GrantRoleRequest {
+            principal: principal(2),
+            role: Role::Operator,
+        };
+        assert!(service.grant_role(request.clone()).is_err());
+
+        set_caller(principal(1));
+        service.grant_role(request).unwrap();
+        assert!(service.has_role(principal(2), &Role::Operator));
+        assert!(!service.has_role(principal(2), &Role::Auditor));
+
+        service
+            .revoke_role(RevokeRoleRequest {
+                principal: principal(2),
+                role: Role::Operator,
+            })
+            .unwrap();
+        assert!(!service.has_role(principal(2), &Role::Operator));
+    }
+
+    #[test]
+    fn test_controllers_hold_every_role() {
+        let service = setup();
+
+        set_caller(principal(100));
+        assert!(service.require_role(&Role::Admin).is_ok());
+        assert_eq!(service.get_my_roles().unwrap().roles, vec![Role::Admin]);
+
+        set_caller(principal(3));
+        assert!(service.require_role(&Role::Auditor).is_err());
+        assert!(service.list_assignments().is_err());
+    }
+}
@@ -0,0 +1,500 @@
+use atp_caip::curve::Curve;
+use ethers_core::types::transaction::eip1559::Eip1559TransactionRequest;
+use std::cell::RefCell;
+
+use crate::application::dtos::signing_proposal_messages::*;
+use crate::application::dtos::signing_proposal_reply::SigningProposalReply;
+use crate::application::services::fee_service::FeeService;
+use crate::domain::models::account::{Account, AccountState};
+use crate::domain::models::fee::FeeOperation;
+use crate::domain::models::signer::SignatureAlgorithm;
+use crate::domain::models::signing_proposal::{
+    SigningPayload, SigningProposal, SigningProposalStatus,
+};
+use crate::domain::repositories::account_repository::IAccountRepository;
+use crate::domain::repositories::signer_repository::ISignerRepository;
+use crate::domain::repositories::signing_proposal_repository::ISigningProposalRepository;
+use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::infrastructure::repositories::signer_repository_impl::SignerRepositoryImpl;
+use crate::infrastructure::repositories::signing_proposal_repository_impl::SigningProposalRepositoryImpl;
+use crate::utils::eth_utils::sha256;
+use crate::utils::ic::api::get_ic_api;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Proposals expire after one day unless the proposer asks otherwise
+const DEFAULT_PROPOSAL_TTL_SECONDS: u64 = 24 * 60 * 60;
+/// Proposals cannot stay open for more than a week
+const MAX_PROPOSAL_TTL_SECONDS: u64 = 7 * 24 * 60 * 60;
+/// Page size used when sweeping the proposals of an account
+const PROPOSAL_SCAN_PAGE_SIZE: usize = 100;
+
+thread_local! {
+    // Disambiguates proposal IDs created by the same caller within one round
+    static PROPOSAL_NONCE: RefCell<u64> = const { RefCell::new(0) };
+}
+
+pub struct SigningProposalService {
+    account_repository: AccountRepositoryImpl,
+    signing_proposal_repository: SigningProposalRepositoryImpl,
+    signer_repository: SignerRepositoryImpl,
+    fee_service: FeeService,
+}
+
+impl SigningProposalService {
+    pub fn new(
+        account_repository: AccountRepositoryImpl,
+        signing_proposal_repository: SigningProposalRepositoryImpl,
+        signer_repository: SignerRepositoryImpl,
+        fee_service: FeeService,
+    ) -> Self {
+        Self {
+            account_repository,
+            signing_proposal_repository,
+            signer_repository,
+            fee_service,
+        }
+    }
+
+    // Convert domain model to DTO
+    pub fn to_proposal_reply(
+        &self,
+        proposal: &SigningProposal,
+        account: &Account,
+    ) -> SigningProposalReply {
+        SigningProposalReply {
+            id: proposal.id().clone(),
+            account_id: proposal.account_id().clone(),
+            proposer: proposal.proposer().to_string(),
+            payload: proposal.payload().clone(),
+            approvals: proposal
+                .approvals()
+                .iter()
+                .map(|approver| approver.to_string())
+                .collect(),
+            threshold: match account.guardians() {
+                Some(guardian_set) => *guardian_set.threshold(),
+                None => 0,
+            },
+            status: proposal.status().clone(),
+            created_at: *proposal.created_at(),
+            expires_at: *proposal.expires_at(),
+            signature: proposal.signature().clone(),
+        }
+    }
+
+    pub fn create_proposal(
+        &self,
+        request: CreateSigningProposalRequest,
+    ) -> Result<CreateSigningProposalResponse, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        // Check if the caller is the owner of the account
+        if !account.is_owner(caller) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        // Check if the account is active
+        if account.account_state().clone() != AccountState::Active {
+            return Err("Account is not activated".to_string());
+        }
+        if !account.requires_guardian_approval() {
+            return Err("Account does not require guardian approval".to_string());
+        }
+        validate_payload(&account, &request.payload)?;
+
+        let ttl_seconds = request.ttl_seconds.unwrap_or(DEFAULT_PROPOSAL_TTL_SECONDS);
+        if ttl_seconds == 0 || ttl_seconds > MAX_PROPOSAL_TTL_SECONDS {
+            return Err(format!(
+                "Proposal TTL must be between 1 and {} seconds",
+                MAX_PROPOSAL_TTL_SECONDS
+            ));
+        }
+
+        // Generate a unique proposal ID
+        let now = ic_api.time();
+        let nonce = PROPOSAL_NONCE.with(|nonce| {
+            let mut nonce = nonce.borrow_mut();
+            *nonce += 1;
+            *nonce
+        });
+        let id_string = format!("{}{}{}{}", account.id(), caller, now, nonce);
+        let id = hex::encode(sha256(&id_string));
+
+        let proposal = SigningProposal::new(
+            id,
+            &account,
+            caller,
+            request.payload,
+            now,
+            now + ttl_seconds * NANOS_PER_SECOND,
+        );
+        let created_proposal = self.signing_proposal_repository.insert(proposal)?;
+        account.record_activity();
+        self.account_repository.insert(account.clone())?;
+        Ok(CreateSigningProposalResponse {
+            proposal: self.to_proposal_reply(&created_proposal, &account),
+        })
+    }
+
+    pub async fn approve_proposal(
+        &self,
+        request: ApproveSigningProposalRequest,
+    ) -> Result<ApproveSigningProposalResponse, String> {
+        // Check if the account and proposal exist
+        let account = self.account_repository.get(&request.account_id)?;
+        let mut proposal = self
+            .signing_proposal_repository
+            .get(&request.account_id, &request.proposal_id)?;
+
+        // Record the approval, persisting the proposal if it turned out to be expired or stale
+        if let Err(e) = proposal.approve(&account) {
+            if proposal.status() != &SigningProposalStatus::Pending {
+                self.signing_proposal_repository.insert(proposal)?;
+            }
+            return Err(e);
+        }
+        let mut proposal = self.signing_proposal_repository.insert(proposal)?;
+
+        // Produce the threshold signature once enough guardians approved
+        if proposal.status() == &SigningProposalStatus::Approved {
+            proposal = self.execute(&account, proposal).await?;
+        }
+
+        Ok(ApproveSigningProposalResponse {
+            proposal: self.to_proposal_reply(&proposal, &account),
+        })
+    }
+
+    /// Retry signing an approved proposal whose signature could not be produced
+    pub async fn execute_proposal(
+        &self,
+        request: ExecuteSigningProposalRequest,
+    ) -> Result<ExecuteSigningProposalResponse, String> {
+        let caller = get_ic_api().caller();
+
+        // Check if the account and proposal exist
+        let account = self.account_repository.get(&request.account_id)?;
+        let proposal = self
+            .signing_proposal_repository
+            .get(&request.account_id, &request.proposal_id)?;
+
+        if !account.is_owner(caller) && !account.is_guardian(caller) {
+            return Err("Caller is neither the owner nor a guardian of the account".to_string());
+        }
+
+        let proposal = self.execute(&account, proposal).await?;
+        Ok(ExecuteSigningProposalResponse {
+            proposal: self.to_proposal_reply(&proposal, &account),
+        })
+    }
+
+    pub fn cancel_proposal(
+        &self,
+        request: CancelSigningProposalRequest,
+    ) -> Result<CancelSigningProposalResponse, String> {
+        // Check if the account and proposal exist
+        let account = self.account_repository.get(&request.account_id)?;
+        let mut proposal = self
+            .signing_proposal_repository
+            .get(&request.account_id, &request.proposal_id)?;
+
+        proposal.cancel()?;
+        let updated_proposal = self.signing_proposal_repository.insert(proposal)?;
+        Ok(CancelSigningProposalResponse {
+            proposal: self.to_proposal_reply(&updated_proposal, &account),
+        })
+    }
+
+    /// Mark every open proposal of the account that passed its expiry time as expired
+    pub fn expire_proposals(
+        &self,
+        request: ExpireSigningProposalsRequest,
+    ) -> Result<ExpireSigningProposalsResponse, String> {
+        // Check if the account exists
+        let account = self.account_repository.get(&request.account_id)?;
+        let now = get_ic_api().time();
+
+        let mut expired = Vec::new();
+        let mut page = 1;
+        // The partition query errors once no further page exists
+        while let Ok(proposals) = self.signing_proposal_repository.find_by_account(
+            account.id(),
+            PROPOSAL_SCAN_PAGE_SIZE,
+            page,
+        ) {
+            for mut proposal in proposals {
+                if proposal.is_open() && proposal.is_expired(now) {
+                    proposal.expire()?;
+                    let updated_proposal = self.signing_proposal_repository.insert(proposal)?;
+                    expired.push(self.to_proposal_reply(&updated_proposal, &account));
+                }
+            }
+            page += 1;
+        }
+
+        Ok(ExpireSigningProposalsResponse { expired })
+    }
+
+    pub fn get_proposal(
+        &self,
+        request: GetSigningProposalRequest,
+    ) -> Result<GetSigningProposalResponse, String> {
+        let account = self.account_repository.get(&request.account_id)?;
+        let proposal = self
+            .signing_proposal_repository
+            .get(&request.account_id, &request.proposal_id)?;
+        Ok(GetSigningProposalResponse {
+            proposal: self.to_proposal_reply(&proposal, &account),
+        })
+    }
+
+    pub fn list_proposals(
+        &self,
+        request: ListSigningProposalsRequest,
+    ) -> Result<ListSigningProposalsResponse, String> {
+        let account = self.account_repository.get(&request.account_id)?;
+        let proposals = self.signing_proposal_repository.find_by_account(
+            &request.account_id,
+            request.page_size as usize,
+            request.page as usize,
+        )?;
+        Ok(ListSigningProposalsResponse {
+            proposals: proposals
+                .iter()
+                .map(|proposal| self.to_proposal_reply(proposal, &account))
+                .collect(),
+        })
+    }
+
+    // Sign the payload of an approved proposal and store the resulting signature
+    async fn execute(
+        &self,
+        account: &Account,
+        mut proposal: SigningProposal,
+    ) -> Result<SigningProposal, String> {
+        // Close proposals that expired or whose account changed owner, key or guardians
+        let status = proposal.status().clone();
+        if let Err(e) = proposal.check_executable(account) {
+            if proposal.status() != &status {
+                self.signing_proposal_repository.insert(proposal)?;
+            }
+            return Err(e);
+        }
+
+        // Check if the account is still active
+        if account.account_state().clone() != AccountState::Active {
+            return Err("Account is not activated".to_string());
+        }
+
+        // The caller that triggers the signature pays its fee
+        let charge = self
+            .fee_service
+            .charge(FeeOperation::Sign, account.curve())?;
+        let signature = self
+            .fee_service
+            .complete(charge, self.sign_payload(account, proposal.payload()).await)?;
+
+        // Reload the proposal so a concurrent cancellation is not overwritten
+        let mut current = self
+            .signing_proposal_repository
+            .get(proposal.account_id(), proposal.id())?;
+        if current.status() != &SigningProposalStatus::Approved {
+            return Err("Proposal is no longer approved".to_string());
+        }
+        current.mark_executed(signature)?;
+        self.signing_proposal_repository.insert(current)
+    }
+
+    // Produce the signature requested by the payload
+    async fn sign_payload(
+        &self,
+        account: &Account,
+        payload: &SigningPayload,
+    ) -> Result<String, String> {
+        let signature = match payload {
+            SigningPayload::Message { message_hex } => {
+                let message_bytes =
+                    hex::decode(message_hex).map_err(|_| "Invalid hex string".to_string())?;
+                let signature = self
+                    .signer_repository
+                    .sign(
+                        account.algorithm().clone(),
+                        account.curve().clone(),
+                        message_bytes,
+                        account.derivation_path(account.key_version()),
+                    )
+                    .await?;
+                hex::encode(signature.signature)
+            }
+            SigningPayload::Eip1559Transaction { tx_request } => {
+                let tx = Eip1559TransactionRequest::try_from(tx_request.clone())?;
+                self.signer_repository
+                    .sign_eip1559_transaction(tx, account.derivation_path(account.key_version()))
+                    .await?
+            }
+        };
+        Ok(signature)
+    }
+}
+
+// Check that the payload can be signed by the account's algorithm and curve
+fn validate_payload(account: &Account, payload: &SigningPayload) -> Result<(), String> {
+    match payload {
+        SigningPayload::Message { message_hex } => {
+            hex::decode(message_hex).map_err(|_| "Invalid hex string".to_string())?;
+        }
+        SigningPayload::Eip1559Transaction { tx_request } => {
+            // Check if the signature algorithm is ECDSA
+            if account.algorithm().clone() != SignatureAlgorithm::Ecdsa {
+                return Err("Signature algorithm is not ECDSA".to_string());
+            }
+            // Check if the curve is secp256k1
+            if account.curve().clone() != Curve::Secp256k1 {
+                return Err("Curve is not secp256k1".to_string());
+            }
+            Eip1559TransactionRequest::try_from(tx_request.clone())?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod signing_proposal_service_tests {
+    use candid::Principal;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::domain::models::guardian::GuardianSet;
+    use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+    use crate::infrastructure::repositories::fee_account_repository_impl::FeeAccountRepositoryImpl;
+    use crate::infrastructure::repositories::payment_repository_impl::PaymentRepositoryImpl;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    const NOW: u64 = 1_000_000;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn set_caller(caller: Principal, time: u64) {
+        set_ic_api(Rc::new(
+            MockIcApi::new().with_caller(caller).with_time(time),
+        ));
+    }
+
+    // Helper function to set up a service with an active account guarded by one guardian
+    fn setup() -> SigningProposalService {
+        set_caller(principal(1), NOW);
+        AccountRepositoryImpl::init().expect("Failed to initialize repository");
+        SigningProposalRepositoryImpl::init().expect("Failed to initialize repository");
+        CanisterConfigRepositoryImpl::init().expect("Failed to initialize repository");
+        FeeAccountRepositoryImpl::init().expect("Failed to initialize repository");
+
+        let mut account = Account::new(
+            "account-1".to_string(),
+            principal(1),
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            principal(1),
+        );
+        account.unlock().unwrap();
+        account.activate().unwrap();
+        account
+            .set_guardians(Some(GuardianSet::new(vec![principal(2)], 1).unwrap()))
+            .unwrap();
+        AccountRepositoryImpl::new().insert(account).unwrap();
+
+        SigningProposalService::new(
+            AccountRepositoryImpl::new(),
+            SigningProposalRepositoryImpl::new(),
+            SignerRepositoryImpl::new("key".to_string()),
+            FeeService::new(
+                CanisterConfigRepositoryImpl::new(),
+                FeeAccountRepositoryImpl::new(),
+                PaymentRepositoryImpl::new(),
+            ),
+        )
+    }
+
+    // Store an approved proposal, as left behind by an approval whose signature failed
+    fn insert_approved_proposal(service: &SigningProposalService, expires_at: u64) -> String {
+        let account = service.account_repository.get("account-1").unwrap();
+        let mut proposal = SigningProposal::new(
+            "proposal-1".to_string(),
+            &account,
+            principal(1),
+            SigningPayload::Message {
+                message_hex: "00".to_string(),
+            },
+            NOW,
+            expires_at,
+        );
+        set_caller(principal(2), NOW);
+        proposal.approve(&account).unwrap();
+        service
+            .signing_proposal_repository
+            .insert(proposal)
+            .unwrap()
+            .id()
+            .clone()
+    }
+
+    fn execute_request(proposal_id: &str) -> ExecuteSigningProposalRequest {
+        ExecuteSigningProposalRequest {
+            account_id: "account-1".to_string(),
+            proposal_id: proposal_id.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_expired_proposal() {
+        let service = setup();
+        let proposal_id = insert_approved_proposal(&service, NOW + 100);
+
+        set_caller(principal(1), NOW + 100);
+        let result = service
+            .execute_proposal(execute_request(&proposal_id))
+            .await;
+        assert_eq!(result.unwrap_err(), "Proposal has expired");
+
+        let proposal = service
+            .signing_proposal_repository
+            .get("account-1", &proposal_id)
+            .unwrap();
+        assert_eq!(proposal.status(), &SigningProposalStatus::Expired);
+        assert_eq!(proposal.signature(), &None);
+    }
+
+    #[tokio::test]
+    async fn test_execute_rejects_proposal_of_previous_key() {
+        let service = setup();
+        let proposal_id = insert_approved_proposal(&service, NOW + 100);
+
+        // Rotating the key needs the guardians removed, which they approve
+        let mut account = service.account_repository.get("account-1").unwrap();
+        set_caller(principal(1), NOW + 1);
+        account.set_guardians(None).unwrap();
+        set_caller(principal(2), NOW + 1);
+        account.approve_guardian_change().unwrap();
+        set_caller(principal(1), NOW + 1);
+        account.rotate_key(1, vec![4, 5, 6]).unwrap();
+        account
+            .set_guardians(Some(GuardianSet::new(vec![principal(2)], 1).unwrap()))
+            .unwrap();
+        service.account_repository.insert(account).unwrap();
+
+        let result = service
+            .execute_proposal(execute_request(&proposal_id))
+            .await;
+        assert!(result.is_err());
+        let proposal = service
+            .signing_proposal_repository
+            .get("account-1", &proposal_id)
+            .unwrap();
+        assert_eq!(proposal.status(), &SigningProposalStatus::Cancelled);
+    }
+}
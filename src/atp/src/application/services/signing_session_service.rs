@@ -0,0 +1,189 @@
+use candid::Principal;
+use std::cell::RefCell;
+
+use crate::application::dtos::signing_session_messages::*;
+use crate::application::dtos::signing_session_reply::SigningSessionReply;
+use crate::domain::models::account::{Account, AccountState};
+use crate::domain::models::signing_session::{SessionLimits, SigningSession};
+use crate::domain::repositories::account_repository::IAccountRepository;
+use crate::domain::repositories::signing_session_repository::ISigningSessionRepository;
+use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::infrastructure::repositories::signing_session_repository_impl::SigningSessionRepositoryImpl;
+use crate::utils::eth_utils::sha256;
+use crate::utils::ic::api::get_ic_api;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Sessions cannot stay valid for more than 30 days
+const MAX_SESSION_TTL_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+thread_local! {
+    // Disambiguates session IDs granted by the same caller within one round
+    static SESSION_NONCE: RefCell<u64> = const { RefCell::new(0) };
+}
+
+pub struct SigningSessionService {
+    account_repository: AccountRepositoryImpl,
+    signing_session_repository: SigningSessionRepositoryImpl,
+}
+
+impl SigningSessionService {
+    pub fn new(
+        account_repository: AccountRepositoryImpl,
+        signing_session_repository: SigningSessionRepositoryImpl,
+    ) -> Self {
+        Self {
+            account_repository,
+            signing_session_repository,
+        }
+    }
+
+    // Convert domain model to DTO
+    pub fn to_session_reply(
+        &self,
+        session: &SigningSession,
+        account: &Account,
+    ) -> SigningSessionReply {
+        let limits = session.limits();
+        SigningSessionReply {
+            id: session.id().clone(),
+            account_id: session.account_id().clone(),
+            delegate: session.delegate().to_string(),
+            created_at: *session.created_at(),
+            expires_at: *session.expires_at(),
+            max_signatures: limits.max_signatures,
+            signatures_used: *session.signatures_used(),
+            allowed_chains: limits
+                .allowed_chains
+                .iter()
+                .map(|chain_id| chain_id.to_string())
+                .collect(),
+            allowed_methods: limits.allowed_methods.clone(),
+            max_value: limits.max_value.map(|value| value.to_string()),
+            revoked: *session.revoked(),
+            active: session.is_active(account, get_ic_api().time()),
+        }
+    }
+
+    pub fn grant_session(
+        &self,
+        request: GrantSigningSessionRequest,
+    ) -> Result<GrantSigningSessionResponse, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+
+        // Check if the account exists
+        let mut account = self.account_repository.get(&request.account_id)?;
+        // Check if the caller is the owner of the account
+        if !account.is_owner(caller) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        // Check if the account is active
+        if account.account_state().clone() != AccountState::Active {
+            return Err("Account is not activated".to_string());
+        }
+        // A session would bypass the guardians of a guarded account
+        if account.requires_guardian_approval() {
+            return Err("Account requires guardian approval, sessions are not allowed".to_string());
+        }
+        if request.delegate == Principal::anonymous() || account.is_owner(request.delegate) {
+            return Err("Delegate must be a principal other than the owner".to_string());
+        }
+        if request.ttl_seconds == 0 || request.ttl_seconds > MAX_SESSION_TTL_SECONDS {
+            return Err(format!(
+                "Session TTL must be between 1 and {} seconds",
+                MAX_SESSION_TTL_SECONDS
+            ));
+        }
+        if request.max_signatures == 0 {
+            return Err("Session must allow at least one signature".to_string());
+        }
+        let max_value = match request.max_value {
+            Some(value) => Some(
+                value
+                    .parse::<u128>()
+                    .map_err(|_| format!("Invalid max value: {}", value))?,
+            ),
+            None => None,
+        };
+
+        // Generate a unique session ID
+        let now = ic_api.time();
+        let nonce = SESSION_NONCE.with(|nonce| {
+            let mut nonce = nonce.borrow_mut();
+            *nonce += 1;
+            *nonce
+        });
+        let id_string = format!("{}{}{}{}", account.id(), request.delegate, now, nonce);
+        let id = hex::encode(sha256(&id_string));
+
+        let session = SigningSession::new(
+            id,
+            &account,
+            request.delegate,
+            now,
+            now + request.ttl_seconds * NANOS_PER_SECOND,
+            SessionLimits {
+                max_signatures: request.max_signatures,
+                allowed_chains: request.allowed_chains,
+                allowed_methods: request.allowed_methods,
+                max_value,
+            },
+        );
+        let created_session = self.signing_session_repository.insert(session)?;
+        account.record_activity();
+        self.account_repository.insert(account.clone())?;
+        Ok(GrantSigningSessionResponse {
+            session: self.to_session_reply(&created_session, &account),
+        })
+    }
+
+    pub fn revoke_session(
+        &self,
+        request: RevokeSigningSessionRequest,
+    ) -> Result<RevokeSigningSessionResponse, String> {
+        // Check if the account and session exist
+        let mut account = self.account_repository.get(&request.account_id)?;
+        let mut session = self
+            .signing_session_repository
+            .get(&request.account_id, &request.session_id)?;
+
+        session.revoke(&account)?;
+        let updated_session = self.signing_session_repository.insert(session)?;
+        account.record_activity();
+        self.account_repository.insert(account.clone())?;
+        Ok(RevokeSigningSessionResponse {
+            session: self.to_session_reply(&updated_session, &account),
+        })
+    }
+
+    pub fn get_session(
+        &self,
+        request: GetSigningSessionRequest,
+    ) -> Result<GetSigningSessionResponse, String> {
+        let account = self.account_repository.get(&request.account_id)?;
+        let session = self
+            .signing_session_repository
+            .get(&request.account_id, &request.session_id)?;
+        Ok(GetSigningSessionResponse {
+            session: self.to_session_reply(&session, &account),
+        })
+    }
+
+    pub fn list_sessions(
+        &self,
+        request: ListSigningSessionsRequest,
+    ) -> Result<ListSigningSessionsResponse, String> {
+        let account = self.account_repository.get(&request.account_id)?;
+        let sessions = self.signing_session_repository.find_by_account(
+            &request.account_id,
+            request.page_size as usize,
+            request.page as usize,
+        )?;
+        Ok(ListSigningSessionsResponse {
+            sessions: sessions
+                .iter()
+                .map(|session| self.to_session_reply(session, &account))
+                .collect(),
+        })
+    }
+}
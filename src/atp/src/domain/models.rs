@@ -1,2 +1,16 @@
 pub mod account;
+pub mod account_metadata;
+pub mod audit_event;
+pub mod canister_config;
+pub mod capability;
+pub mod fee;
+pub mod guardian;
+pub mod inheritance;
+pub mod job;
+pub mod rate_limit;
+pub mod recovery;
+pub mod registry;
+pub mod role;
 pub mod signer;
+pub mod signing_proposal;
+pub mod signing_session;
@@ -1,10 +1,17 @@
 use atp_caip::curve::Curve;
-use candid::{CandidType, Decode, Encode, Principal};
-use ic_nosql::traits::Model;
+use candid::{CandidType, Principal};
+use ic_nosql::traits::{Index, Model};
+use ic_nosql::utils::serialization::{decode_storable, encode_storable};
 use ic_stable_structures::{storable::Bound, Storable};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
 
+use crate::domain::models::account_metadata::{tag_index_key, AccountMetadata};
+use crate::domain::models::guardian::{GuardianChange, GuardianSet};
+use crate::domain::models::inheritance::InheritancePlan;
+use crate::domain::models::recovery::{
+    validate_recovery_time_lock, RecoveryRequest, DEFAULT_RECOVERY_TIME_LOCK_SECONDS,
+};
 use crate::domain::models::signer::SignatureAlgorithm;
 use crate::generate_getters;
 use crate::utils::ic::api::get_ic_api;
@@ -19,13 +26,25 @@ pub enum AccountState {
     Active,
 }
 
+impl AccountState {
+    // Name of the state, as serialized
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountState::Locked => "locked",
+            AccountState::Unlocked => "unlocked",
+            AccountState::Active => "active",
+        }
+    }
+}
+
 impl Storable for AccountState {
     fn to_bytes(&self) -> Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
+        encode_storable(self)
     }
 
+    // A corrupt state is reported and traps, rather than being read as another state
     fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), AccountState).unwrap()
+        decode_storable(bytes.as_ref())
     }
 
     const BOUND: Bound = Bound::Unbounded;
@@ -40,18 +59,16 @@ pub struct Account {
     curve: Curve,
     account_state: AccountState,
     approved_address: Option<Principal>,
-}
-
-impl Storable for Account {
-    fn to_bytes(&self) -> Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
-    }
-
-    fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), Account).unwrap()
-    }
-
-    const BOUND: Bound = Bound::Unbounded;
+    guardians: Option<GuardianSet>,
+    pending_guardian_change: Option<GuardianChange>,
+    recovery_time_lock: Option<u64>,
+    pending_recovery: Option<RecoveryRequest>,
+    last_activity: Option<u64>,
+    inheritance: Option<InheritancePlan>,
+    key_version: Option<u32>,
+    metadata: Option<AccountMetadata>,
+    stored_version: Option<u64>,
+    ownership_epoch: Option<u32>,
 }
 
 #[derive(CandidType, Clone, Serialize, Deserialize)]
@@ -83,6 +100,16 @@ impl Account {
             curve,
             account_state: AccountState::Locked,
             approved_address: Some(approved_address),
+            guardians: None,
+            pending_guardian_change: None,
+            recovery_time_lock: None,
+            pending_recovery: None,
+            last_activity: None,
+            inheritance: None,
+            key_version: None,
+            metadata: None,
+            stored_version: None,
+            ownership_epoch: None,
         }
     }
 
@@ -93,9 +120,120 @@ impl Account {
         algorithm: SignatureAlgorithm,
         curve: Curve,
         account_state: AccountState,
-        approved_address: Option<Principal>
+        approved_address: Option<Principal>,
+        guardians: Option<GuardianSet>,
+        pending_guardian_change: Option<GuardianChange>,
+        pending_recovery: Option<RecoveryRequest>,
+        last_activity: Option<u64>,
+        inheritance: Option<InheritancePlan>
     );
 
+    // Current version of the account key, accounts that never rotated their key use version 0
+    pub fn key_version(&self) -> u32 {
+        self.key_version.unwrap_or(0)
+    }
+
+    // Number of times the account changed owner, accounts never transferred are at epoch 0
+    //
+    // Grants bound to an owner also record the epoch, so they do not come back to life
+    // when the account returns to that owner.
+    pub fn ownership_epoch(&self) -> u32 {
+        self.ownership_epoch.unwrap_or(0)
+    }
+
+    // Version of the stored account this one was read from, none until it is first stored
+    //
+    // Storing an account whose stored version moved on fails, so concurrent updates are
+    // detected instead of overwriting each other.
+    pub fn stored_version(&self) -> Option<u64> {
+        self.stored_version
+    }
+
+    // Record the version the account is stored at, set by repositories on reads and writes
+    pub fn set_stored_version(&mut self, version: u64) {
+        self.stored_version = Some(version);
+    }
+
+    // Owner-defined metadata, accounts created before metadata existed have none
+    pub fn metadata(&self) -> AccountMetadata {
+        self.metadata.clone().unwrap_or_default()
+    }
+
+    // Replace the metadata, allowing only the owner to change it
+    pub fn set_metadata(&mut self, metadata: AccountMetadata) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        if !self.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        self.metadata = Some(metadata);
+        Ok(self.clone())
+    }
+
+    // Derivation path of the account key at the given version
+    //
+    // Version 0 is derived from the account ID alone so keys created before
+    // key versioning stay valid.
+    pub fn derivation_path(&self, version: u32) -> Vec<Vec<u8>> {
+        if version == 0 {
+            vec![self.id.as_bytes().to_vec()]
+        } else {
+            vec![self.id.as_bytes().to_vec(), version.to_be_bytes().to_vec()]
+        }
+    }
+
+    // Check that the caller can move the account to a new key, returning the next key version
+    pub fn next_key_version(&self) -> Result<u32, String> {
+        let ic_api = get_ic_api();
+        if !self.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        // Check if the account is held by an approved application
+        if self.account_state == AccountState::Locked {
+            return Err("Account is locked".to_string());
+        }
+        // The funds of the old key could only be swept through signing proposals
+        if self.requires_guardian_approval() {
+            return Err(
+                "Account requires guardian approval, remove the guardians to rotate the key"
+                    .to_string(),
+            );
+        }
+        self.key_version()
+            .checked_add(1)
+            .ok_or_else(|| "Key version overflow".to_string())
+    }
+
+    // Replace the account key with the key derived at the next version
+    pub fn rotate_key(&mut self, version: u32, public_key: Vec<u8>) -> Result<Account, String> {
+        if version != self.next_key_version()? {
+            return Err("Account key was rotated concurrently".to_string());
+        }
+        self.public_key = public_key;
+        self.key_version = Some(version);
+        Ok(self.clone())
+    }
+
+    // Check that the caller can sign with a retired key, only allowed for the owner to sweep its funds
+    pub fn authorize_retired_key(&self, version: u32) -> Result<(), String> {
+        let ic_api = get_ic_api();
+        if version >= self.key_version() {
+            return Err(format!("Key version {} is not a retired key", version));
+        }
+        if !self.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        // Check if the account is held by an approved application
+        if self.account_state == AccountState::Locked {
+            return Err("Account is locked".to_string());
+        }
+        if self.requires_guardian_approval() {
+            return Err(
+                "Account requires guardian approval, create a signing proposal".to_string(),
+            );
+        }
+        Ok(())
+    }
+
     // Create a new account AccountReply
     pub fn to_account_reply(&self) -> AccountReply {
         AccountReply {
@@ -125,14 +263,318 @@ impl Account {
         self.owner == caller
     }
 
+    // Method to check if the caller is one of the account guardians
+    pub fn is_guardian(&self, caller: Principal) -> bool {
+        match &self.guardians {
+            Some(guardians) => guardians.is_guardian(caller),
+            None => false,
+        }
+    }
+
+    // Method to check if signing requires guardian approval
+    pub fn requires_guardian_approval(&self) -> bool {
+        self.guardians.is_some()
+    }
+
+    // Set or clear the guardian set, allowing only the owner to change it
+    //
+    // Guardians are set right away on accounts without any. Changing an existing set is
+    // only proposed, and applied once the current guardians approve it, so the owner
+    // alone cannot lift the protection they give.
+    pub fn set_guardians(&mut self, guardians: Option<GuardianSet>) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        if !self.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        if let Some(guardian_set) = &guardians {
+            if guardian_set.is_guardian(self.owner) {
+                return Err("Owner cannot be a guardian of the account".to_string());
+            }
+        }
+        if self.guardians.is_some() {
+            // A new proposal replaces the pending one and its approvals
+            self.pending_guardian_change = Some(GuardianChange::new(guardians, ic_api.time()));
+        } else {
+            self.apply_guardians(guardians);
+        }
+        Ok(self.clone())
+    }
+
+    // Approve the pending guardian change, only allowed for current guardians
+    //
+    // The change is applied once the threshold of current guardians approved it.
+    pub fn approve_guardian_change(&mut self) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+        let threshold = match &self.guardians {
+            Some(guardian_set) if guardian_set.is_guardian(caller) => *guardian_set.threshold(),
+            _ => return Err("Caller is not a guardian of the account".to_string()),
+        };
+        let change = match &mut self.pending_guardian_change {
+            Some(change) => {
+                change.approve(caller)?;
+                change.clone()
+            }
+            None => return Err("No guardian change in progress".to_string()),
+        };
+        let approvals = change
+            .approvals()
+            .iter()
+            .filter(|guardian| self.is_guardian(**guardian))
+            .count();
+        if approvals >= threshold as usize {
+            self.apply_guardians(change.guardians().clone());
+        }
+        Ok(self.clone())
+    }
+
+    // Replace the guardian set, dropping anything approved by the old one
+    fn apply_guardians(&mut self, guardians: Option<GuardianSet>) {
+        self.guardians = guardians;
+        self.pending_guardian_change = None;
+        // A new guardian set invalidates the confirmations of the old one
+        self.pending_recovery = None;
+    }
+
+    // Time-lock in seconds a guardian recovery has to wait before it can complete
+    pub fn recovery_time_lock(&self) -> u64 {
+        self.recovery_time_lock
+            .unwrap_or(DEFAULT_RECOVERY_TIME_LOCK_SECONDS)
+    }
+
+    // Method to check if a guardian recovery is in progress
+    pub fn is_under_recovery(&self) -> bool {
+        self.pending_recovery.is_some()
+    }
+
+    // Set the recovery time-lock, allowing only the owner to change it
+    pub fn set_recovery_time_lock(&mut self, time_lock_seconds: u64) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        if !self.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        validate_recovery_time_lock(time_lock_seconds)?;
+        self.recovery_time_lock = Some(time_lock_seconds);
+        Ok(self.clone())
+    }
+
+    // Start recovering the account to a new owner, only allowed for guardians
+    pub fn start_recovery(&mut self, new_owner: Principal) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+        if !self.is_guardian(caller) {
+            return Err("Caller is not a guardian of the account".to_string());
+        }
+        // Check if the account is held by an approved application
+        if self.account_state == AccountState::Locked {
+            return Err("Account is locked".to_string());
+        }
+        if self.pending_recovery.is_some() {
+            return Err("A recovery is already in progress".to_string());
+        }
+        if new_owner == Principal::anonymous() {
+            return Err("Anonymous principal cannot own the account".to_string());
+        }
+        if self.is_owner(new_owner) {
+            return Err("New owner is already the owner of the account".to_string());
+        }
+        if self.is_guardian(new_owner) {
+            return Err("New owner cannot be a guardian of the account".to_string());
+        }
+
+        self.pending_recovery = Some(RecoveryRequest::new(
+            new_owner,
+            caller,
+            ic_api.time(),
+            self.recovery_time_lock(),
+        ));
+        Ok(self.clone())
+    }
+
+    // Confirm the pending recovery, only allowed for guardians
+    pub fn confirm_recovery(&mut self) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+        if !self.is_guardian(caller) {
+            return Err("Caller is not a guardian of the account".to_string());
+        }
+        match &mut self.pending_recovery {
+            Some(recovery) => recovery.confirm(caller)?,
+            None => return Err("No recovery in progress".to_string()),
+        }
+        Ok(self.clone())
+    }
+
+    // Cancel the pending recovery, only allowed for the owner during the time-lock
+    pub fn cancel_recovery(&mut self) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        if !self.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        if self.pending_recovery.is_none() {
+            return Err("No recovery in progress".to_string());
+        }
+        self.pending_recovery = None;
+        Ok(self.clone())
+    }
+
+    // Count the recovery confirmations given by principals that are still guardians
+    pub fn recovery_confirmation_count(&self) -> usize {
+        match &self.pending_recovery {
+            Some(recovery) => recovery
+                .confirmations()
+                .iter()
+                .filter(|guardian| self.is_guardian(**guardian))
+                .count(),
+            None => 0,
+        }
+    }
+
+    // Hand the account over to the new owner once the threshold is met and the time-lock elapsed
+    pub fn complete_recovery(&mut self) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+        let (recovery, threshold) = match (&self.pending_recovery, &self.guardians) {
+            (Some(recovery), Some(guardian_set)) => (recovery.clone(), *guardian_set.threshold()),
+            _ => return Err("No recovery in progress".to_string()),
+        };
+        if !self.is_guardian(caller) && recovery.new_owner() != &caller {
+            return Err("Caller is neither a guardian nor the new owner".to_string());
+        }
+        if self.recovery_confirmation_count() < threshold as usize {
+            return Err("Recovery has not reached the guardian threshold".to_string());
+        }
+        if !recovery.is_unlocked(ic_api.time()) {
+            return Err("Recovery time-lock has not elapsed".to_string());
+        }
+
+        // Reset the owner and drop any application or guardian change approved by the previous owner
+        self.owner = *recovery.new_owner();
+        self.ownership_epoch = Some(self.ownership_epoch().wrapping_add(1));
+        self.approved_address = None;
+        self.pending_guardian_change = None;
+        self.pending_recovery = None;
+        self.last_activity = Some(ic_api.time());
+        Ok(self.clone())
+    }
+
+    // Record that the owner used the account, postponing any inheritance claim
+    //
+    // Returns whether the caller was the owner and the activity was recorded.
+    pub fn record_activity(&mut self) -> bool {
+        let ic_api = get_ic_api();
+        if !self.is_owner(ic_api.caller()) {
+            return false;
+        }
+        self.last_activity = Some(ic_api.time());
+        if let Some(plan) = &mut self.inheritance {
+            plan.set_notified_at(None);
+        }
+        true
+    }
+
+    // Set or clear the inheritance plan, allowing only the owner to change it
+    pub fn set_inheritance(
+        &mut self,
+        inheritance: Option<InheritancePlan>,
+    ) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        if !self.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        if let Some(plan) = &inheritance {
+            if self.is_owner(*plan.beneficiary()) {
+                return Err("Owner cannot be the beneficiary of the account".to_string());
+            }
+        }
+        self.inheritance = inheritance;
+        self.record_activity();
+        Ok(self.clone())
+    }
+
+    // Time from which the beneficiary can claim the account, if an inheritance plan is set
+    pub fn inheritance_claimable_at(&self) -> Option<u64> {
+        match (&self.inheritance, self.last_activity) {
+            (Some(plan), Some(last_activity)) => Some(plan.claimable_at(last_activity)),
+            _ => None,
+        }
+    }
+
+    // Method to check if the owner has been inactive for the inheritance period
+    pub fn is_inheritance_claimable(&self, now: u64) -> bool {
+        match self.inheritance_claimable_at() {
+            Some(claimable_at) => now >= claimable_at,
+            None => false,
+        }
+    }
+
+    // Flag the inactivity notice once the account becomes claimable
+    //
+    // Returns whether a new notice has to be issued.
+    pub fn mark_inactivity_notified(&mut self, now: u64) -> bool {
+        if !self.is_inheritance_claimable(now) {
+            return false;
+        }
+        match &mut self.inheritance {
+            Some(plan) if plan.notified_at().is_none() => {
+                plan.set_notified_at(Some(now));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    // Hand the account over to the beneficiary after the owner's inactivity period
+    pub fn claim_inheritance(&mut self) -> Result<Account, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+        let beneficiary = match &self.inheritance {
+            Some(plan) => *plan.beneficiary(),
+            None => return Err("Account has no inheritance plan".to_string()),
+        };
+        if beneficiary != caller {
+            return Err("Caller is not the beneficiary of the account".to_string());
+        }
+        // Check if the account is held by an approved application
+        if self.account_state == AccountState::Locked {
+            return Err("Account is locked".to_string());
+        }
+        if !self.is_inheritance_claimable(ic_api.time()) {
+            return Err("Owner inactivity period has not elapsed".to_string());
+        }
+
+        // Same outcome as a transfer: new owner, no approvals, guardians or plans, unlocked
+        self.owner = beneficiary;
+        self.ownership_epoch = Some(self.ownership_epoch().wrapping_add(1));
+        self.approved_address = None;
+        self.guardians = None;
+        self.pending_guardian_change = None;
+        self.recovery_time_lock = None;
+        self.pending_recovery = None;
+        self.inheritance = None;
+        self.metadata = self.metadata.as_ref().map(AccountMetadata::after_transfer);
+        self.last_activity = Some(ic_api.time());
+        self.account_state = AccountState::Unlocked;
+        Ok(self.clone())
+    }
+
     // Transfer the account to a new owner, only allowed if locked and approved
     pub fn transfer_account(&mut self, to: Principal) -> Result<Account, String> {
         let ic_api = get_ic_api();
         if self.is_approved(ic_api.caller()) {
             if self.account_state == AccountState::Locked {
-                // Reset the owner and remove the approved address
+                // Reset the owner and remove the approved address, guardians, recovery and inheritance
                 self.owner = to;
+                self.ownership_epoch = Some(self.ownership_epoch().wrapping_add(1));
                 self.approved_address = None;
+                self.guardians = None;
+                self.pending_guardian_change = None;
+                self.recovery_time_lock = None;
+                self.pending_recovery = None;
+                self.inheritance = None;
+                // Keep only the metadata fields the transfer policy lets through
+                self.metadata = self.metadata.as_ref().map(AccountMetadata::after_transfer);
+                self.last_activity = Some(ic_api.time());
                 // Unlock the account
                 self.account_state = AccountState::Unlocked;
                 Ok(self.clone())
@@ -214,7 +656,8 @@ impl Account {
         match self.account_state {
             AccountState::Locked => Err("Account is already locked".to_string()),
             AccountState::Unlocked => {
-                if self.is_approved(ic_cdk::api::caller()) || self.is_approved(ic_cdk::api::id()) {
+                let ic_api = get_ic_api();
+                if self.is_approved(ic_api.caller()) || self.is_approved(ic_api.id()) {
                     // Check if the caller is approved application
                     self.account_state = AccountState::Locked;
                     Ok(self.clone())
@@ -257,7 +700,399 @@ impl Model for Account {
         Some(self.owner.to_string())
     }
 
+    fn indexes() -> Vec<Index<Self>> {
+        vec![
+            Index::new("approved_address", |account: &Account| {
+                account
+                    .approved_address
+                    .iter()
+                    .map(|address| address.to_string())
+                    .collect()
+            }),
+            Index::new("state", |account: &Account| {
+                vec![account.account_state.as_str().to_string()]
+            }),
+            Index::new("tag", |account: &Account| {
+                let owner = account.owner.to_string();
+                account
+                    .metadata()
+                    .tags()
+                    .iter()
+                    .map(|tag| tag_index_key(&owner, tag))
+                    .collect()
+            }),
+        ]
+    }
+
     fn model_name() -> &'static str {
         "accounts"
     }
 }
+
+#[cfg(test)]
+mod account_tests {
+    use candid::Principal;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::domain::models::inheritance::MIN_INACTIVITY_PERIOD_SECONDS;
+    use crate::domain::models::recovery::MIN_RECOVERY_TIME_LOCK_SECONDS;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    const NOW: u64 = 1_000_000;
+    const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn set_caller(caller: Principal, time: u64) {
+        set_ic_api(Rc::new(
+            MockIcApi::new().with_caller(caller).with_time(time),
+        ));
+    }
+
+    // Helper function to create an active account guarded by a 2-of-3 guardian set
+    fn create_guarded_account() -> Account {
+        let owner = principal(1);
+        set_caller(owner, NOW);
+        let mut account = Account::new(
+            "account-1".to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            owner,
+        );
+        account.unlock().unwrap();
+        account.activate().unwrap();
+        let guardians =
+            GuardianSet::new(vec![principal(2), principal(3), principal(4)], 2).unwrap();
+        account.set_guardians(Some(guardians)).unwrap();
+        account
+    }
+
+    #[test]
+    fn test_guardian_change_requires_guardian_threshold() {
+        let mut account = create_guarded_account();
+        let guardians = account.guardians().clone();
+
+        // The owner alone can only propose to clear the guardians
+        set_caller(principal(1), NOW);
+        account.set_guardians(None).unwrap();
+        assert_eq!(account.guardians(), &guardians);
+        assert!(account.pending_guardian_change().is_some());
+
+        set_caller(principal(9), NOW);
+        assert!(account.approve_guardian_change().is_err());
+
+        set_caller(principal(2), NOW);
+        account.approve_guardian_change().unwrap();
+        assert!(account.approve_guardian_change().is_err());
+        assert_eq!(account.guardians(), &guardians);
+
+        // The second approval reaches the threshold of the current set
+        set_caller(principal(3), NOW);
+        account.approve_guardian_change().unwrap();
+        assert_eq!(account.guardians(), &None);
+        assert_eq!(account.pending_guardian_change(), &None);
+
+        // Without guardians the owner sets them right away
+        set_caller(principal(1), NOW);
+        account.set_guardians(guardians.clone()).unwrap();
+        assert_eq!(account.guardians(), &guardians);
+    }
+
+    #[test]
+    fn test_new_guardian_proposal_resets_approvals() {
+        let mut account = create_guarded_account();
+        let lowered = GuardianSet::new(vec![principal(2), principal(3), principal(4)], 1).unwrap();
+
+        set_caller(principal(1), NOW);
+        account.set_guardians(Some(lowered)).unwrap();
+        set_caller(principal(2), NOW);
+        account.approve_guardian_change().unwrap();
+
+        set_caller(principal(1), NOW + 1);
+        account.set_guardians(None).unwrap();
+        set_caller(principal(3), NOW + 1);
+        account.approve_guardian_change().unwrap();
+        assert!(account.guardians().is_some());
+
+        // A recovery to a new owner drops the change proposed by the old one
+        set_caller(principal(2), NOW + 2);
+        account.start_recovery(principal(9)).unwrap();
+        set_caller(principal(4), NOW + 2);
+        account.confirm_recovery().unwrap();
+        set_caller(
+            principal(9),
+            NOW + 2 + DEFAULT_RECOVERY_TIME_LOCK_SECONDS * NANOS_PER_SECOND,
+        );
+        account.complete_recovery().unwrap();
+        assert_eq!(account.pending_guardian_change(), &None);
+    }
+
+    #[test]
+    fn test_recovery_after_threshold_and_time_lock() {
+        let mut account = create_guarded_account();
+        let new_owner = principal(9);
+        let unlocks_at = NOW + DEFAULT_RECOVERY_TIME_LOCK_SECONDS * NANOS_PER_SECOND;
+
+        set_caller(principal(2), NOW);
+        account.start_recovery(new_owner).unwrap();
+        assert!(account.is_under_recovery());
+        assert_eq!(account.recovery_confirmation_count(), 1);
+
+        // A single confirmation does not reach the threshold
+        set_caller(principal(2), unlocks_at);
+        assert!(account.complete_recovery().is_err());
+
+        set_caller(principal(3), NOW + 1);
+        account.confirm_recovery().unwrap();
+        assert!(account.confirm_recovery().is_err());
+
+        // The threshold is met but the time-lock has not elapsed yet
+        set_caller(principal(3), unlocks_at - 1);
+        assert!(account.complete_recovery().is_err());
+        assert!(account.is_owner(principal(1)));
+
+        set_caller(new_owner, unlocks_at);
+        account.complete_recovery().unwrap();
+        assert!(account.is_owner(new_owner));
+        assert!(!account.is_under_recovery());
+        assert_eq!(account.approved_address(), &None);
+        assert_eq!(account.account_state(), &AccountState::Active);
+    }
+
+    #[test]
+    fn test_owner_cancels_recovery_during_time_lock() {
+        let mut account = create_guarded_account();
+
+        set_caller(principal(2), NOW);
+        account.start_recovery(principal(9)).unwrap();
+        set_caller(principal(3), NOW + 1);
+        account.confirm_recovery().unwrap();
+
+        // Only the owner can cancel
+        set_caller(principal(3), NOW + 2);
+        assert!(account.cancel_recovery().is_err());
+
+        set_caller(principal(1), NOW + 2);
+        account.cancel_recovery().unwrap();
+        assert!(!account.is_under_recovery());
+
+        set_caller(
+            principal(2),
+            NOW + DEFAULT_RECOVERY_TIME_LOCK_SECONDS * NANOS_PER_SECOND,
+        );
+        assert!(account.complete_recovery().is_err());
+        assert!(account.is_owner(principal(1)));
+    }
+
+    #[test]
+    fn test_only_guardians_start_recovery() {
+        let mut account = create_guarded_account();
+
+        set_caller(principal(9), NOW);
+        assert!(account.start_recovery(principal(9)).is_err());
+
+        // Guardians cannot recover the account to themselves or to the current owner
+        set_caller(principal(2), NOW);
+        assert!(account.start_recovery(principal(3)).is_err());
+        assert!(account.start_recovery(principal(1)).is_err());
+
+        account.start_recovery(principal(9)).unwrap();
+        set_caller(principal(3), NOW);
+        assert!(account.start_recovery(principal(8)).is_err());
+    }
+
+    #[test]
+    fn test_configurable_time_lock() {
+        let mut account = create_guarded_account();
+
+        // Only the owner can change the time-lock, within bounds
+        set_caller(principal(2), NOW);
+        assert!(account
+            .set_recovery_time_lock(MIN_RECOVERY_TIME_LOCK_SECONDS)
+            .is_err());
+        set_caller(principal(1), NOW);
+        assert!(account.set_recovery_time_lock(1).is_err());
+        account
+            .set_recovery_time_lock(MIN_RECOVERY_TIME_LOCK_SECONDS)
+            .unwrap();
+
+        set_caller(principal(2), NOW);
+        account.start_recovery(principal(9)).unwrap();
+        set_caller(principal(4), NOW);
+        account.confirm_recovery().unwrap();
+
+        set_caller(
+            principal(4),
+            NOW + MIN_RECOVERY_TIME_LOCK_SECONDS * NANOS_PER_SECOND,
+        );
+        account.complete_recovery().unwrap();
+        assert!(account.is_owner(principal(9)));
+    }
+
+    // Helper function to name principal(7) as beneficiary after the minimum inactivity period
+    fn create_inherited_account() -> Account {
+        let mut account = create_guarded_account();
+        let plan = InheritancePlan::new(principal(7), MIN_INACTIVITY_PERIOD_SECONDS).unwrap();
+        set_caller(principal(1), NOW);
+        account.set_inheritance(Some(plan)).unwrap();
+        account
+    }
+
+    #[test]
+    fn test_claim_inheritance_after_inactivity() {
+        let mut account = create_inherited_account();
+        let claimable_at = NOW + MIN_INACTIVITY_PERIOD_SECONDS * NANOS_PER_SECOND;
+        assert_eq!(account.inheritance_claimable_at(), Some(claimable_at));
+
+        // The beneficiary has to wait for the inactivity period
+        set_caller(principal(7), claimable_at - 1);
+        assert!(account.claim_inheritance().is_err());
+        assert!(!account.mark_inactivity_notified(claimable_at - 1));
+
+        // Only one notice is issued once the account becomes claimable
+        assert!(account.mark_inactivity_notified(claimable_at));
+        assert!(!account.mark_inactivity_notified(claimable_at + 1));
+
+        // Nobody but the beneficiary can claim
+        set_caller(principal(2), claimable_at);
+        assert!(account.claim_inheritance().is_err());
+
+        set_caller(principal(7), claimable_at);
+        account.claim_inheritance().unwrap();
+        assert!(account.is_owner(principal(7)));
+        assert_eq!(account.account_state(), &AccountState::Unlocked);
+        assert_eq!(account.guardians(), &None);
+        assert_eq!(account.inheritance(), &None);
+        assert_eq!(account.approved_address(), &None);
+    }
+
+    #[test]
+    fn test_locked_account_cannot_be_claimed() {
+        let owner = principal(1);
+        set_caller(owner, NOW);
+        let mut account = Account::new(
+            "account-1".to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            principal(5),
+        );
+        let plan = InheritancePlan::new(principal(7), MIN_INACTIVITY_PERIOD_SECONDS).unwrap();
+        account.set_inheritance(Some(plan)).unwrap();
+        let claimable_at = NOW + MIN_INACTIVITY_PERIOD_SECONDS * NANOS_PER_SECOND;
+
+        // The application holding the account keeps it until it unlocks it
+        set_caller(principal(7), claimable_at);
+        assert!(account.claim_inheritance().is_err());
+        assert!(account.is_owner(owner));
+        assert_eq!(account.approved_address(), &Some(principal(5)));
+
+        set_caller(principal(5), claimable_at);
+        account.unlock().unwrap();
+        set_caller(principal(7), claimable_at);
+        account.claim_inheritance().unwrap();
+        assert!(account.is_owner(principal(7)));
+    }
+
+    #[test]
+    fn test_owner_activity_postpones_inheritance() {
+        let mut account = create_inherited_account();
+        let period = MIN_INACTIVITY_PERIOD_SECONDS * NANOS_PER_SECOND;
+
+        assert!(account.mark_inactivity_notified(NOW + period));
+
+        // Activity by someone else does not count
+        set_caller(principal(2), NOW + period);
+        assert!(!account.record_activity());
+        assert!(account.is_inheritance_claimable(NOW + period));
+
+        // The owner coming back resets the clock and the notice
+        set_caller(principal(1), NOW + period);
+        assert!(account.record_activity());
+        assert!(!account.is_inheritance_claimable(NOW + period));
+        assert_eq!(account.inheritance().as_ref().unwrap().notified_at(), &None);
+
+        set_caller(principal(7), NOW + period + 1);
+        assert!(account.claim_inheritance().is_err());
+        assert!(account.is_owner(principal(1)));
+    }
+
+    #[test]
+    fn test_rotate_key_after_transfer() {
+        let owner = principal(1);
+        set_caller(owner, NOW);
+        let mut account = Account::new(
+            "account-1".to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            principal(5),
+        );
+        assert_eq!(account.key_version(), 0);
+        assert_eq!(account.derivation_path(0), vec![b"account-1".to_vec()]);
+
+        // The key cannot be rotated while the application holds the account
+        assert!(account.next_key_version().is_err());
+
+        set_caller(principal(5), NOW);
+        account.transfer_account(principal(6)).unwrap();
+        assert!(account.next_key_version().is_err());
+
+        set_caller(principal(6), NOW);
+        let version = account.next_key_version().unwrap();
+        assert_eq!(version, 1);
+        account.rotate_key(version, vec![4, 5, 6]).unwrap();
+        assert_eq!(account.key_version(), 1);
+        assert_eq!(account.public_key(), &vec![4, 5, 6]);
+        assert_eq!(
+            account.derivation_path(1),
+            vec![b"account-1".to_vec(), 1u32.to_be_bytes().to_vec()]
+        );
+
+        // A stale version is rejected
+        assert!(account.rotate_key(version, vec![7, 8, 9]).is_err());
+    }
+
+    #[test]
+    fn test_retired_key_only_for_owner() {
+        let owner = principal(1);
+        set_caller(owner, NOW);
+        let mut account = Account::new(
+            "account-1".to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            owner,
+        );
+        account.unlock().unwrap();
+        assert!(account.authorize_retired_key(0).is_err());
+
+        account.rotate_key(1, vec![4, 5, 6]).unwrap();
+        account.authorize_retired_key(0).unwrap();
+        assert!(account.authorize_retired_key(1).is_err());
+
+        set_caller(principal(2), NOW);
+        assert!(account.authorize_retired_key(0).is_err());
+    }
+
+    #[test]
+    fn test_account_state_round_trips_through_storable() {
+        let bytes = AccountState::Locked.to_bytes();
+        assert_eq!(AccountState::from_bytes(bytes), AccountState::Locked);
+    }
+
+    #[test]
+    #[should_panic(expected = "ic-nosql")]
+    fn test_corrupt_account_state_is_not_read_as_another_state() {
+        AccountState::from_bytes(Cow::Borrowed(b"garbage"));
+    }
+}
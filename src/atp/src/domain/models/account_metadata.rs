@@ -0,0 +1,248 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::generate_getters;
+
+/// Longest label, in bytes
+pub const MAX_LABEL_LENGTH: usize = 64;
+/// Longest description, in bytes
+pub const MAX_DESCRIPTION_LENGTH: usize = 256;
+/// Most key-value attributes an account can hold
+pub const MAX_ATTRIBUTES: usize = 8;
+/// Longest attribute key, in bytes
+pub const MAX_ATTRIBUTE_KEY_LENGTH: usize = 32;
+/// Longest attribute value, in bytes
+pub const MAX_ATTRIBUTE_VALUE_LENGTH: usize = 64;
+/// Most tags an account can hold
+pub const MAX_TAGS: usize = 8;
+/// Longest tag, in bytes
+pub const MAX_TAG_LENGTH: usize = 32;
+
+/// What happens to a metadata field when the account is transferred
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum TransferPolicy {
+    #[serde(rename = "keep")]
+    Keep,
+    #[serde(rename = "clear")]
+    Clear,
+}
+
+/// Transfer policy of every metadata field
+///
+/// By default the description and attributes describing the account travel with it,
+/// while the label and tags organizing the accounts of the previous owner are cleared.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct MetadataTransferPolicy {
+    pub label: TransferPolicy,
+    pub description: TransferPolicy,
+    pub attributes: TransferPolicy,
+    pub tags: TransferPolicy,
+}
+
+impl Default for MetadataTransferPolicy {
+    fn default() -> Self {
+        MetadataTransferPolicy {
+            label: TransferPolicy::Clear,
+            description: TransferPolicy::Keep,
+            attributes: TransferPolicy::Keep,
+            tags: TransferPolicy::Clear,
+        }
+    }
+}
+
+/// Owner-defined information telling accounts apart
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct AccountMetadata {
+    label: Option<String>,
+    description: Option<String>,
+    attributes: BTreeMap<String, String>,
+    tags: Vec<String>,
+    transfer_policy: MetadataTransferPolicy,
+}
+
+impl AccountMetadata {
+    // Constructor method validating the size of every field
+    //
+    // Tags are lowercased and deduplicated, so filtering by tag is case insensitive.
+    pub fn new(
+        label: Option<String>,
+        description: Option<String>,
+        attributes: BTreeMap<String, String>,
+        tags: Vec<String>,
+        transfer_policy: MetadataTransferPolicy,
+    ) -> Result<Self, String> {
+        if let Some(label) = &label {
+            validate_length("Label", label, MAX_LABEL_LENGTH)?;
+        }
+        if let Some(description) = &description {
+            validate_length("Description", description, MAX_DESCRIPTION_LENGTH)?;
+        }
+        if attributes.len() > MAX_ATTRIBUTES {
+            return Err(format!(
+                "Accounts cannot hold more than {} attributes",
+                MAX_ATTRIBUTES
+            ));
+        }
+        for (key, value) in &attributes {
+            validate_length("Attribute key", key, MAX_ATTRIBUTE_KEY_LENGTH)?;
+            if key.trim().is_empty() {
+                return Err("Attribute key cannot be empty".to_string());
+            }
+            validate_length("Attribute value", value, MAX_ATTRIBUTE_VALUE_LENGTH)?;
+        }
+
+        let mut normalized_tags: Vec<String> = Vec::new();
+        for tag in tags {
+            let tag = normalize_tag(&tag)?;
+            if !normalized_tags.contains(&tag) {
+                normalized_tags.push(tag);
+            }
+        }
+        if normalized_tags.len() > MAX_TAGS {
+            return Err(format!("Accounts cannot hold more than {} tags", MAX_TAGS));
+        }
+
+        Ok(AccountMetadata {
+            label,
+            description,
+            attributes,
+            tags: normalized_tags,
+            transfer_policy,
+        })
+    }
+
+    generate_getters!(
+        label: Option<String>,
+        description: Option<String>,
+        attributes: BTreeMap<String, String>,
+        tags: Vec<String>,
+        transfer_policy: MetadataTransferPolicy
+    );
+
+    // Check whether the account carries the tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags
+            .iter()
+            .any(|own| own.eq_ignore_ascii_case(tag.trim()))
+    }
+
+    // The metadata handed over to the new owner, clearing the fields the policy asks to
+    pub fn after_transfer(&self) -> AccountMetadata {
+        let keep = |policy: &TransferPolicy| *policy == TransferPolicy::Keep;
+        let policy = &self.transfer_policy;
+        AccountMetadata {
+            label: self.label.clone().filter(|_| keep(&policy.label)),
+            description: self
+                .description
+                .clone()
+                .filter(|_| keep(&policy.description)),
+            attributes: if keep(&policy.attributes) {
+                self.attributes.clone()
+            } else {
+                BTreeMap::new()
+            },
+            tags: if keep(&policy.tags) {
+                self.tags.clone()
+            } else {
+                Vec::new()
+            },
+            transfer_policy: policy.clone(),
+        }
+    }
+}
+
+// Lengths are counted in UTF-8 bytes, which bound the stored size of the metadata
+fn validate_length(field: &str, value: &str, max_length: usize) -> Result<(), String> {
+    if value.len() > max_length {
+        return Err(format!(
+            "{} cannot be longer than {} bytes",
+            field, max_length
+        ));
+    }
+    Ok(())
+}
+
+// Tags are short lowercase words made of letters, digits, '-' and '_'
+pub fn normalize_tag(tag: &str) -> Result<String, String> {
+    let tag = tag.trim().to_lowercase();
+    if tag.is_empty() {
+        return Err("Tag cannot be empty".to_string());
+    }
+    validate_length("Tag", &tag, MAX_TAG_LENGTH)?;
+    if !tag
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    {
+        return Err(format!(
+            "Tag {} can only contain letters, digits, '-' and '_'",
+            tag
+        ));
+    }
+    Ok(tag)
+}
+
+// Key of the tag index, tags are only listed within the accounts of an owner
+pub fn tag_index_key(owner: &str, tag: &str) -> String {
+    format!("{}:{}", owner, tag)
+}
+
+#[cfg(test)]
+mod account_metadata_tests {
+    use super::*;
+
+    fn metadata(tags: Vec<&str>) -> Result<AccountMetadata, String> {
+        AccountMetadata::new(
+            Some("Savings".to_string()),
+            Some("Long term holdings".to_string()),
+            BTreeMap::from([("listing".to_string(), "42".to_string())]),
+            tags.into_iter().map(String::from).collect(),
+            MetadataTransferPolicy::default(),
+        )
+    }
+
+    #[test]
+    fn test_fields_are_bounded() {
+        let metadata = metadata(vec!["DeFi", "defi ", "cold"]).unwrap();
+        assert_eq!(
+            metadata.tags(),
+            &vec!["defi".to_string(), "cold".to_string()]
+        );
+        assert!(metadata.has_tag("DEFI"));
+
+        assert!(self::metadata(vec!["two words"]).is_err());
+        let too_many: Vec<String> = (0..=MAX_TAGS).map(|i| format!("tag{}", i)).collect();
+        assert!(self::metadata(too_many.iter().map(String::as_str).collect()).is_err());
+        assert!(AccountMetadata::new(
+            Some("x".repeat(MAX_LABEL_LENGTH + 1)),
+            None,
+            BTreeMap::new(),
+            Vec::new(),
+            MetadataTransferPolicy::default(),
+        )
+        .is_err());
+
+        // Lengths are counted in bytes, not characters
+        let label = |text: String| {
+            AccountMetadata::new(
+                Some(text),
+                None,
+                BTreeMap::new(),
+                Vec::new(),
+                MetadataTransferPolicy::default(),
+            )
+        };
+        assert!(label("é".repeat(MAX_LABEL_LENGTH / 2)).is_ok());
+        assert!(label("é".repeat(MAX_LABEL_LENGTH / 2 + 1)).is_err());
+    }
+
+    #[test]
+    fn test_transfer_policy() {
+        let metadata = metadata(vec!["cold"]).unwrap();
+        let transferred = metadata.after_transfer();
+        assert_eq!(transferred.label(), &None);
+        assert!(transferred.tags().is_empty());
+        assert_eq!(transferred.description(), metadata.description());
+        assert_eq!(transferred.attributes(), metadata.attributes());
+    }
+}
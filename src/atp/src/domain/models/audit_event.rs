@@ -0,0 +1,69 @@
+use candid::{CandidType, Principal};
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum AuditEventKind {
+    #[serde(rename = "inheritance_configured")]
+    InheritanceConfigured,
+    #[serde(rename = "inactivity_notice")]
+    InactivityNotice,
+    #[serde(rename = "inheritance_claimed")]
+    InheritanceClaimed,
+}
+
+/// A notable event in the life of an account, kept for the owner and auditors
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct AuditEvent {
+    id: String,
+    account_id: String,
+    kind: AuditEventKind,
+    actor: Principal,
+    timestamp: u64,
+    message: String,
+}
+
+impl AuditEvent {
+    // Constructor method, the ID orders events of an account chronologically
+    pub fn new(
+        account_id: String,
+        kind: AuditEventKind,
+        actor: Principal,
+        timestamp: u64,
+        sequence: u64,
+        message: String,
+    ) -> Self {
+        AuditEvent {
+            id: format!("{:020}-{:020}", timestamp, sequence),
+            account_id,
+            kind,
+            actor,
+            timestamp,
+            message,
+        }
+    }
+
+    generate_getters!(
+        id: String,
+        account_id: String,
+        kind: AuditEventKind,
+        actor: Principal,
+        timestamp: u64,
+        message: String
+    );
+}
+
+impl Model for AuditEvent {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.id.clone()
+    }
+
+    fn model_name() -> &'static str {
+        "audit_events"
+    }
+}
@@ -0,0 +1,204 @@
+use atp_caip::curve::Curve;
+use candid::CandidType;
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::fee::{CurveFee, FeeLedger, FeeOperation};
+use crate::domain::models::rate_limit::{validate_rate_limit_settings, RateLimitSettings};
+use crate::generate_getters;
+
+/// The configuration is stored as a single document under this key
+pub const CANISTER_CONFIG_ID: &str = "current";
+
+/// Cycles charged to callers for canister operations
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct FeeSettings {
+    /// Cycles charged for creating an account
+    pub create_account_cycles: u64,
+    /// Cycles charged for every signature
+    pub sign_cycles: u64,
+    /// Cycles charged for rotating the key of an account, the account creation fee if unset
+    pub rotate_key_cycles: Option<u64>,
+    /// Fees replacing the defaults above for accounts on a given curve
+    pub curve_fees: Vec<CurveFee>,
+    /// Ledger callers can prepay fees on instead of attaching cycles
+    pub ledger: Option<FeeLedger>,
+}
+
+impl FeeSettings {
+    // Cycles charged for the operation on an account using the curve
+    pub fn fee(&self, operation: &FeeOperation, curve: &Curve) -> u64 {
+        let curve_fee = self
+            .curve_fees
+            .iter()
+            .find(|fee| &fee.operation == operation && &fee.curve == curve);
+        match (curve_fee, operation) {
+            (Some(fee), _) => fee.cycles,
+            (None, FeeOperation::CreateAccount) => self.create_account_cycles,
+            (None, FeeOperation::Sign) => self.sign_cycles,
+            // Rotating derives a new key with the same threshold key call as creating an account
+            (None, FeeOperation::RotateKey) => {
+                self.rotate_key_cycles.unwrap_or(self.create_account_cycles)
+            }
+        }
+    }
+}
+
+/// Canister settings chosen at install and upgrade time, kept in stable memory
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct CanisterConfig {
+    id: String,
+    key_id: String,
+    fee_settings: FeeSettings,
+    // Missing from configurations stored before rate limiting existed
+    rate_limits: Option<RateLimitSettings>,
+    updated_at: u64,
+}
+
+impl CanisterConfig {
+    // Constructor method for the configuration of a freshly installed canister
+    pub fn new(key_id: String, fee_settings: FeeSettings, now: u64) -> Result<Self, String> {
+        validate_key_id(&key_id)?;
+        validate_fee_settings(&fee_settings)?;
+        Ok(CanisterConfig {
+            id: CANISTER_CONFIG_ID.to_string(),
+            key_id,
+            fee_settings,
+            rate_limits: None,
+            updated_at: now,
+        })
+    }
+
+    generate_getters!(
+        id: String,
+        key_id: String,
+        fee_settings: FeeSettings,
+        updated_at: u64
+    );
+
+    pub fn rate_limits(&self) -> RateLimitSettings {
+        self.rate_limits.clone().unwrap_or_default()
+    }
+
+    // Switch the threshold key, accounts derived from the previous key can no longer sign
+    pub fn set_key_id(&mut self, key_id: String, now: u64) -> Result<(), String> {
+        validate_key_id(&key_id)?;
+        self.key_id = key_id;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn set_fee_settings(&mut self, fee_settings: FeeSettings, now: u64) -> Result<(), String> {
+        validate_fee_settings(&fee_settings)?;
+        self.fee_settings = fee_settings;
+        self.updated_at = now;
+        Ok(())
+    }
+
+    pub fn set_rate_limits(
+        &mut self,
+        rate_limits: RateLimitSettings,
+        now: u64,
+    ) -> Result<(), String> {
+        validate_rate_limit_settings(&rate_limits)?;
+        self.rate_limits = Some(rate_limits);
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+// Check that a threshold key ID is usable
+fn validate_key_id(key_id: &str) -> Result<(), String> {
+    if key_id.trim().is_empty() {
+        return Err("Key ID cannot be empty".to_string());
+    }
+    Ok(())
+}
+
+// Check that every operation and curve has at most one fee and that prepaid fees are priced
+fn validate_fee_settings(fee_settings: &FeeSettings) -> Result<(), String> {
+    if let Some(ledger) = &fee_settings.ledger {
+        if !matches!(ledger.tokens_per_trillion_cycles, Some(rate) if rate > 0) {
+            return Err("Fee ledger must set a positive tokens_per_trillion_cycles".to_string());
+        }
+    }
+    for (index, fee) in fee_settings.curve_fees.iter().enumerate() {
+        if fee_settings.curve_fees[..index]
+            .iter()
+            .any(|other| other.operation == fee.operation && other.curve == fee.curve)
+        {
+            return Err(format!(
+                "Duplicate {:?} fee for curve {}",
+                fee.operation, fee.curve
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl Model for CanisterConfig {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.id.clone()
+    }
+
+    fn model_name() -> &'static str {
+        "config"
+    }
+}
+
+#[cfg(test)]
+mod canister_config_tests {
+    use super::*;
+    use candid::Principal;
+
+    #[test]
+    fn test_curve_fee_overrides_default() {
+        let fee_settings = FeeSettings {
+            create_account_cycles: 100,
+            sign_cycles: 10,
+            curve_fees: vec![CurveFee {
+                operation: FeeOperation::Sign,
+                curve: Curve::Ed25519,
+                cycles: 5,
+            }],
+            ledger: None,
+            ..Default::default()
+        };
+        assert_eq!(fee_settings.fee(&FeeOperation::Sign, &Curve::Ed25519), 5);
+        assert_eq!(fee_settings.fee(&FeeOperation::Sign, &Curve::Secp256k1), 10);
+        assert_eq!(
+            fee_settings.fee(&FeeOperation::CreateAccount, &Curve::Ed25519),
+            100
+        );
+        assert_eq!(
+            fee_settings.fee(&FeeOperation::RotateKey, &Curve::Ed25519),
+            100
+        );
+
+        let mut duplicated = fee_settings.clone();
+        duplicated.curve_fees.push(duplicated.curve_fees[0].clone());
+        assert!(CanisterConfig::new("key".to_string(), duplicated, 1).is_err());
+    }
+
+    #[test]
+    fn test_fee_ledger_requires_price() {
+        let mut fee_settings = FeeSettings {
+            ledger: Some(FeeLedger {
+                canister_id: Principal::from_slice(&[9; 29]),
+                decimals: 8,
+                transfer_fee: 10_000,
+                tokens_per_trillion_cycles: None,
+            }),
+            ..Default::default()
+        };
+        assert!(CanisterConfig::new("key".to_string(), fee_settings.clone(), 1).is_err());
+
+        if let Some(ledger) = fee_settings.ledger.as_mut() {
+            ledger.tokens_per_trillion_cycles = Some(100_000_000);
+        }
+        assert!(CanisterConfig::new("key".to_string(), fee_settings, 1).is_ok());
+    }
+}
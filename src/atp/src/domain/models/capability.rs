@@ -0,0 +1,96 @@
+use atp_caip::curve::Curve;
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::signer::SignatureAlgorithm;
+use crate::domain::models::signing_session::SigningMethod;
+
+/// An algorithm and curve pair the threshold signer can derive keys for
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct KeyType {
+    pub algorithm: SignatureAlgorithm,
+    pub curve: Curve,
+}
+
+/// Every key type accounts can be created with
+pub fn supported_key_types() -> Vec<KeyType> {
+    vec![
+        KeyType {
+            algorithm: SignatureAlgorithm::Ecdsa,
+            curve: Curve::Secp256k1,
+        },
+        KeyType {
+            algorithm: SignatureAlgorithm::Schnorr,
+            curve: Curve::Secp256k1,
+        },
+        KeyType {
+            algorithm: SignatureAlgorithm::Schnorr,
+            curve: Curve::Ed25519,
+        },
+    ]
+}
+
+// Check that accounts can be created with the algorithm and curve
+pub fn validate_key_type(algorithm: &SignatureAlgorithm, curve: &Curve) -> Result<(), String> {
+    let key_type = KeyType {
+        algorithm: algorithm.clone(),
+        curve: curve.clone(),
+    };
+    if !supported_key_types().contains(&key_type) {
+        return Err(format!(
+            "Curve {} is not supported for {:?} signatures",
+            curve, algorithm
+        ));
+    }
+    Ok(())
+}
+
+// Signing methods available to accounts of the key type
+pub fn signing_methods(algorithm: &SignatureAlgorithm, curve: &Curve) -> Vec<SigningMethod> {
+    match (algorithm, curve) {
+        (SignatureAlgorithm::Ecdsa, Curve::Secp256k1) => {
+            vec![SigningMethod::Message, SigningMethod::Eip1559Transaction]
+        }
+        _ => vec![SigningMethod::Message],
+    }
+}
+
+/// How addresses are derived from the account public key on a chain
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum AddressFormat {
+    /// 0x-prefixed lowercase hex of the Keccak-256 hash of the public key
+    #[serde(rename = "hex")]
+    Hex,
+    /// Base58 of the raw public key
+    #[serde(rename = "base58")]
+    Base58,
+    /// Bech32 segwit v0 address of the public key hash
+    #[serde(rename = "p2wpkh")]
+    P2wpkh,
+}
+
+// Address format of the chains of a CAIP-2 namespace, if addresses can be generated
+pub fn address_format(namespace: &str) -> Option<AddressFormat> {
+    match namespace {
+        "eip155" => Some(AddressFormat::Hex),
+        "solana" => Some(AddressFormat::Base58),
+        "bip122" => Some(AddressFormat::P2wpkh),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod capability_tests {
+    use super::*;
+
+    #[test]
+    fn test_key_type_matrix() {
+        assert!(validate_key_type(&SignatureAlgorithm::Ecdsa, &Curve::Secp256k1).is_ok());
+        assert!(validate_key_type(&SignatureAlgorithm::Schnorr, &Curve::Ed25519).is_ok());
+        assert!(validate_key_type(&SignatureAlgorithm::Ecdsa, &Curve::Ed25519).is_err());
+        assert_eq!(
+            signing_methods(&SignatureAlgorithm::Schnorr, &Curve::Secp256k1),
+            vec![SigningMethod::Message]
+        );
+    }
+}
@@ -0,0 +1,213 @@
+use atp_caip::curve::Curve;
+use atp_caip::money::Money;
+use candid::{CandidType, Principal};
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+/// Cycles are accounted with 12 decimals, one unit being one trillion cycles
+pub const CYCLES_DECIMALS: u8 = 12;
+
+/// The canister operations callers pay a fee for
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum FeeOperation {
+    #[serde(rename = "create_account")]
+    CreateAccount,
+    #[serde(rename = "sign")]
+    Sign,
+    #[serde(rename = "rotate_key")]
+    RotateKey,
+}
+
+/// A fee overriding the default of an operation for accounts on a curve
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct CurveFee {
+    pub operation: FeeOperation,
+    pub curve: Curve,
+    pub cycles: u64,
+}
+
+/// The ICRC-1 ledger callers can prepay fees on
+///
+/// Fees are priced in cycles and converted to base units of its token at a fixed rate,
+/// e.g. 10^12 base units per trillion cycles on the cycles ledger.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct FeeLedger {
+    pub canister_id: Principal,
+    pub decimals: u8,
+    pub transfer_fee: u64,
+    // Missing from ledgers configured before prepaid fees were priced, fees cannot be prepaid on them
+    pub tokens_per_trillion_cycles: Option<u64>,
+}
+
+impl FeeLedger {
+    // Base units of the token charged for a fee in cycles, rounded up
+    pub fn price(&self, cycles: u64) -> Result<u128, String> {
+        let rate = match self.tokens_per_trillion_cycles {
+            Some(rate) if rate > 0 => rate as u128,
+            _ => return Err("No token price is configured for the fee ledger".to_string()),
+        };
+        Ok((cycles as u128 * rate).div_ceil(TRILLION_CYCLES))
+    }
+}
+
+const TRILLION_CYCLES: u128 = 1_000_000_000_000;
+
+/// The kinds of funds the canister collects fees in
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum FeeAsset {
+    #[serde(rename = "cycles")]
+    Cycles,
+    #[serde(rename = "tokens")]
+    Tokens,
+}
+
+impl FeeAsset {
+    // ID of the fee account holding the collected fees of the asset
+    pub fn collected_account_id(&self) -> String {
+        match self {
+            FeeAsset::Cycles => "collected:cycles".to_string(),
+            FeeAsset::Tokens => "collected:tokens".to_string(),
+        }
+    }
+}
+
+/// ID of the fee account holding the prepaid balance of a principal
+pub fn prepaid_account_id(owner: &Principal) -> String {
+    owner.to_string()
+}
+
+/// The ledger subaccount of the canister a principal deposits prepaid fees to
+pub fn deposit_subaccount(owner: &Principal) -> Vec<u8> {
+    let bytes = owner.as_slice();
+    let mut subaccount = vec![0u8; 32];
+    subaccount[0] = bytes.len() as u8;
+    subaccount[1..1 + bytes.len()].copy_from_slice(bytes);
+    subaccount
+}
+
+/// A balance kept by the fee subsystem, either prepaid by a caller or collected by the canister
+///
+/// The amount is stored as raw base units and handled as `Money`.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct FeeAccount {
+    id: String,
+    balance: String,
+    decimals: u8,
+    updated_at: u64,
+}
+
+impl FeeAccount {
+    // Constructor method for an empty fee account
+    pub fn new(id: String, decimals: u8, now: u64) -> Self {
+        FeeAccount {
+            id,
+            balance: "0".to_string(),
+            decimals,
+            updated_at: now,
+        }
+    }
+
+    generate_getters!(id: String, decimals: u8, updated_at: u64);
+
+    pub fn balance(&self) -> Result<Money, String> {
+        Money::from_raw(&self.balance, self.decimals).map_err(|e| e.to_string())
+    }
+
+    // Add funds to the account
+    pub fn credit(&mut self, amount: &Money, now: u64) -> Result<(), String> {
+        let balance = self.balance()?.add(amount).map_err(|e| e.to_string())?;
+        self.balance = balance.raw_amount().to_string();
+        self.updated_at = now;
+        Ok(())
+    }
+
+    // Take funds from the account, failing if the balance does not cover the amount
+    pub fn debit(&mut self, amount: &Money, now: u64) -> Result<(), String> {
+        let balance = self.balance()?;
+        if balance.decimals != amount.decimals {
+            return Err(format!(
+                "Fee account uses {} decimals, got {}",
+                balance.decimals, amount.decimals
+            ));
+        }
+        if balance.raw_amount() < amount.raw_amount() {
+            return Err(format!(
+                "Insufficient fee balance: {} < {}",
+                balance.raw_amount(),
+                amount.raw_amount()
+            ));
+        }
+        let balance = balance.sub(amount).map_err(|e| e.to_string())?;
+        self.balance = balance.raw_amount().to_string();
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+impl Model for FeeAccount {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.id.clone()
+    }
+
+    fn model_name() -> &'static str {
+        "fee_accounts"
+    }
+}
+
+#[cfg(test)]
+mod fee_tests {
+    use super::*;
+
+    fn money(amount: u64, decimals: u8) -> Money {
+        Money::from_raw(&amount.to_string(), decimals).unwrap()
+    }
+
+    #[test]
+    fn test_credit_and_debit() {
+        let mut account = FeeAccount::new("account".to_string(), CYCLES_DECIMALS, 1);
+        account.credit(&money(100, CYCLES_DECIMALS), 2).unwrap();
+        account.debit(&money(40, CYCLES_DECIMALS), 3).unwrap();
+        assert_eq!(account.balance().unwrap(), money(60, CYCLES_DECIMALS));
+        assert_eq!(account.updated_at(), &3);
+
+        // Overdrafts and mismatched decimals leave the balance untouched
+        assert!(account.debit(&money(61, CYCLES_DECIMALS), 4).is_err());
+        assert!(account.debit(&money(1, 8), 4).is_err());
+        assert!(account.credit(&money(1, 8), 4).is_err());
+        assert_eq!(account.balance().unwrap(), money(60, CYCLES_DECIMALS));
+    }
+
+    #[test]
+    fn test_ledger_price() {
+        let mut ledger = FeeLedger {
+            canister_id: Principal::from_slice(&[9; 29]),
+            decimals: 8,
+            transfer_fee: 10_000,
+            tokens_per_trillion_cycles: Some(10_000_000),
+        };
+        // 0.1 token per trillion cycles, partial base units are rounded up
+        assert_eq!(ledger.price(26_000_000_000).unwrap(), 260_000);
+        assert_eq!(ledger.price(1).unwrap(), 1);
+        assert_eq!(ledger.price(0).unwrap(), 0);
+
+        ledger.tokens_per_trillion_cycles = Some(0);
+        assert!(ledger.price(1).is_err());
+        ledger.tokens_per_trillion_cycles = None;
+        assert!(ledger.price(1).is_err());
+    }
+
+    #[test]
+    fn test_deposit_subaccount() {
+        let owner = Principal::from_slice(&[7; 29]);
+        let subaccount = deposit_subaccount(&owner);
+        assert_eq!(subaccount.len(), 32);
+        assert_eq!(subaccount[0], 29);
+        assert_eq!(&subaccount[1..30], owner.as_slice());
+        assert_eq!(subaccount[30..], [0, 0]);
+    }
+}
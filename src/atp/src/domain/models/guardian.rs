@@ -0,0 +1,89 @@
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+/// Maximum number of guardians that can be attached to a single account
+pub const MAX_GUARDIANS: usize = 10;
+
+/// An M-of-N set of guardians whose approval is required before the account signs
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct GuardianSet {
+    guardians: Vec<Principal>,
+    threshold: u8,
+}
+
+impl GuardianSet {
+    // Constructor method validating the guardian list and threshold
+    pub fn new(guardians: Vec<Principal>, threshold: u8) -> Result<Self, String> {
+        if guardians.is_empty() {
+            return Err("Guardian set must contain at least one guardian".to_string());
+        }
+        if guardians.len() > MAX_GUARDIANS {
+            return Err(format!(
+                "Guardian set can contain at most {} guardians",
+                MAX_GUARDIANS
+            ));
+        }
+        if guardians.contains(&Principal::anonymous()) {
+            return Err("Anonymous principal cannot be a guardian".to_string());
+        }
+        for (i, guardian) in guardians.iter().enumerate() {
+            if guardians[..i].contains(guardian) {
+                return Err(format!("Duplicate guardian: {}", guardian));
+            }
+        }
+        if threshold == 0 || threshold as usize > guardians.len() {
+            return Err(format!(
+                "Threshold must be between 1 and {}",
+                guardians.len()
+            ));
+        }
+
+        Ok(GuardianSet {
+            guardians,
+            threshold,
+        })
+    }
+
+    generate_getters!(guardians: Vec<Principal>, threshold: u8);
+
+    // Method to check if the principal is one of the guardians
+    pub fn is_guardian(&self, principal: Principal) -> bool {
+        self.guardians.contains(&principal)
+    }
+}
+
+/// A change of the guardian set proposed by the owner, applied once the current guardians approve it
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct GuardianChange {
+    guardians: Option<GuardianSet>,
+    approvals: Vec<Principal>,
+    proposed_at: u64,
+}
+
+impl GuardianChange {
+    // Constructor method for a change without approvals, clearing the guardians if none are given
+    pub fn new(guardians: Option<GuardianSet>, proposed_at: u64) -> Self {
+        GuardianChange {
+            guardians,
+            approvals: Vec::new(),
+            proposed_at,
+        }
+    }
+
+    generate_getters!(
+        guardians: Option<GuardianSet>,
+        approvals: Vec<Principal>,
+        proposed_at: u64
+    );
+
+    // Record the approval of a guardian
+    pub fn approve(&mut self, guardian: Principal) -> Result<(), String> {
+        if self.approvals.contains(&guardian) {
+            return Err("Guardian has already approved the change".to_string());
+        }
+        self.approvals.push(guardian);
+        Ok(())
+    }
+}
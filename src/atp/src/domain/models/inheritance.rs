@@ -0,0 +1,57 @@
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Shortest inactivity period an owner can configure (30 days)
+pub const MIN_INACTIVITY_PERIOD_SECONDS: u64 = 30 * 24 * 60 * 60;
+/// Longest inactivity period an owner can configure (10 years)
+pub const MAX_INACTIVITY_PERIOD_SECONDS: u64 = 10 * 365 * 24 * 60 * 60;
+
+/// A beneficiary who can claim the account once the owner has been inactive for long enough
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct InheritancePlan {
+    beneficiary: Principal,
+    inactivity_period_seconds: u64,
+    notified_at: Option<u64>,
+}
+
+impl InheritancePlan {
+    // Constructor method validating the inactivity period
+    pub fn new(beneficiary: Principal, inactivity_period_seconds: u64) -> Result<Self, String> {
+        if beneficiary == Principal::anonymous() {
+            return Err("Anonymous principal cannot be a beneficiary".to_string());
+        }
+        if !(MIN_INACTIVITY_PERIOD_SECONDS..=MAX_INACTIVITY_PERIOD_SECONDS)
+            .contains(&inactivity_period_seconds)
+        {
+            return Err(format!(
+                "Inactivity period must be between {} and {} seconds",
+                MIN_INACTIVITY_PERIOD_SECONDS, MAX_INACTIVITY_PERIOD_SECONDS
+            ));
+        }
+
+        Ok(InheritancePlan {
+            beneficiary,
+            inactivity_period_seconds,
+            notified_at: None,
+        })
+    }
+
+    generate_getters!(
+        beneficiary: Principal,
+        inactivity_period_seconds: u64,
+        notified_at: Option<u64>
+    );
+
+    // Time from which the beneficiary can claim, given the last owner activity
+    pub fn claimable_at(&self, last_activity: u64) -> u64 {
+        last_activity + self.inactivity_period_seconds * NANOS_PER_SECOND
+    }
+
+    // Record when the inactivity notice was issued
+    pub fn set_notified_at(&mut self, notified_at: Option<u64>) {
+        self.notified_at = notified_at;
+    }
+}
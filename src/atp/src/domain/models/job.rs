@@ -0,0 +1,134 @@
+use candid::CandidType;
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+/// How a background job is triggered
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum JobSchedule {
+    /// Runs every `interval_seconds` while the canister is installed
+    #[serde(rename = "recurring")]
+    Recurring { interval_seconds: u64 },
+    /// Runs once, `delay_seconds` after every install or upgrade
+    #[serde(rename = "one_shot")]
+    OneShot { delay_seconds: u64 },
+}
+
+/// Run history of a background job, kept across upgrades
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct Job {
+    name: String,
+    schedule: JobSchedule,
+    registered_at: u64,
+    last_run_at: Option<u64>,
+    last_success_at: Option<u64>,
+    last_error: Option<String>,
+    run_count: u64,
+    failure_count: u64,
+    // Missing from jobs stored before jobs kept a position, they start from the beginning
+    position: Option<String>,
+}
+
+impl Job {
+    // Constructor method for a job that has never run
+    pub fn new(name: String, schedule: JobSchedule, registered_at: u64) -> Self {
+        Job {
+            name,
+            schedule,
+            registered_at,
+            last_run_at: None,
+            last_success_at: None,
+            last_error: None,
+            run_count: 0,
+            failure_count: 0,
+            position: None,
+        }
+    }
+
+    generate_getters!(
+        name: String,
+        schedule: JobSchedule,
+        registered_at: u64,
+        last_run_at: Option<u64>,
+        last_success_at: Option<u64>,
+        last_error: Option<String>,
+        run_count: u64,
+        failure_count: u64,
+        position: Option<String>
+    );
+
+    // Refresh the schedule when the job is registered again, keeping its history
+    pub fn reschedule(&mut self, schedule: JobSchedule, registered_at: u64) {
+        self.schedule = schedule;
+        self.registered_at = registered_at;
+    }
+
+    // Record where a job working through its data in batches resumes on its next run
+    pub fn set_position(&mut self, position: Option<String>) {
+        self.position = position;
+    }
+
+    // Record the outcome of a run
+    pub fn record_run(&mut self, now: u64, result: Result<(), String>) {
+        self.last_run_at = Some(now);
+        self.run_count += 1;
+        match result {
+            Ok(()) => {
+                self.last_success_at = Some(now);
+                self.last_error = None;
+            }
+            Err(e) => {
+                self.failure_count += 1;
+                self.last_error = Some(e);
+            }
+        }
+    }
+}
+
+impl Model for Job {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.name.clone()
+    }
+
+    fn model_name() -> &'static str {
+        "jobs"
+    }
+}
+
+#[cfg(test)]
+mod job_tests {
+    use super::*;
+
+    #[test]
+    fn test_record_runs() {
+        let mut job = Job::new(
+            "cleanup".to_string(),
+            JobSchedule::Recurring {
+                interval_seconds: 60,
+            },
+            1,
+        );
+
+        job.record_run(10, Err("boom".to_string()));
+        assert_eq!(job.run_count(), &1);
+        assert_eq!(job.failure_count(), &1);
+        assert_eq!(job.last_success_at(), &None);
+        assert_eq!(job.last_error(), &Some("boom".to_string()));
+
+        job.record_run(20, Ok(()));
+        assert_eq!(job.run_count(), &2);
+        assert_eq!(job.failure_count(), &1);
+        assert_eq!(job.last_run_at(), &Some(20));
+        assert_eq!(job.last_success_at(), &Some(20));
+        assert_eq!(job.last_error(), &None);
+
+        // Registering again keeps the history
+        job.reschedule(JobSchedule::OneShot { delay_seconds: 0 }, 30);
+        assert_eq!(job.registered_at(), &30);
+        assert_eq!(job.run_count(), &2);
+    }
+}
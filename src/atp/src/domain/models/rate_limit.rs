@@ -0,0 +1,166 @@
+use candid::CandidType;
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+
+/// The operations a principal is rate limited on
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum RateLimitOperation {
+    #[serde(rename = "create_account")]
+    CreateAccount,
+    #[serde(rename = "sign")]
+    Sign,
+    #[serde(rename = "rotate_key")]
+    RotateKey,
+}
+
+/// A token bucket limit: up to `capacity` calls in a burst, one more every `refill_seconds`
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub refill_seconds: u64,
+}
+
+/// Limits protecting the canister from callers spamming costly operations
+///
+/// Missing limits are not enforced.
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug, Default)]
+pub struct RateLimitSettings {
+    pub create_account: Option<RateLimit>,
+    pub sign: Option<RateLimit>,
+    pub rotate_key: Option<RateLimit>,
+    /// Accounts a single principal can own
+    pub max_accounts_per_owner: Option<u32>,
+}
+
+impl RateLimitSettings {
+    // Limit of the operation, if any
+    pub fn limit(&self, operation: &RateLimitOperation) -> Option<&RateLimit> {
+        match operation {
+            RateLimitOperation::CreateAccount => self.create_account.as_ref(),
+            RateLimitOperation::Sign => self.sign.as_ref(),
+            RateLimitOperation::RotateKey => self.rotate_key.as_ref(),
+        }
+    }
+}
+
+// Check that every limit lets at least one call through
+pub fn validate_rate_limit_settings(settings: &RateLimitSettings) -> Result<(), String> {
+    for limit in [
+        &settings.create_account,
+        &settings.sign,
+        &settings.rotate_key,
+    ]
+    .into_iter()
+    .flatten()
+    {
+        if limit.capacity == 0 || limit.refill_seconds == 0 {
+            return Err("Rate limit capacity and refill must be positive".to_string());
+        }
+    }
+    if settings.max_accounts_per_owner == Some(0) {
+        return Err("Max accounts per owner must be positive".to_string());
+    }
+    Ok(())
+}
+
+/// The calls a principal has left for an operation, kept in heap memory
+///
+/// Tokens are refilled lazily from the elapsed time when the bucket is used.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TokenBucket {
+    tokens: u32,
+    refilled_at: u64,
+}
+
+impl TokenBucket {
+    // Constructor method for a full bucket
+    pub fn new(limit: &RateLimit, now: u64) -> Self {
+        TokenBucket {
+            tokens: limit.capacity,
+            refilled_at: now,
+        }
+    }
+
+    generate_getters!(tokens: u32, refilled_at: u64);
+
+    // Add the tokens earned since the last refill, keeping the remainder of a partial interval
+    pub fn refill(&mut self, limit: &RateLimit, now: u64) {
+        let interval = limit.refill_seconds.saturating_mul(NANOS_PER_SECOND);
+        let earned = now.saturating_sub(self.refilled_at) / interval;
+        if self.tokens >= limit.capacity || earned >= u64::from(limit.capacity) {
+            self.tokens = limit.capacity;
+            self.refilled_at = now;
+            return;
+        }
+        self.tokens = limit.capacity.min(self.tokens + earned as u32);
+        self.refilled_at += earned * interval;
+    }
+
+    // Take a token, failing if the bucket is empty
+    pub fn acquire(&mut self, limit: &RateLimit, now: u64) -> Result<(), String> {
+        self.refill(limit, now);
+        if self.tokens == 0 {
+            let interval = limit.refill_seconds.saturating_mul(NANOS_PER_SECOND);
+            let wait = (self.refilled_at + interval).saturating_sub(now);
+            return Err(format!(
+                "Rate limit exceeded, retry in {} seconds",
+                wait.div_ceil(NANOS_PER_SECOND)
+            ));
+        }
+        self.tokens -= 1;
+        Ok(())
+    }
+
+    // A full bucket behaves like a missing one and can be dropped
+    pub fn is_full(&self, limit: &RateLimit, now: u64) -> bool {
+        let mut bucket = self.clone();
+        bucket.refill(limit, now);
+        bucket.tokens >= limit.capacity
+    }
+}
+
+#[cfg(test)]
+mod rate_limit_tests {
+    use super::*;
+
+    const LIMIT: RateLimit = RateLimit {
+        capacity: 2,
+        refill_seconds: 10,
+    };
+
+    #[test]
+    fn test_bucket_refills_over_time() {
+        let mut bucket = TokenBucket::new(&LIMIT, 0);
+        bucket.acquire(&LIMIT, 0).unwrap();
+        bucket.acquire(&LIMIT, 0).unwrap();
+        assert!(bucket.acquire(&LIMIT, 5 * NANOS_PER_SECOND).is_err());
+        assert!(!bucket.is_full(&LIMIT, 5 * NANOS_PER_SECOND));
+
+        // Partial intervals carry over to the next refill
+        bucket.acquire(&LIMIT, 12 * NANOS_PER_SECOND).unwrap();
+        assert!(bucket.acquire(&LIMIT, 19 * NANOS_PER_SECOND).is_err());
+        bucket.acquire(&LIMIT, 20 * NANOS_PER_SECOND).unwrap();
+
+        // Long idle periods never exceed the capacity
+        assert!(bucket.is_full(&LIMIT, 1000 * NANOS_PER_SECOND));
+        bucket.refill(&LIMIT, 1000 * NANOS_PER_SECOND);
+        assert_eq!(bucket.tokens(), &2);
+    }
+
+    #[test]
+    fn test_validate_settings() {
+        let mut settings = RateLimitSettings {
+            sign: Some(LIMIT),
+            ..Default::default()
+        };
+        assert!(validate_rate_limit_settings(&settings).is_ok());
+        settings.create_account = Some(RateLimit {
+            capacity: 0,
+            refill_seconds: 1,
+        });
+        assert!(validate_rate_limit_settings(&settings).is_err());
+    }
+}
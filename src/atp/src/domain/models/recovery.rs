@@ -0,0 +1,75 @@
+use candid::{CandidType, Principal};
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+const NANOS_PER_SECOND: u64 = 1_000_000_000;
+/// Time-lock applied to a recovery when the owner did not configure one (two days)
+pub const DEFAULT_RECOVERY_TIME_LOCK_SECONDS: u64 = 2 * 24 * 60 * 60;
+/// Shortest time-lock an owner can configure, leaving time to cancel a malicious recovery
+pub const MIN_RECOVERY_TIME_LOCK_SECONDS: u64 = 60 * 60;
+/// Longest time-lock an owner can configure
+pub const MAX_RECOVERY_TIME_LOCK_SECONDS: u64 = 30 * 24 * 60 * 60;
+
+/// A pending transfer of ownership started by the guardians of an account
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct RecoveryRequest {
+    new_owner: Principal,
+    initiator: Principal,
+    confirmations: Vec<Principal>,
+    initiated_at: u64,
+    unlocks_at: u64,
+}
+
+impl RecoveryRequest {
+    // Constructor method, the initiating guardian confirms the recovery right away
+    pub fn new(
+        new_owner: Principal,
+        initiator: Principal,
+        initiated_at: u64,
+        time_lock_seconds: u64,
+    ) -> Self {
+        RecoveryRequest {
+            new_owner,
+            initiator,
+            confirmations: vec![initiator],
+            initiated_at,
+            unlocks_at: initiated_at + time_lock_seconds * NANOS_PER_SECOND,
+        }
+    }
+
+    generate_getters!(
+        new_owner: Principal,
+        initiator: Principal,
+        confirmations: Vec<Principal>,
+        initiated_at: u64,
+        unlocks_at: u64
+    );
+
+    // Method to check if the time-lock has elapsed
+    pub fn is_unlocked(&self, now: u64) -> bool {
+        now >= self.unlocks_at
+    }
+
+    // Record the confirmation of a guardian
+    pub fn confirm(&mut self, guardian: Principal) -> Result<(), String> {
+        if self.confirmations.contains(&guardian) {
+            return Err("Guardian has already confirmed the recovery".to_string());
+        }
+        self.confirmations.push(guardian);
+        Ok(())
+    }
+}
+
+// Check that a recovery time-lock lies within the allowed bounds
+pub fn validate_recovery_time_lock(time_lock_seconds: u64) -> Result<(), String> {
+    if !(MIN_RECOVERY_TIME_LOCK_SECONDS..=MAX_RECOVERY_TIME_LOCK_SECONDS)
+        .contains(&time_lock_seconds)
+    {
+        return Err(format!(
+            "Recovery time-lock must be between {} and {} seconds",
+            MIN_RECOVERY_TIME_LOCK_SECONDS, MAX_RECOVERY_TIME_LOCK_SECONDS
+        ));
+    }
+    Ok(())
+}
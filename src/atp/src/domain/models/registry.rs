@@ -0,0 +1,218 @@
+use atp_caip::{ChainId, TokenPair};
+use atp_chain_registry::{AssetConfig, ChainConfig, RegistryConfig};
+use candid::CandidType;
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum RegistryEntryKind {
+    #[serde(rename = "chain")]
+    Chain,
+    #[serde(rename = "asset")]
+    Asset,
+    #[serde(rename = "token_pair")]
+    TokenPair,
+}
+
+impl RegistryEntryKind {
+    // The partition the entries of this kind are stored in
+    pub fn partition(&self) -> &'static str {
+        match self {
+            RegistryEntryKind::Chain => "chain",
+            RegistryEntryKind::Asset => "asset",
+            RegistryEntryKind::TokenPair => "token_pair",
+        }
+    }
+}
+
+/// A chain, asset or token pair of the chain registry persisted in stable memory
+///
+/// Chain and asset metadata hold arbitrary TOML values that have no Candid
+/// representation, so each entry keeps its configuration as JSON. Removed
+/// entries are kept as tombstones.
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RegistryEntry {
+    kind: RegistryEntryKind,
+    key: String,
+    config_json: String,
+    removed: bool,
+    updated_at: u64,
+}
+
+impl RegistryEntry {
+    // Constructor method for a chain entry, keyed by chain ID
+    pub fn chain(chain: &ChainConfig, now: u64) -> Result<Self, String> {
+        Self::new(RegistryEntryKind::Chain, chain.chain_id.clone(), chain, now)
+    }
+
+    // Constructor method for an asset entry, keyed by asset ID base
+    pub fn asset(asset: &AssetConfig, now: u64) -> Result<Self, String> {
+        Self::new(
+            RegistryEntryKind::Asset,
+            asset.asset_id_base.to_string(),
+            asset,
+            now,
+        )
+    }
+
+    // Constructor method for a token pair entry, keyed by pair string
+    pub fn token_pair(pair: &TokenPair, now: u64) -> Result<Self, String> {
+        Self::new(
+            RegistryEntryKind::TokenPair,
+            pair.to_pair_string(),
+            pair,
+            now,
+        )
+    }
+
+    fn new<T: Serialize>(
+        kind: RegistryEntryKind,
+        key: String,
+        config: &T,
+        now: u64,
+    ) -> Result<Self, String> {
+        let config_json = serde_json::to_string(config)
+            .map_err(|e| format!("Failed to encode registry entry {}: {}", key, e))?;
+        Ok(RegistryEntry {
+            kind,
+            key,
+            config_json,
+            removed: false,
+            updated_at: now,
+        })
+    }
+
+    generate_getters!(
+        kind: RegistryEntryKind,
+        key: String,
+        config_json: String,
+        removed: bool,
+        updated_at: u64
+    );
+
+    // Turn the entry into a tombstone
+    pub fn remove(&mut self, now: u64) {
+        self.removed = true;
+        self.updated_at = now;
+    }
+}
+
+impl Model for RegistryEntry {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.key.clone()
+    }
+
+    fn model_name() -> &'static str {
+        "registry"
+    }
+}
+
+/// Split a registry configuration into entries
+pub fn registry_entries(config: &RegistryConfig, now: u64) -> Result<Vec<RegistryEntry>, String> {
+    let mut entries = Vec::new();
+    for chain in config.chains.values() {
+        entries.push(RegistryEntry::chain(chain, now)?);
+    }
+    for asset in config.assets.values() {
+        entries.push(RegistryEntry::asset(asset, now)?);
+    }
+    for pair in &config.token_pairs {
+        entries.push(RegistryEntry::token_pair(pair, now)?);
+    }
+    Ok(entries)
+}
+
+/// Assemble the registry configuration from its live entries
+pub fn registry_config(entries: &[RegistryEntry]) -> Result<RegistryConfig, String> {
+    let mut config = RegistryConfig::new();
+    for entry in entries.iter().filter(|entry| !entry.removed) {
+        let decode_error =
+            |e: serde_json::Error| format!("Failed to decode registry entry {}: {}", entry.key, e);
+        match entry.kind {
+            RegistryEntryKind::Chain => {
+                let chain: ChainConfig =
+                    serde_json::from_str(&entry.config_json).map_err(decode_error)?;
+                config.chains.insert(entry.key.clone(), chain);
+            }
+            RegistryEntryKind::Asset => {
+                let asset: AssetConfig =
+                    serde_json::from_str(&entry.config_json).map_err(decode_error)?;
+                config.assets.insert(entry.key.clone(), asset);
+            }
+            RegistryEntryKind::TokenPair => {
+                let pair: TokenPair =
+                    serde_json::from_str(&entry.config_json).map_err(decode_error)?;
+                config.token_pairs.push(pair);
+            }
+        }
+    }
+    Ok(config)
+}
+
+/// Look up a chain, falling back to the wildcard chain of its namespace
+pub fn find_chain<'a>(
+    config: &'a RegistryConfig,
+    chain_id: &ChainId,
+) -> Result<&'a ChainConfig, String> {
+    if let Some(chain) = config.chains.get(&chain_id.to_string()) {
+        return Ok(chain);
+    }
+    let wildcard = chain_id
+        .to_wildcard()
+        .map_err(|e| format!("Invalid chain ID {}: {}", chain_id, e))?;
+    config
+        .chains
+        .get(&wildcard.to_string())
+        .ok_or_else(|| format!("Chain {} not found", chain_id))
+}
+
+#[cfg(test)]
+mod registry_tests {
+    use super::*;
+    use atp_chain_registry::{ChainRegistry, DEFAULT_CONFIG};
+    use std::str::FromStr;
+
+    #[test]
+    fn test_entries_round_trip() {
+        let config = ChainRegistry::config_from_toml(DEFAULT_CONFIG).unwrap();
+        let mut entries = registry_entries(&config, 1).unwrap();
+
+        let decoded = registry_config(&entries).unwrap();
+        assert_eq!(decoded.chains.len(), config.chains.len());
+        assert_eq!(decoded.assets.len(), config.assets.len());
+        assert_eq!(decoded.token_pairs.len(), config.token_pairs.len());
+        assert_eq!(
+            decoded.chains["eip155:1"].metadata["gas_token"],
+            config.chains["eip155:1"].metadata["gas_token"]
+        );
+
+        // Tombstones are left out of the configuration
+        let chain = entries
+            .iter_mut()
+            .find(|entry| entry.key() == "eip155:1")
+            .unwrap();
+        chain.remove(2);
+        let decoded = registry_config(&entries).unwrap();
+        assert!(!decoded.chains.contains_key("eip155:1"));
+    }
+
+    #[test]
+    fn test_find_chain_falls_back_to_wildcard() {
+        let mut config = ChainRegistry::config_from_toml(DEFAULT_CONFIG).unwrap();
+        let chain_id = ChainId::from_str("eip155:137").unwrap();
+        assert!(find_chain(&config, &chain_id).is_err());
+
+        let mut wildcard = config.chains["eip155:1"].clone();
+        wildcard.chain_id = "eip155:*".to_string();
+        config.chains.insert(wildcard.chain_id.clone(), wildcard);
+        assert_eq!(find_chain(&config, &chain_id).unwrap().chain_id, "eip155:*");
+
+        let chain_id = ChainId::from_str("eip155:1").unwrap();
+        assert_eq!(find_chain(&config, &chain_id).unwrap().chain_id, "eip155:1");
+    }
+}
@@ -0,0 +1,108 @@
+use candid::{CandidType, Principal};
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::generate_getters;
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum Role {
+    /// Manages roles and the canister configuration
+    #[serde(rename = "admin")]
+    Admin,
+    /// Runs day-to-day maintenance operations
+    #[serde(rename = "operator")]
+    Operator,
+    /// Reads the canister state without changing it
+    #[serde(rename = "auditor")]
+    Auditor,
+}
+
+impl Role {
+    // Method to check if holding this role grants the permissions of the other role
+    pub fn includes(&self, other: &Role) -> bool {
+        self == other || *self == Role::Admin
+    }
+}
+
+/// The roles held by a principal
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct RoleAssignment {
+    principal: Principal,
+    roles: Vec<Role>,
+    updated_at: u64,
+}
+
+impl RoleAssignment {
+    // Constructor method for a principal without roles
+    pub fn new(principal: Principal, updated_at: u64) -> Self {
+        RoleAssignment {
+            principal,
+            roles: Vec::new(),
+            updated_at,
+        }
+    }
+
+    generate_getters!(principal: Principal, roles: Vec<Role>, updated_at: u64);
+
+    // Method to check if any held role grants the permissions of the role
+    pub fn has_role(&self, role: &Role) -> bool {
+        self.roles.iter().any(|held| held.includes(role))
+    }
+
+    // Add a role to the principal
+    pub fn grant(&mut self, role: Role, now: u64) -> Result<(), String> {
+        if self.roles.contains(&role) {
+            return Err(format!("Principal already has the {:?} role", role));
+        }
+        self.roles.push(role);
+        self.updated_at = now;
+        Ok(())
+    }
+
+    // Remove a role from the principal
+    pub fn revoke(&mut self, role: &Role, now: u64) -> Result<(), String> {
+        if !self.roles.contains(role) {
+            return Err(format!("Principal does not have the {:?} role", role));
+        }
+        self.roles.retain(|held| held != role);
+        self.updated_at = now;
+        Ok(())
+    }
+}
+
+impl Model for RoleAssignment {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.principal.to_string()
+    }
+
+    fn model_name() -> &'static str {
+        "roles"
+    }
+}
+
+#[cfg(test)]
+mod role_tests {
+    use super::*;
+
+    #[test]
+    fn test_admin_includes_other_roles() {
+        let mut assignment = RoleAssignment::new(Principal::anonymous(), 0);
+        assert!(!assignment.has_role(&Role::Auditor));
+
+        assignment.grant(Role::Auditor, 1).unwrap();
+        assert!(assignment.has_role(&Role::Auditor));
+        assert!(!assignment.has_role(&Role::Operator));
+        assert!(assignment.grant(Role::Auditor, 2).is_err());
+
+        assignment.grant(Role::Admin, 3).unwrap();
+        assert!(assignment.has_role(&Role::Operator));
+
+        assignment.revoke(&Role::Admin, 4).unwrap();
+        assert!(!assignment.has_role(&Role::Operator));
+        assert!(assignment.revoke(&Role::Admin, 5).is_err());
+        assert_eq!(assignment.updated_at(), &4);
+    }
+}
@@ -1,4 +1,5 @@
-use candid::{CandidType, Decode, Encode};
+use candid::CandidType;
+use ic_nosql::utils::serialization::{decode_storable, encode_storable};
 use ic_stable_structures::{storable::Bound, Storable};
 use serde::{Deserialize, Serialize};
 use std::borrow::Cow;
@@ -12,12 +13,13 @@ pub enum SignatureAlgorithm {
 }
 
 impl Storable for SignatureAlgorithm {
-    fn to_bytes(&self) -> Cow<[u8]> {
-        Cow::Owned(Encode!(self).unwrap())
+    fn to_bytes(&self) -> Cow<'_, [u8]> {
+        encode_storable(self)
     }
 
+    // A corrupt algorithm is reported and traps, rather than being read as another algorithm
     fn from_bytes(bytes: Cow<[u8]>) -> Self {
-        Decode!(bytes.as_ref(), SignatureAlgorithm).unwrap()
+        decode_storable(bytes.as_ref())
     }
 
     const BOUND: Bound = Bound::Unbounded;
@@ -0,0 +1,416 @@
+use candid::{CandidType, Principal};
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::application::dtos::eip1559::Eip1559TransactionRequestDTO;
+use crate::domain::models::account::Account;
+use crate::generate_getters;
+use crate::utils::ic::api::get_ic_api;
+
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum SigningProposalStatus {
+    #[serde(rename = "pending")]
+    Pending,
+    #[serde(rename = "approved")]
+    Approved,
+    #[serde(rename = "executed")]
+    Executed,
+    #[serde(rename = "cancelled")]
+    Cancelled,
+    #[serde(rename = "expired")]
+    Expired,
+}
+
+/// The signing request held by a proposal until enough guardians approve it
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum SigningPayload {
+    #[serde(rename = "message")]
+    Message { message_hex: String },
+    #[serde(rename = "eip1559_transaction")]
+    Eip1559Transaction {
+        tx_request: Eip1559TransactionRequestDTO,
+    },
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SigningProposal {
+    id: String,
+    account_id: String,
+    proposer: Principal,
+    payload: SigningPayload,
+    approvals: Vec<Principal>,
+    status: SigningProposalStatus,
+    created_at: u64,
+    expires_at: u64,
+    signature: Option<String>,
+    // Owner, ownership epoch and key version of the account at creation, missing from proposals
+    // created before they were recorded, which can no longer be signed
+    account_owner: Option<Principal>,
+    key_version: Option<u32>,
+    ownership_epoch: Option<u32>,
+}
+
+impl SigningProposal {
+    // Constructor method for creating a new pending proposal, bound to the current owner and key
+    pub fn new(
+        id: String,
+        account: &Account,
+        proposer: Principal,
+        payload: SigningPayload,
+        created_at: u64,
+        expires_at: u64,
+    ) -> Self {
+        SigningProposal {
+            id,
+            account_id: account.id().clone(),
+            proposer,
+            payload,
+            approvals: Vec::new(),
+            status: SigningProposalStatus::Pending,
+            created_at,
+            expires_at,
+            signature: None,
+            account_owner: Some(*account.owner()),
+            key_version: Some(account.key_version()),
+            ownership_epoch: Some(account.ownership_epoch()),
+        }
+    }
+
+    generate_getters!(
+        id: String,
+        account_id: String,
+        proposer: Principal,
+        payload: SigningPayload,
+        approvals: Vec<Principal>,
+        status: SigningProposalStatus,
+        created_at: u64,
+        expires_at: u64,
+        signature: Option<String>
+    );
+
+    // Method to check if the proposal has passed its expiry time
+    pub fn is_expired(&self, now: u64) -> bool {
+        now >= self.expires_at
+    }
+
+    // Method to check if the proposal is still waiting for a decision
+    pub fn is_open(&self) -> bool {
+        matches!(
+            self.status,
+            SigningProposalStatus::Pending | SigningProposalStatus::Approved
+        )
+    }
+
+    // Method to check if the account still has the owner and key the proposal was created for
+    //
+    // The ownership epoch keeps the proposal closed if the account returns to its owner.
+    pub fn matches_account(&self, account: &Account) -> bool {
+        self.account_owner == Some(*account.owner())
+            && self.ownership_epoch == Some(account.ownership_epoch())
+            && self.key_version == Some(account.key_version())
+    }
+
+    // Count the approvals given by principals that are still guardians of the account
+    pub fn approval_count(&self, account: &Account) -> usize {
+        self.approvals
+            .iter()
+            .filter(|approver| account.is_guardian(**approver))
+            .count()
+    }
+
+    // Approve the proposal, only allowed for guardians of the account
+    pub fn approve(&mut self, account: &Account) -> Result<SigningProposal, String> {
+        let ic_api = get_ic_api();
+        let caller = ic_api.caller();
+
+        let guardian_set = match account.guardians() {
+            Some(guardian_set) => guardian_set,
+            None => return Err("Account does not require guardian approval".to_string()),
+        };
+        if !guardian_set.is_guardian(caller) {
+            return Err("Caller is not a guardian of the account".to_string());
+        }
+        if self.status != SigningProposalStatus::Pending {
+            return Err("Proposal is not pending".to_string());
+        }
+        if !self.matches_account(account) {
+            self.status = SigningProposalStatus::Cancelled;
+            return Err("Account owner or key changed since the proposal was created".to_string());
+        }
+        if self.is_expired(ic_api.time()) {
+            self.status = SigningProposalStatus::Expired;
+            return Err("Proposal has expired".to_string());
+        }
+        if self.approvals.contains(&caller) {
+            return Err("Caller has already approved the proposal".to_string());
+        }
+
+        self.approvals.push(caller);
+        if self.approval_count(account) >= *guardian_set.threshold() as usize {
+            self.status = SigningProposalStatus::Approved;
+        }
+        Ok(self.clone())
+    }
+
+    // Cancel the proposal, only allowed for the proposer while it is open
+    pub fn cancel(&mut self) -> Result<SigningProposal, String> {
+        let ic_api = get_ic_api();
+        if self.proposer != ic_api.caller() {
+            return Err("Caller is not the proposer".to_string());
+        }
+        if !self.is_open() {
+            return Err("Proposal is not open".to_string());
+        }
+        self.status = SigningProposalStatus::Cancelled;
+        Ok(self.clone())
+    }
+
+    // Expire the proposal if it is open and past its expiry time
+    pub fn expire(&mut self) -> Result<SigningProposal, String> {
+        let ic_api = get_ic_api();
+        if !self.is_open() {
+            return Err("Proposal is not open".to_string());
+        }
+        if !self.is_expired(ic_api.time()) {
+            return Err("Proposal has not expired yet".to_string());
+        }
+        self.status = SigningProposalStatus::Expired;
+        Ok(self.clone())
+    }
+
+    // Check that the approved proposal can still be signed for the account
+    //
+    // Proposals whose account changed owner or key are cancelled and expired ones expire.
+    // Proposals that lost approvals to a guardian change go back to pending.
+    pub fn check_executable(&mut self, account: &Account) -> Result<(), String> {
+        let ic_api = get_ic_api();
+        if self.status != SigningProposalStatus::Approved {
+            return Err("Proposal is not approved".to_string());
+        }
+        if !self.matches_account(account) {
+            self.status = SigningProposalStatus::Cancelled;
+            return Err("Account owner or key changed since the proposal was created".to_string());
+        }
+        if self.is_expired(ic_api.time()) {
+            self.status = SigningProposalStatus::Expired;
+            return Err("Proposal has expired".to_string());
+        }
+        let threshold = match account.guardians() {
+            Some(guardian_set) => *guardian_set.threshold() as usize,
+            None => return Err("Account does not require guardian approval".to_string()),
+        };
+        if self.approval_count(account) < threshold {
+            self.status = SigningProposalStatus::Pending;
+            return Err("Proposal no longer reaches the guardian threshold".to_string());
+        }
+        Ok(())
+    }
+
+    // Record the threshold signature once the approved proposal has been signed
+    pub fn mark_executed(&mut self, signature: String) -> Result<SigningProposal, String> {
+        if self.status != SigningProposalStatus::Approved {
+            return Err("Proposal is not approved".to_string());
+        }
+        self.status = SigningProposalStatus::Executed;
+        self.signature = Some(signature);
+        Ok(self.clone())
+    }
+}
+
+impl Model for SigningProposal {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.id.clone()
+    }
+
+    fn model_name() -> &'static str {
+        "signing_proposals"
+    }
+}
+
+#[cfg(test)]
+mod signing_proposal_tests {
+    use candid::Principal;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::domain::models::guardian::GuardianSet;
+    use crate::domain::models::recovery::DEFAULT_RECOVERY_TIME_LOCK_SECONDS;
+    use crate::domain::models::signer::SignatureAlgorithm;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+    use atp_caip::curve::Curve;
+
+    const NOW: u64 = 1_000_000;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn set_caller(caller: Principal, time: u64) {
+        set_ic_api(Rc::new(
+            MockIcApi::new().with_caller(caller).with_time(time),
+        ));
+    }
+
+    // Helper function to create an account guarded by a 2-of-3 guardian set
+    fn create_guarded_account() -> Account {
+        let owner = principal(1);
+        set_caller(owner, NOW);
+        let mut account = Account::new(
+            "account-1".to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            owner,
+        );
+        let guardians =
+            GuardianSet::new(vec![principal(2), principal(3), principal(4)], 2).unwrap();
+        account.set_guardians(Some(guardians)).unwrap();
+        account
+    }
+
+    fn create_proposal(account: &Account) -> SigningProposal {
+        SigningProposal::new(
+            "proposal-1".to_string(),
+            account,
+            principal(1),
+            SigningPayload::Message {
+                message_hex: "00".to_string(),
+            },
+            NOW,
+            NOW + 100,
+        )
+    }
+
+    #[test]
+    fn test_threshold_approval() {
+        let account = create_guarded_account();
+        let mut proposal = create_proposal(&account);
+
+        set_caller(principal(2), NOW + 1);
+        proposal.approve(&account).unwrap();
+        assert_eq!(proposal.status(), &SigningProposalStatus::Pending);
+
+        // The same guardian cannot approve twice
+        assert!(proposal.approve(&account).is_err());
+
+        set_caller(principal(3), NOW + 2);
+        proposal.approve(&account).unwrap();
+        assert_eq!(proposal.status(), &SigningProposalStatus::Approved);
+
+        proposal.check_executable(&account).unwrap();
+        proposal.mark_executed("signature".to_string()).unwrap();
+        assert_eq!(proposal.status(), &SigningProposalStatus::Executed);
+        assert_eq!(proposal.signature(), &Some("signature".to_string()));
+    }
+
+    #[test]
+    fn test_non_guardian_cannot_approve() {
+        let account = create_guarded_account();
+        let mut proposal = create_proposal(&account);
+
+        set_caller(principal(1), NOW + 1);
+        assert!(proposal.approve(&account).is_err());
+        assert!(proposal.approvals().is_empty());
+    }
+
+    #[test]
+    fn test_expired_proposal() {
+        let account = create_guarded_account();
+        let mut proposal = create_proposal(&account);
+
+        set_caller(principal(2), NOW + 50);
+        assert!(proposal.expire().is_err());
+
+        set_caller(principal(2), NOW + 100);
+        assert!(proposal.approve(&account).is_err());
+        assert_eq!(proposal.status(), &SigningProposalStatus::Expired);
+    }
+
+    #[test]
+    fn test_cancel_only_by_proposer() {
+        let account = create_guarded_account();
+        let mut proposal = create_proposal(&account);
+
+        set_caller(principal(2), NOW + 1);
+        assert!(proposal.cancel().is_err());
+
+        set_caller(principal(1), NOW + 1);
+        proposal.cancel().unwrap();
+        assert_eq!(proposal.status(), &SigningProposalStatus::Cancelled);
+        assert!(proposal.cancel().is_err());
+    }
+
+    // Helper function to approve the proposal with the first two guardians
+    fn approve_proposal(proposal: &mut SigningProposal, account: &Account) {
+        set_caller(principal(2), NOW + 1);
+        proposal.approve(account).unwrap();
+        set_caller(principal(3), NOW + 1);
+        proposal.approve(account).unwrap();
+        assert_eq!(proposal.status(), &SigningProposalStatus::Approved);
+    }
+
+    #[test]
+    fn test_expired_approval_cannot_execute() {
+        let account = create_guarded_account();
+        let mut proposal = create_proposal(&account);
+        approve_proposal(&mut proposal, &account);
+
+        set_caller(principal(1), NOW + 100);
+        assert!(proposal.check_executable(&account).is_err());
+        assert_eq!(proposal.status(), &SigningProposalStatus::Expired);
+    }
+
+    #[test]
+    fn test_change_of_owner_cancels_proposal() {
+        let mut account = create_guarded_account();
+        let mut proposal = create_proposal(&account);
+        let mut pending = create_proposal(&account);
+        approve_proposal(&mut proposal, &account);
+
+        // The guardians recover the account to a new owner
+        set_caller(principal(1), NOW + 2);
+        account.unlock().unwrap();
+        set_caller(principal(2), NOW + 2);
+        account.start_recovery(principal(9)).unwrap();
+        set_caller(principal(3), NOW + 2);
+        account.confirm_recovery().unwrap();
+        set_caller(
+            principal(9),
+            NOW + 2 + DEFAULT_RECOVERY_TIME_LOCK_SECONDS * 1_000_000_000,
+        );
+        account.complete_recovery().unwrap();
+
+        assert!(proposal.check_executable(&account).is_err());
+        assert_eq!(proposal.status(), &SigningProposalStatus::Cancelled);
+
+        // Pending proposals are cancelled on their next approval
+        set_caller(principal(2), NOW + 3);
+        assert!(pending.approve(&account).is_err());
+        assert_eq!(pending.status(), &SigningProposalStatus::Cancelled);
+    }
+
+    #[test]
+    fn test_guardian_change_reopens_approved_proposal() {
+        let mut account = create_guarded_account();
+        let mut proposal = create_proposal(&account);
+        approve_proposal(&mut proposal, &account);
+
+        // Replace the guardians that approved the proposal
+        let guardians = GuardianSet::new(vec![principal(4), principal(5)], 2).unwrap();
+        set_caller(principal(1), NOW + 2);
+        account.set_guardians(Some(guardians)).unwrap();
+        set_caller(principal(2), NOW + 2);
+        account.approve_guardian_change().unwrap();
+        set_caller(principal(3), NOW + 2);
+        account.approve_guardian_change().unwrap();
+
+        set_caller(principal(1), NOW + 3);
+        assert!(proposal.check_executable(&account).is_err());
+        assert_eq!(proposal.status(), &SigningProposalStatus::Pending);
+    }
+}
@@ -0,0 +1,384 @@
+use atp_caip::chain_id::ChainId;
+use candid::{CandidType, Principal};
+use ic_nosql::traits::Model;
+use serde::{Deserialize, Serialize};
+
+use crate::domain::models::account::Account;
+use crate::generate_getters;
+use crate::utils::ic::api::get_ic_api;
+
+/// The signing endpoints a session can be scoped to
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub enum SigningMethod {
+    #[serde(rename = "message")]
+    Message,
+    #[serde(rename = "eip1559_transaction")]
+    Eip1559Transaction,
+}
+
+/// A signing request checked against the scope of a session
+pub struct SigningScope {
+    pub method: SigningMethod,
+    pub chain_id: Option<ChainId>,
+    pub value: Option<u128>,
+}
+
+/// The limits an owner places on what a session can sign
+#[derive(CandidType, Clone, Serialize, Deserialize, PartialEq, Debug)]
+pub struct SessionLimits {
+    pub max_signatures: u32,
+    pub allowed_chains: Vec<ChainId>,
+    pub allowed_methods: Vec<SigningMethod>,
+    pub max_value: Option<u128>,
+}
+
+/// A time-limited grant letting a delegate sign with an account on behalf of its owner
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct SigningSession {
+    id: String,
+    account_id: String,
+    granted_by: Principal,
+    delegate: Principal,
+    created_at: u64,
+    expires_at: u64,
+    limits: SessionLimits,
+    signatures_used: u32,
+    revoked: bool,
+    // Ownership epoch of the account at grant time, missing from sessions granted before it
+    // was recorded, which are no longer active
+    ownership_epoch: Option<u32>,
+}
+
+impl SigningSession {
+    // Constructor method for creating a new session granted by the account owner
+    pub fn new(
+        id: String,
+        account: &Account,
+        delegate: Principal,
+        created_at: u64,
+        expires_at: u64,
+        limits: SessionLimits,
+    ) -> Self {
+        SigningSession {
+            id,
+            account_id: account.id().clone(),
+            granted_by: *account.owner(),
+            delegate,
+            created_at,
+            expires_at,
+            limits,
+            signatures_used: 0,
+            revoked: false,
+            ownership_epoch: Some(account.ownership_epoch()),
+        }
+    }
+
+    generate_getters!(
+        id: String,
+        account_id: String,
+        granted_by: Principal,
+        delegate: Principal,
+        created_at: u64,
+        expires_at: u64,
+        limits: SessionLimits,
+        signatures_used: u32,
+        revoked: bool
+    );
+
+    // Method to check if the session can still sign for the account
+    //
+    // Sessions are bound to the owner that granted them and its ownership epoch, so they
+    // end as soon as the account changes hands, even if it later returns to the granter.
+    pub fn is_active(&self, account: &Account, now: u64) -> bool {
+        !self.revoked
+            && now < self.expires_at
+            && self.signatures_used < self.limits.max_signatures
+            && account.is_owner(self.granted_by)
+            && self.ownership_epoch == Some(account.ownership_epoch())
+    }
+
+    // Method to check if the session is scoped to the chain
+    pub fn allows_chain(&self, chain_id: &ChainId) -> bool {
+        self.limits.allowed_chains.is_empty()
+            || self.limits.allowed_chains.iter().any(|allowed| {
+                allowed == chain_id
+                    || (allowed.namespace() == chain_id.namespace() && allowed.reference() == "*")
+            })
+    }
+
+    // Method to check if the session is scoped to the signing method
+    //
+    // Raw messages can encode a transaction for any chain and value, so sessions limiting
+    // either only sign them if the owner listed the message method explicitly.
+    pub fn allows_method(&self, method: &SigningMethod) -> bool {
+        if self.limits.allowed_methods.contains(method) {
+            return true;
+        }
+        let limits_transactions =
+            !self.limits.allowed_chains.is_empty() || self.limits.max_value.is_some();
+        self.limits.allowed_methods.is_empty()
+            && !(method == &SigningMethod::Message && limits_transactions)
+    }
+
+    // Consume one signature of the session, only allowed for the delegate within the session scope
+    pub fn authorize(&mut self, account: &Account, scope: &SigningScope) -> Result<(), String> {
+        let ic_api = get_ic_api();
+        if self.delegate != ic_api.caller() {
+            return Err("Caller is not the delegate of the session".to_string());
+        }
+        if !self.is_active(account, ic_api.time()) {
+            return Err("Session is not active".to_string());
+        }
+        if !self.allows_method(&scope.method) {
+            return Err("Signing method is not allowed by the session".to_string());
+        }
+        if !self.limits.allowed_chains.is_empty() {
+            match &scope.chain_id {
+                Some(chain_id) if self.allows_chain(chain_id) => {}
+                Some(chain_id) => {
+                    return Err(format!("Chain {} is not allowed by the session", chain_id))
+                }
+                None => return Err("Session requires a chain ID".to_string()),
+            }
+        }
+        if let Some(max_value) = self.limits.max_value {
+            if scope.value.unwrap_or(0) > max_value {
+                return Err("Value exceeds the session cap".to_string());
+            }
+        }
+
+        self.signatures_used += 1;
+        Ok(())
+    }
+
+    // Revoke the session, only allowed for the account owner
+    pub fn revoke(&mut self, account: &Account) -> Result<SigningSession, String> {
+        let ic_api = get_ic_api();
+        if !account.is_owner(ic_api.caller()) {
+            return Err("Caller is not the owner of the account".to_string());
+        }
+        if self.revoked {
+            return Err("Session is already revoked".to_string());
+        }
+        self.revoked = true;
+        Ok(self.clone())
+    }
+}
+
+impl Model for SigningSession {
+    type PrimaryKey = String;
+    type SecondaryKey = String;
+
+    fn get_primary_key(&self) -> Self::PrimaryKey {
+        self.id.clone()
+    }
+
+    fn model_name() -> &'static str {
+        "signing_sessions"
+    }
+}
+
+#[cfg(test)]
+mod signing_session_tests {
+    use candid::Principal;
+    use std::rc::Rc;
+    use std::str::FromStr;
+
+    use super::*;
+    use crate::domain::models::signer::SignatureAlgorithm;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+    use atp_caip::curve::Curve;
+
+    const NOW: u64 = 1_000_000;
+
+    fn principal(id: u8) -> Principal {
+        Principal::from_slice(&[id; 29])
+    }
+
+    fn set_caller(caller: Principal, time: u64) {
+        set_ic_api(Rc::new(
+            MockIcApi::new().with_caller(caller).with_time(time),
+        ));
+    }
+
+    fn create_account_owned_by(owner: Principal) -> Account {
+        Account::new(
+            "account-1".to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            owner,
+        )
+    }
+
+    fn create_account() -> Account {
+        create_account_owned_by(principal(1))
+    }
+
+    // Helper function to create a two-signature session scoped to Ethereum transactions
+    fn create_session() -> SigningSession {
+        SigningSession::new(
+            "session-1".to_string(),
+            &create_account(),
+            principal(2),
+            NOW,
+            NOW + 100,
+            SessionLimits {
+                max_signatures: 2,
+                allowed_chains: vec![ChainId::from_str("eip155:*").unwrap()],
+                allowed_methods: vec![SigningMethod::Eip1559Transaction],
+                max_value: Some(1_000),
+            },
+        )
+    }
+
+    fn eth_scope(value: u128) -> SigningScope {
+        SigningScope {
+            method: SigningMethod::Eip1559Transaction,
+            chain_id: Some(ChainId::from_str("eip155:1").unwrap()),
+            value: Some(value),
+        }
+    }
+
+    #[test]
+    fn test_authorize_within_scope() {
+        let account = create_account();
+        let mut session = create_session();
+
+        set_caller(principal(2), NOW + 1);
+        session.authorize(&account, &eth_scope(1_000)).unwrap();
+        session.authorize(&account, &eth_scope(0)).unwrap();
+        assert_eq!(session.signatures_used(), &2);
+
+        // The signature budget is exhausted
+        assert!(session.authorize(&account, &eth_scope(0)).is_err());
+    }
+
+    #[test]
+    fn test_authorize_outside_scope() {
+        let account = create_account();
+        let mut session = create_session();
+
+        set_caller(principal(3), NOW + 1);
+        assert!(session.authorize(&account, &eth_scope(0)).is_err());
+
+        set_caller(principal(2), NOW + 1);
+        assert!(session.authorize(&account, &eth_scope(1_001)).is_err());
+        let solana_scope = SigningScope {
+            method: SigningMethod::Eip1559Transaction,
+            chain_id: Some(ChainId::from_str("solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp").unwrap()),
+            value: None,
+        };
+        assert!(session.authorize(&account, &solana_scope).is_err());
+        let message_scope = SigningScope {
+            method: SigningMethod::Message,
+            chain_id: Some(ChainId::from_str("eip155:1").unwrap()),
+            value: None,
+        };
+        assert!(session.authorize(&account, &message_scope).is_err());
+
+        set_caller(principal(2), NOW + 100);
+        assert!(session.authorize(&account, &eth_scope(0)).is_err());
+        assert_eq!(session.signatures_used(), &0);
+    }
+
+    #[test]
+    fn test_raw_messages_need_explicit_method_on_limited_sessions() {
+        let account = create_account();
+        let message_scope = SigningScope {
+            method: SigningMethod::Message,
+            chain_id: Some(ChainId::from_str("eip155:1").unwrap()),
+            value: None,
+        };
+        let mut limits = SessionLimits {
+            max_signatures: 10,
+            allowed_chains: vec![],
+            allowed_methods: vec![],
+            max_value: Some(1_000),
+        };
+        let session = |limits: &SessionLimits| {
+            SigningSession::new(
+                "session-1".to_string(),
+                &account,
+                principal(2),
+                NOW,
+                NOW + 100,
+                limits.clone(),
+            )
+        };
+
+        // A value cap does not let the delegate sign arbitrary digests
+        set_caller(principal(2), NOW + 1);
+        assert!(session(&limits)
+            .authorize(&account, &message_scope)
+            .is_err());
+        assert!(session(&limits).authorize(&account, &eth_scope(1)).is_ok());
+
+        // Neither does a chain limit, whatever chain ID the caller claims
+        limits.max_value = None;
+        limits.allowed_chains = vec![ChainId::from_str("eip155:1").unwrap()];
+        assert!(session(&limits)
+            .authorize(&account, &message_scope)
+            .is_err());
+
+        // Unless the owner allowed raw messages explicitly
+        limits.allowed_methods = vec![SigningMethod::Message];
+        assert!(session(&limits).authorize(&account, &message_scope).is_ok());
+
+        // Sessions without chain or value limits sign messages by default
+        limits.allowed_methods = vec![];
+        limits.allowed_chains = vec![];
+        assert!(session(&limits).authorize(&account, &message_scope).is_ok());
+    }
+
+    #[test]
+    fn test_revoke_only_by_owner() {
+        let account = create_account();
+        let mut session = create_session();
+
+        set_caller(principal(2), NOW + 1);
+        assert!(session.revoke(&account).is_err());
+
+        set_caller(principal(1), NOW + 1);
+        session.revoke(&account).unwrap();
+        assert!(!session.is_active(&account, NOW + 1));
+
+        set_caller(principal(2), NOW + 1);
+        assert!(session.authorize(&account, &eth_scope(0)).is_err());
+    }
+
+    #[test]
+    fn test_session_ends_with_ownership() {
+        let mut session = create_session();
+
+        // The account now belongs to someone else than the granting owner
+        let account = create_account_owned_by(principal(5));
+        assert!(!session.is_active(&account, NOW + 1));
+
+        set_caller(principal(2), NOW + 1);
+        assert!(session.authorize(&account, &eth_scope(0)).is_err());
+    }
+
+    #[test]
+    fn test_session_stays_ended_when_account_returns() {
+        let session = create_session();
+        let mut account = create_account();
+
+        // The account is transferred away and back to the granting owner
+        set_caller(principal(1), NOW + 1);
+        account.approve_address(principal(9)).unwrap();
+        set_caller(principal(9), NOW + 1);
+        account.transfer_account(principal(5)).unwrap();
+        set_caller(principal(5), NOW + 1);
+        account.approve_address(principal(9)).unwrap();
+        set_caller(principal(9), NOW + 1);
+        account.lock().unwrap();
+        account.transfer_account(principal(1)).unwrap();
+
+        assert!(account.is_owner(principal(1)));
+        assert_eq!(account.ownership_epoch(), 2);
+        assert!(!session.is_active(&account, NOW + 1));
+    }
+}
@@ -1,2 +1,12 @@
 pub mod account_repository;
+pub mod audit_event_repository;
+pub mod canister_config_repository;
+pub mod fee_account_repository;
+pub mod job_repository;
+pub mod payment_repository;
+pub mod rate_limit_repository;
+pub mod registry_repository;
+pub mod role_repository;
 pub mod signer_repository;
+pub mod signing_proposal_repository;
+pub mod signing_session_repository;
@@ -1,6 +1,7 @@
-use crate::domain::models::account::Account;
+use crate::domain::models::account::{Account, AccountState};
 
 pub trait IAccountRepository {
+    // Store the account, failing if it was stored by someone else since it was read
     fn insert(&self, account: Account) -> Result<Account, String>;
     fn get(&self, id: &str) -> Result<Account, String>;
     fn exists(&self, id: &str) -> bool;
@@ -10,4 +11,28 @@ pub trait IAccountRepository {
         page_size: usize,
         page: usize,
     ) -> Result<Vec<Account>, String>;
+    // Accounts of the owner carrying the tag, pages past the end are empty
+    fn find_by_tag(
+        &self,
+        owner: &str,
+        tag: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String>;
+    // Accounts the address is approved on, pages past the end are empty
+    fn find_by_approved_address(
+        &self,
+        address: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String>;
+    // Accounts in the state, pages past the end are empty
+    fn find_by_state(
+        &self,
+        state: &AccountState,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String>;
+    // Accounts in ID order after the given ID, a short page is the last one
+    fn scan_after(&self, after: Option<&str>, limit: usize) -> Result<Vec<Account>, String>;
 }
@@ -0,0 +1,11 @@
+use crate::domain::models::audit_event::AuditEvent;
+
+pub trait IAuditEventRepository {
+    fn insert(&self, event: AuditEvent) -> Result<AuditEvent, String>;
+    fn find_by_account(
+        &self,
+        account_id: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<AuditEvent>, String>;
+}
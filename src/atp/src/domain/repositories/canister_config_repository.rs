@@ -0,0 +1,6 @@
+use crate::domain::models::canister_config::CanisterConfig;
+
+pub trait ICanisterConfigRepository {
+    fn insert(&self, config: CanisterConfig) -> Result<CanisterConfig, String>;
+    fn get(&self) -> Result<CanisterConfig, String>;
+}
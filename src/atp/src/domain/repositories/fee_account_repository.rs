@@ -0,0 +1,6 @@
+use crate::domain::models::fee::FeeAccount;
+
+pub trait IFeeAccountRepository {
+    fn insert(&self, account: FeeAccount) -> Result<FeeAccount, String>;
+    fn get(&self, id: &str) -> Result<FeeAccount, String>;
+}
@@ -0,0 +1,7 @@
+use crate::domain::models::job::Job;
+
+pub trait IJobRepository {
+    fn insert(&self, job: Job) -> Result<Job, String>;
+    fn get(&self, name: &str) -> Result<Job, String>;
+    fn find_all(&self) -> Result<Vec<Job>, String>;
+}
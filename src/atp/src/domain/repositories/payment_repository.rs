@@ -0,0 +1,54 @@
+use candid::{CandidType, Nat, Principal};
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+
+// Account on an ICRC-1 ledger
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct Icrc1Account {
+    pub owner: Principal,
+    pub subaccount: Option<Vec<u8>>,
+}
+
+// Arguments of an ICRC-1 transfer
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub struct Icrc1TransferArg {
+    pub from_subaccount: Option<Vec<u8>>,
+    pub to: Icrc1Account,
+    pub amount: Nat,
+    pub fee: Option<Nat>,
+    pub memo: Option<Vec<u8>>,
+    pub created_at_time: Option<u64>,
+}
+
+#[derive(CandidType, Clone, Serialize, Deserialize, Debug)]
+pub enum Icrc1TransferError {
+    BadFee { expected_fee: Nat },
+    BadBurn { min_burn_amount: Nat },
+    InsufficientFunds { balance: Nat },
+    TooOld,
+    CreatedInFuture { ledger_time: u64 },
+    Duplicate { duplicate_of: Nat },
+    TemporarilyUnavailable,
+    GenericError { error_code: Nat, message: String },
+}
+
+/// Interface for moving the funds fees are paid with
+pub trait IPaymentRepository {
+    fn balance_of(
+        &self,
+        ledger: Principal,
+        account: Icrc1Account,
+    ) -> impl Future<Output = Result<Nat, String>> + Send;
+
+    fn transfer(
+        &self,
+        ledger: Principal,
+        args: Icrc1TransferArg,
+    ) -> impl Future<Output = Result<Nat, String>> + Send;
+
+    fn deposit_cycles(
+        &self,
+        canister_id: Principal,
+        cycles: u128,
+    ) -> impl Future<Output = Result<(), String>> + Send;
+}
@@ -0,0 +1,10 @@
+use candid::Principal;
+
+use crate::domain::models::rate_limit::{RateLimitOperation, TokenBucket};
+
+pub trait IRateLimitRepository {
+    fn insert(&self, caller: Principal, operation: RateLimitOperation, bucket: TokenBucket);
+    fn get(&self, caller: &Principal, operation: &RateLimitOperation) -> Option<TokenBucket>;
+    fn remove(&self, caller: &Principal, operation: &RateLimitOperation);
+    fn find_all(&self) -> Vec<(Principal, RateLimitOperation, TokenBucket)>;
+}
@@ -0,0 +1,6 @@
+use crate::domain::models::registry::RegistryEntry;
+
+pub trait IRegistryRepository {
+    fn insert(&self, entry: RegistryEntry) -> Result<RegistryEntry, String>;
+    fn find_all(&self) -> Result<Vec<RegistryEntry>, String>;
+}
@@ -0,0 +1,9 @@
+use candid::Principal;
+
+use crate::domain::models::role::RoleAssignment;
+
+pub trait IRoleRepository {
+    fn insert(&self, assignment: RoleAssignment) -> Result<RoleAssignment, String>;
+    fn get(&self, principal: &Principal) -> Result<RoleAssignment, String>;
+    fn find_all(&self) -> Result<Vec<RoleAssignment>, String>;
+}
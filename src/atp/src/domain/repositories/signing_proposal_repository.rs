@@ -0,0 +1,12 @@
+use crate::domain::models::signing_proposal::SigningProposal;
+
+pub trait ISigningProposalRepository {
+    fn insert(&self, proposal: SigningProposal) -> Result<SigningProposal, String>;
+    fn get(&self, account_id: &str, proposal_id: &str) -> Result<SigningProposal, String>;
+    fn find_by_account(
+        &self,
+        account_id: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<SigningProposal>, String>;
+}
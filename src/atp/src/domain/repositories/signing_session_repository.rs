@@ -0,0 +1,12 @@
+use crate::domain::models::signing_session::SigningSession;
+
+pub trait ISigningSessionRepository {
+    fn insert(&self, session: SigningSession) -> Result<SigningSession, String>;
+    fn get(&self, account_id: &str, session_id: &str) -> Result<SigningSession, String>;
+    fn find_by_account(
+        &self,
+        account_id: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<SigningSession>, String>;
+}
@@ -1 +1,11 @@
 pub mod account_endpoints;
+pub mod audit_endpoints;
+pub mod canister_config_endpoints;
+pub mod fee_endpoints;
+pub mod guards;
+pub mod inheritance_endpoints;
+pub mod job_endpoints;
+pub mod registry_endpoints;
+pub mod role_endpoints;
+pub mod signing_proposal_endpoints;
+pub mod signing_session_endpoints;
@@ -2,28 +2,45 @@ use ic_cdk::{query, update};
 
 use crate::application::dtos::account_messages::*;
 use crate::application::services::account_service::AccountService;
+use crate::application::services::rate_limit_service::RateLimitService;
+use crate::endpoints::fee_endpoints::get_fee_service;
 use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+use crate::infrastructure::repositories::rate_limit_repository_impl::RateLimitRepositoryImpl;
+use crate::infrastructure::repositories::registry_repository_impl::RegistryRepositoryImpl;
 use crate::infrastructure::repositories::signer_repository_impl::SignerRepositoryImpl;
-use crate::utils::config::KEY_ID;
+use crate::infrastructure::repositories::signing_session_repository_impl::SigningSessionRepositoryImpl;
 
-// Initialize repositories for service
-fn get_repositories() -> (AccountRepositoryImpl, SignerRepositoryImpl) {
-    // Create repository instances
-    let account_repository = AccountRepositoryImpl::global();
-    let signer_repository = SignerRepositoryImpl::global();
-    (account_repository, signer_repository)
+// Initialize service with the global repositories
+fn get_service() -> AccountService<AccountRepositoryImpl, SignerRepositoryImpl> {
+    AccountService::new(
+        AccountRepositoryImpl::global(),
+        SignerRepositoryImpl::global(),
+        SigningSessionRepositoryImpl::global(),
+        RegistryRepositoryImpl::global(),
+        get_fee_service(),
+        get_rate_limit_service(),
+    )
+}
+
+// Initialize the rate limiter, shared with the ingress inspection and the cleanup job
+pub(crate) fn get_rate_limit_service() -> RateLimitService {
+    RateLimitService::new(
+        CanisterConfigRepositoryImpl::global(),
+        RateLimitRepositoryImpl::new(),
+    )
 }
 
 /// Create a new account with the given parameters
 ///
 /// This function will generate a new key pair and create an account.
 /// The caller will be set as the owner of the account.
+/// Anonymous callers are rejected and account creation is rate limited per caller.
 #[update]
 pub async fn create_account(
     request: CreateAccountRequest,
 ) -> Result<CreateAccountResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Use the caller as the owner
     let owner = ic_cdk::api::caller();
@@ -38,8 +55,7 @@ pub async fn create_account(
 /// The account must be in the Locked state.
 #[update]
 pub fn unlock_account(request: UnlockAccountRequest) -> Result<UnlockAccountResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Unlock the account
     service.unlock_account(request)
@@ -53,8 +69,7 @@ pub fn unlock_account(request: UnlockAccountRequest) -> Result<UnlockAccountResp
 pub fn transfer_account(
     request: TransferAccountRequest,
 ) -> Result<TransferAccountResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Transfer the account
     service.transfer_account(request)
@@ -68,34 +83,145 @@ pub fn transfer_account(
 pub fn activate_account(
     request: ActivateAccountRequest,
 ) -> Result<ActivateAccountResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Activate the account
     service.activate_account(request)
 }
 
+/// Set the guardians of an account
+///
+/// Only the owner can set guardians.
+/// Once set, signing requires M-of-N guardian approval through signing proposals.
+/// Changing existing guardians waits for the approval of their threshold.
+/// An empty guardian list restores the single owner default.
+#[update]
+pub fn set_guardians(request: SetGuardiansRequest) -> Result<SetGuardiansResponse, String> {
+    let service = get_service();
+
+    // Set the guardians
+    service.set_guardians(request)
+}
+
+/// Approve the pending guardian change of an account
+///
+/// Only current guardians can approve, each guardian once.
+/// The change is applied once the guardian threshold is reached.
+#[update]
+pub fn approve_guardian_change(
+    request: ApproveGuardianChangeRequest,
+) -> Result<ApproveGuardianChangeResponse, String> {
+    let service = get_service();
+
+    // Approve the guardian change
+    service.approve_guardian_change(request)
+}
+
+/// Set the label, description, attributes and tags of an account
+///
+/// Only the owner can set metadata. Fields are bounded in size and tags are
+/// lowercased. The transfer policy decides which fields a new owner keeps.
+#[update]
+pub fn set_account_metadata(
+    request: SetAccountMetadataRequest,
+) -> Result<SetAccountMetadataResponse, String> {
+    let service = get_service();
+
+    // Replace the metadata
+    service.set_account_metadata(request)
+}
+
+/// List the accounts of the caller
+///
+/// Pass a tag to only list the accounts carrying it. Pages start at 1
+/// and pages past the end are empty.
+#[query]
+pub fn list_accounts(request: ListAccountsRequest) -> Result<ListAccountsResponse, String> {
+    get_service().list_accounts(request)
+}
+
+/// Start recovering an account to a new owner
+///
+/// Only guardians can start a recovery; the initiating guardian confirms it.
+/// The owner can cancel it until the recovery time-lock elapses.
+#[update]
+pub fn start_recovery(request: StartRecoveryRequest) -> Result<StartRecoveryResponse, String> {
+    let service = get_service();
+
+    // Start the recovery
+    service.start_recovery(request)
+}
+
+/// Confirm the pending recovery of an account
+///
+/// Only guardians can confirm, each guardian once.
+#[update]
+pub fn confirm_recovery(
+    request: ConfirmRecoveryRequest,
+) -> Result<ConfirmRecoveryResponse, String> {
+    let service = get_service();
+
+    // Confirm the recovery
+    service.confirm_recovery(request)
+}
+
+/// Cancel the pending recovery of an account
+///
+/// Only the owner can cancel a recovery.
+#[update]
+pub fn cancel_recovery(request: CancelRecoveryRequest) -> Result<CancelRecoveryResponse, String> {
+    let service = get_service();
+
+    // Cancel the recovery
+    service.cancel_recovery(request)
+}
+
+/// Complete the pending recovery of an account
+///
+/// Only a guardian or the new owner can complete a recovery.
+/// Requires the guardian threshold of confirmations and an elapsed time-lock.
+#[update]
+pub fn complete_recovery(
+    request: CompleteRecoveryRequest,
+) -> Result<CompleteRecoveryResponse, String> {
+    let service = get_service();
+
+    // Complete the recovery
+    service.complete_recovery(request)
+}
+
 /// Get account details
 ///
 /// Retrieves the details of an account by its ID.
 /// Anyone can query account details.
 #[query]
 pub fn get_account(request: GetAccountRequest) -> Result<GetAccountResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Get the account
     service.get_account(request)
 }
 
+/// Rotate the key of an account
+///
+/// Only the owner can rotate the key, and the account must not be locked.
+/// Returns the sweep plan from the addresses of the previous key to the new ones.
+#[update]
+pub async fn rotate_key(request: RotateKeyRequest) -> Result<RotateKeyResponse, String> {
+    let service = get_service();
+
+    // Rotate the key
+    service.rotate_key(request).await
+}
+
 /// Sign a message with the account's private key
 ///
-/// Only the owner can sign messages.
+/// The owner can sign messages, and so can the delegate of an active signing session
+/// within the chains and methods the session allows.
 /// The account must be in the Active state.
 #[update]
 pub async fn sign(request: SignRequest) -> Result<SignResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Sign the message
     service.sign(request).await
@@ -103,15 +229,15 @@ pub async fn sign(request: SignRequest) -> Result<SignResponse, String> {
 
 /// Sign an EIP-1559 transaction with the account's private key
 ///
-/// Only the owner can sign transactions.
+/// The owner can sign transactions, and so can the delegate of an active signing session
+/// within the chains, methods and value the session allows.
 /// The account must be in the Active state.
 /// The account must use ECDSA signature algorithm and secp256k1 curve.
 #[update]
 pub async fn sign_eip1559_transaction(
     request: SignEip1559TransactionRequest,
 ) -> Result<SignEip1559TransactionResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Sign the transaction
     service.sign_eip1559_transaction(request).await
@@ -125,21 +251,36 @@ pub async fn sign_eip1559_transaction(
 pub fn generate_address(
     request: GenerateAddressRequest,
 ) -> Result<GenerateAddressResponse, String> {
-    let (account_repository, signer_repository) = get_repositories();
-    let service = AccountService::new(account_repository, signer_repository);
+    let service = get_service();
 
     // Generate address for the specified chain
     service.generate_address(request)
 }
 
+/// Get the algorithm and curve pairs accounts can be created with
+///
+/// Each pair lists the signing endpoints its accounts can use.
+#[query]
+pub fn get_capabilities() -> Result<GetCapabilitiesResponse, String> {
+    get_service().get_capabilities()
+}
+
+/// Get the capabilities of an account
+///
+/// Lists the signing endpoints of the account and the registered chains
+/// supporting its curve, with the format of the addresses generated for them.
+#[query]
+pub fn get_account_capabilities(
+    request: GetAccountCapabilitiesRequest,
+) -> Result<GetAccountCapabilitiesResponse, String> {
+    get_service().get_account_capabilities(request)
+}
+
 /// Get the current key ID
 ///
-/// Returns the key ID configured for this environment.
+/// Returns the key ID set by the init or upgrade arguments.
 /// Anyone can query this information.
 #[query]
 pub fn get_key_id() -> String {
-    KEY_ID.to_string()
+    SignerRepositoryImpl::global().key_id().to_string()
 }
-
-// Export the Candid interface
-ic_cdk::export_candid!();
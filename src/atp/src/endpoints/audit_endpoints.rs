@@ -0,0 +1,16 @@
+use ic_cdk::query;
+
+use crate::application::dtos::audit_messages::*;
+use crate::application::services::audit_service::AuditService;
+use crate::infrastructure::repositories::audit_event_repository_impl::AuditEventRepositoryImpl;
+
+/// List the audit trail of an account
+///
+/// Events are returned oldest first.
+/// Anyone can list audit events.
+#[query]
+pub fn list_audit_events(
+    request: ListAuditEventsRequest,
+) -> Result<ListAuditEventsResponse, String> {
+    AuditService::new(AuditEventRepositoryImpl::global()).list_events(request)
+}
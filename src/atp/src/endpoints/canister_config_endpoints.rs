@@ -0,0 +1,15 @@
+use ic_cdk::query;
+
+use crate::application::dtos::canister_config_messages::*;
+use crate::application::services::canister_config_service::CanisterConfigService;
+use crate::endpoints::guards::require_auditor;
+use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+
+/// Get the canister configuration
+///
+/// Only auditors and admins can read the configuration.
+/// Returns the threshold key ID and fee settings set at install or upgrade time.
+#[query(guard = "require_auditor")]
+pub fn get_canister_config() -> Result<GetCanisterConfigResponse, String> {
+    CanisterConfigService::new(CanisterConfigRepositoryImpl::global()).get_config()
+}
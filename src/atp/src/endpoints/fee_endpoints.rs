@@ -0,0 +1,57 @@
+use ic_cdk::{query, update};
+
+use crate::application::dtos::fee_messages::*;
+use crate::application::services::fee_service::FeeService;
+use crate::endpoints::guards::{require_admin, require_auditor};
+use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+use crate::infrastructure::repositories::fee_account_repository_impl::FeeAccountRepositoryImpl;
+use crate::infrastructure::repositories::payment_repository_impl::PaymentRepositoryImpl;
+
+// Initialize service with the global repositories, shared by the services charging fees
+pub(crate) fn get_fee_service() -> FeeService {
+    FeeService::new(
+        CanisterConfigRepositoryImpl::global(),
+        FeeAccountRepositoryImpl::global(),
+        PaymentRepositoryImpl::new(),
+    )
+}
+
+/// Get the fees charged for every operation and curve
+///
+/// Anyone can query the fees.
+/// Fees are paid by attaching cycles to the call or from a prepaid ledger balance.
+#[query]
+pub fn get_fees() -> Result<GetFeesResponse, String> {
+    get_fee_service().get_fees()
+}
+
+/// Get the prepaid fee balance of the caller
+///
+/// Also returns the ledger account the caller deposits prepaid fees to.
+#[query]
+pub fn get_fee_balance() -> Result<GetFeeBalanceResponse, String> {
+    get_fee_service().get_fee_balance()
+}
+
+/// Credit the tokens sent to the caller's deposit account to its prepaid fee balance
+#[update]
+pub async fn deposit_fees() -> Result<DepositFeesResponse, String> {
+    get_fee_service().deposit().await
+}
+
+/// Get the fees collected by the canister
+///
+/// Only auditors and admins can read the collected fees.
+#[query(guard = "require_auditor")]
+pub fn get_collected_fees() -> Result<GetCollectedFeesResponse, String> {
+    get_fee_service().get_collected_fees()
+}
+
+/// Withdraw collected fees
+///
+/// Only admins can withdraw fees.
+/// Cycles are deposited to the `to` canister, tokens are sent to the `to` ledger account.
+#[update(guard = "require_admin")]
+pub async fn withdraw_fees(request: WithdrawFeesRequest) -> Result<WithdrawFeesResponse, String> {
+    get_fee_service().withdraw(request).await
+}
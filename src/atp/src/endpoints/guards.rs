@@ -0,0 +1,26 @@
+//! Call guards restricting endpoints to principals holding a role
+//!
+//! Use them through the `guard` attribute, e.g. `#[update(guard = "require_admin")]`.
+
+use crate::application::services::role_service::RoleService;
+use crate::domain::models::role::Role;
+use crate::infrastructure::repositories::role_repository_impl::RoleRepositoryImpl;
+
+fn require_role(role: Role) -> Result<(), String> {
+    RoleService::new(RoleRepositoryImpl::global()).require_role(&role)
+}
+
+/// Allow only admins
+pub fn require_admin() -> Result<(), String> {
+    require_role(Role::Admin)
+}
+
+/// Allow only operators and admins
+pub fn require_operator() -> Result<(), String> {
+    require_role(Role::Operator)
+}
+
+/// Allow only auditors and admins
+pub fn require_auditor() -> Result<(), String> {
+    require_role(Role::Auditor)
+}
@@ -0,0 +1,36 @@
+use ic_cdk::update;
+
+use crate::application::dtos::account_messages::*;
+use crate::application::services::audit_service::AuditService;
+use crate::application::services::inheritance_service::InheritanceService;
+use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::infrastructure::repositories::audit_event_repository_impl::AuditEventRepositoryImpl;
+
+// Initialize service with the global repositories
+pub(crate) fn get_service() -> InheritanceService {
+    InheritanceService::new(
+        AccountRepositoryImpl::global(),
+        AuditService::new(AuditEventRepositoryImpl::global()),
+    )
+}
+
+/// Set the inheritance plan of an account
+///
+/// Only the owner can set the plan.
+/// The beneficiary can claim the account once the owner has not used it for the inactivity period.
+/// No beneficiary removes the plan.
+#[update]
+pub fn set_inheritance(request: SetInheritanceRequest) -> Result<SetInheritanceResponse, String> {
+    get_service().set_inheritance(request)
+}
+
+/// Claim an account from an inactive owner
+///
+/// Only the beneficiary can claim, once the inactivity period has elapsed.
+/// The account moves to the Unlocked state under the beneficiary, as after a transfer.
+#[update]
+pub fn claim_inheritance(
+    request: ClaimInheritanceRequest,
+) -> Result<ClaimInheritanceResponse, String> {
+    get_service().claim_inheritance(request)
+}
@@ -0,0 +1,15 @@
+use ic_cdk::query;
+
+use crate::application::dtos::job_messages::*;
+use crate::application::services::job_service::JobService;
+use crate::endpoints::guards::require_auditor;
+use crate::infrastructure::repositories::job_repository_impl::JobRepositoryImpl;
+
+/// List the background jobs of the canister
+///
+/// Only auditors and admins can list jobs.
+/// Returns the schedule and run history of every registered job.
+#[query(guard = "require_auditor")]
+pub fn list_jobs() -> Result<ListJobsResponse, String> {
+    JobService::new(JobRepositoryImpl::global()).list_jobs()
+}
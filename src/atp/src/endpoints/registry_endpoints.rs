@@ -0,0 +1,86 @@
+use ic_cdk::{query, update};
+
+use crate::application::dtos::registry_messages::*;
+use crate::application::services::registry_service::RegistryService;
+use crate::endpoints::guards::require_admin;
+use crate::infrastructure::repositories::registry_repository_impl::RegistryRepositoryImpl;
+
+// Initialize service with the global repository
+fn get_service() -> RegistryService {
+    RegistryService::new(RegistryRepositoryImpl::global())
+}
+
+/// Get the chain registry
+///
+/// Returns the chains, assets and token pairs with the registry revision.
+#[query]
+pub fn get_registry() -> Result<GetRegistryResponse, String> {
+    get_service().get_registry()
+}
+
+/// List the chains supported by the canister
+#[query]
+pub fn list_supported_chains() -> Result<ListSupportedChainsResponse, String> {
+    get_service().list_supported_chains()
+}
+
+/// Add a chain to the registry
+///
+/// Only admins can change the registry.
+/// Every change is validated before it is stored.
+#[update(guard = "require_admin")]
+pub fn add_chain(request: AddChainRequest) -> Result<UpdateRegistryResponse, String> {
+    get_service().add_chain(request)
+}
+
+/// Replace the configuration of a chain
+#[update(guard = "require_admin")]
+pub fn update_chain(request: UpdateChainRequest) -> Result<UpdateRegistryResponse, String> {
+    get_service().update_chain(request)
+}
+
+/// Remove a chain that no token pair uses
+#[update(guard = "require_admin")]
+pub fn remove_chain(request: RemoveChainRequest) -> Result<UpdateRegistryResponse, String> {
+    get_service().remove_chain(request)
+}
+
+/// Add an asset to the registry
+#[update(guard = "require_admin")]
+pub fn add_asset(request: AddAssetRequest) -> Result<UpdateRegistryResponse, String> {
+    get_service().add_asset(request)
+}
+
+/// Replace the configuration of an asset
+#[update(guard = "require_admin")]
+pub fn update_asset(request: UpdateAssetRequest) -> Result<UpdateRegistryResponse, String> {
+    get_service().update_asset(request)
+}
+
+/// Remove an asset that no token pair uses
+#[update(guard = "require_admin")]
+pub fn remove_asset(request: RemoveAssetRequest) -> Result<UpdateRegistryResponse, String> {
+    get_service().remove_asset(request)
+}
+
+/// Add a token pair between two registered assets
+#[update(guard = "require_admin")]
+pub fn add_token_pair(request: AddTokenPairRequest) -> Result<UpdateRegistryResponse, String> {
+    get_service().add_token_pair(request)
+}
+
+/// Replace the configuration of a token pair
+#[update(guard = "require_admin")]
+pub fn update_token_pair(
+    request: UpdateTokenPairRequest,
+) -> Result<UpdateRegistryResponse, String> {
+    get_service().update_token_pair(request)
+}
+
+/// Remove a token pair
+#[update(guard = "require_admin")]
+pub fn remove_token_pair(
+    request: RemoveTokenPairRequest,
+) -> Result<UpdateRegistryResponse, String> {
+    get_service().remove_token_pair(request)
+}
@@ -0,0 +1,44 @@
+use ic_cdk::{query, update};
+
+use crate::application::dtos::role_messages::*;
+use crate::application::services::role_service::RoleService;
+use crate::endpoints::guards::{require_admin, require_auditor};
+use crate::infrastructure::repositories::role_repository_impl::RoleRepositoryImpl;
+
+// Initialize service with the global repository
+fn get_service() -> RoleService {
+    RoleService::new(RoleRepositoryImpl::global())
+}
+
+/// Grant a role to a principal
+///
+/// Only admins can grant roles.
+#[update(guard = "require_admin")]
+pub fn grant_role(request: GrantRoleRequest) -> Result<GrantRoleResponse, String> {
+    get_service().grant_role(request)
+}
+
+/// Revoke a role from a principal
+///
+/// Only admins can revoke roles.
+/// Controllers keep every role regardless of their stored roles.
+#[update(guard = "require_admin")]
+pub fn revoke_role(request: RevokeRoleRequest) -> Result<RevokeRoleResponse, String> {
+    get_service().revoke_role(request)
+}
+
+/// List the principals holding roles
+///
+/// Only auditors and admins can list role assignments.
+#[query(guard = "require_auditor")]
+pub fn list_role_assignments() -> Result<ListRoleAssignmentsResponse, String> {
+    get_service().list_assignments()
+}
+
+/// Get the roles of the caller
+///
+/// Anyone can query their own roles.
+#[query]
+pub fn get_my_roles() -> Result<GetMyRolesResponse, String> {
+    get_service().get_my_roles()
+}
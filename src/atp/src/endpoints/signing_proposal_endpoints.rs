@@ -0,0 +1,92 @@
+use ic_cdk::{query, update};
+
+use crate::application::dtos::signing_proposal_messages::*;
+use crate::application::services::signing_proposal_service::SigningProposalService;
+use crate::endpoints::fee_endpoints::get_fee_service;
+use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::infrastructure::repositories::signer_repository_impl::SignerRepositoryImpl;
+use crate::infrastructure::repositories::signing_proposal_repository_impl::SigningProposalRepositoryImpl;
+
+// Initialize service with the global repositories
+fn get_service() -> SigningProposalService {
+    SigningProposalService::new(
+        AccountRepositoryImpl::global(),
+        SigningProposalRepositoryImpl::global(),
+        SignerRepositoryImpl::global(),
+        get_fee_service(),
+    )
+}
+
+/// Create a signing proposal for a guarded account
+///
+/// Only the owner can create proposals.
+/// The account must be in the Active state and have guardians configured.
+#[update]
+pub fn create_signing_proposal(
+    request: CreateSigningProposalRequest,
+) -> Result<CreateSigningProposalResponse, String> {
+    get_service().create_proposal(request)
+}
+
+/// Approve a signing proposal
+///
+/// Only guardians of the account can approve.
+/// The signature is produced as soon as the guardian threshold is reached.
+#[update]
+pub async fn approve_signing_proposal(
+    request: ApproveSigningProposalRequest,
+) -> Result<ApproveSigningProposalResponse, String> {
+    get_service().approve_proposal(request).await
+}
+
+/// Execute an approved signing proposal
+///
+/// Only the owner or a guardian can execute.
+/// Used to retry signing when it failed after the final approval.
+#[update]
+pub async fn execute_signing_proposal(
+    request: ExecuteSigningProposalRequest,
+) -> Result<ExecuteSigningProposalResponse, String> {
+    get_service().execute_proposal(request).await
+}
+
+/// Cancel a signing proposal
+///
+/// Only the proposer can cancel a proposal that has not been executed yet.
+#[update]
+pub fn cancel_signing_proposal(
+    request: CancelSigningProposalRequest,
+) -> Result<CancelSigningProposalResponse, String> {
+    get_service().cancel_proposal(request)
+}
+
+/// Expire the signing proposals of an account
+///
+/// Marks every open proposal past its expiry time as expired.
+/// Anyone can trigger the expiry.
+#[update]
+pub fn expire_signing_proposals(
+    request: ExpireSigningProposalsRequest,
+) -> Result<ExpireSigningProposalsResponse, String> {
+    get_service().expire_proposals(request)
+}
+
+/// Get signing proposal details
+///
+/// Anyone can query proposal details.
+#[query]
+pub fn get_signing_proposal(
+    request: GetSigningProposalRequest,
+) -> Result<GetSigningProposalResponse, String> {
+    get_service().get_proposal(request)
+}
+
+/// List the signing proposals of an account
+///
+/// Anyone can list proposals.
+#[query]
+pub fn list_signing_proposals(
+    request: ListSigningProposalsRequest,
+) -> Result<ListSigningProposalsResponse, String> {
+    get_service().list_proposals(request)
+}
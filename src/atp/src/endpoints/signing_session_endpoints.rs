@@ -0,0 +1,56 @@
+use ic_cdk::{query, update};
+
+use crate::application::dtos::signing_session_messages::*;
+use crate::application::services::signing_session_service::SigningSessionService;
+use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::infrastructure::repositories::signing_session_repository_impl::SigningSessionRepositoryImpl;
+
+// Initialize service with the global repositories
+fn get_service() -> SigningSessionService {
+    SigningSessionService::new(
+        AccountRepositoryImpl::global(),
+        SigningSessionRepositoryImpl::global(),
+    )
+}
+
+/// Grant a signing session to a delegate
+///
+/// Only the owner can grant sessions.
+/// The account must be in the Active state and must not have guardians.
+/// The delegate can then sign within the session limits until it expires.
+#[update]
+pub fn grant_signing_session(
+    request: GrantSigningSessionRequest,
+) -> Result<GrantSigningSessionResponse, String> {
+    get_service().grant_session(request)
+}
+
+/// Revoke a signing session
+///
+/// Only the owner can revoke a session.
+#[update]
+pub fn revoke_signing_session(
+    request: RevokeSigningSessionRequest,
+) -> Result<RevokeSigningSessionResponse, String> {
+    get_service().revoke_session(request)
+}
+
+/// Get signing session details
+///
+/// Anyone can query session details.
+#[query]
+pub fn get_signing_session(
+    request: GetSigningSessionRequest,
+) -> Result<GetSigningSessionResponse, String> {
+    get_service().get_session(request)
+}
+
+/// List the signing sessions of an account
+///
+/// Anyone can list sessions.
+#[query]
+pub fn list_signing_sessions(
+    request: ListSigningSessionsRequest,
+) -> Result<ListSigningSessionsResponse, String> {
+    get_service().list_sessions(request)
+}
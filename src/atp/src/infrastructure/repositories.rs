@@ -1,2 +1,15 @@
 pub mod account_repository_impl;
+pub mod audit_event_repository_impl;
+pub mod canister_config_repository_impl;
+pub mod fee_account_repository_impl;
+pub mod in_memory_account_repository_impl;
+pub mod job_repository_impl;
+#[cfg(any(test, feature = "local-signer"))]
+pub mod local_signer_repository_impl;
+pub mod payment_repository_impl;
+pub mod rate_limit_repository_impl;
+pub mod registry_repository_impl;
+pub mod role_repository_impl;
 pub mod signer_repository_impl;
+pub mod signing_proposal_repository_impl;
+pub mod signing_session_repository_impl;
@@ -1,11 +1,14 @@
 use ic_nosql::{
+    database::Document,
     traits::{Model, Repository},
-    DatabaseManager,
+    Cursor, DatabaseError, DatabaseManager,
 };
 use std::cell::RefCell;
 
-use crate::domain::models::account::Account;
+use crate::domain::models::account::{Account, AccountState};
+use crate::domain::models::account_metadata::tag_index_key;
 use crate::domain::repositories::account_repository::IAccountRepository;
+use crate::utils::ic::api::get_ic_api;
 
 thread_local! {
     static DB_MANAGER: RefCell<Option<DatabaseManager>> = RefCell::new(None);
@@ -28,6 +31,11 @@ impl AccountRepositoryImpl {
         // Register the Account model with secondary index for owner queries
         db_manager.register_model("accounts", Some(0), Some(1))?;
 
+        // Register the named indexes of the Account model
+        db_manager.register_index("accounts", "tag", Some(10))?;
+        db_manager.register_index("accounts", "approved_address", Some(11))?;
+        db_manager.register_index("accounts", "state", Some(12))?;
+
         // Store the database manager
         DB_MANAGER.with(|manager| {
             *manager.borrow_mut() = Some(db_manager);
@@ -57,30 +65,76 @@ impl AccountRepositoryImpl {
             let manager = manager.borrow();
             let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
 
-            // Create database with secondary key function for owner queries
-            db_manager.get_database(
+            // Create database with secondary key function for owner queries and named indexes
+            let database = db_manager.get_database_with_indexes(
                 "accounts",
                 Some(Box::new(|account: &Account| account.get_secondary_key())),
-            )
+                Account::indexes(),
+            )?;
+            Ok(database.with_clock(|| get_ic_api().time()))
         })
     }
+
+    // Query a named index, keys without accounts and pages past the end are empty
+    fn find_by_index(
+        &self,
+        name: &str,
+        key: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        if page_size == 0 {
+            return Err("Page size must be greater than 0.".to_string());
+        }
+        if page == 0 {
+            return Err("Page number must be greater than 0.".to_string());
+        }
+        let db = self.get_database()?;
+
+        match db.query_index(name, key, page_size, page) {
+            Ok(query_result) => Ok(query_result
+                .results
+                .into_iter()
+                .map(account_from_document)
+                .collect()),
+            Err(_) => Ok(vec![]),
+        }
+    }
+}
+
+// Error returned when an account was stored again after it was read
+pub fn concurrent_update_error(id: &str) -> String {
+    format!("Account {} was updated concurrently, retry the request", id)
+}
+
+// Account of a document, carrying the version it is stored at
+fn account_from_document(document: Document<Account>) -> Account {
+    let mut account = document.data;
+    account.set_stored_version(document.version);
+    account
 }
 
 impl IAccountRepository for AccountRepositoryImpl {
     fn insert(&self, account: Account) -> Result<Account, String> {
         let db = self.get_database()?;
-        let document = db.insert(
-            account.get_primary_key(),
-            None, // No sort key for primary operations
-            account.clone(),
-        )?;
-        Ok(document.data)
+        let document = db
+            .compare_and_put(
+                account.get_primary_key(),
+                None, // No sort key for primary operations
+                account.stored_version(),
+                account.clone(),
+            )
+            .map_err(|error| match error {
+                DatabaseError::ConditionFailed { .. } => concurrent_update_error(account.id()),
+                error => error.to_string(),
+            })?;
+        Ok(account_from_document(document))
     }
 
     fn get(&self, id: &str) -> Result<Account, String> {
         let db = self.get_database()?;
         let document = db.get(id, None)?;
-        Ok(document.data)
+        Ok(account_from_document(document))
     }
 
     fn exists(&self, id: &str) -> bool {
@@ -109,7 +163,51 @@ impl IAccountRepository for AccountRepositoryImpl {
         let accounts = query_result
             .results
             .into_iter()
-            .map(|doc| doc.data)
+            .map(account_from_document)
+            .collect();
+
+        Ok(accounts)
+    }
+
+    fn find_by_tag(
+        &self,
+        owner: &str,
+        tag: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        self.find_by_index("tag", &tag_index_key(owner, tag), page_size, page)
+    }
+
+    fn find_by_approved_address(
+        &self,
+        address: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        self.find_by_index("approved_address", address, page_size, page)
+    }
+
+    fn find_by_state(
+        &self,
+        state: &AccountState,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        self.find_by_index("state", state.as_str(), page_size, page)
+    }
+
+    fn scan_after(&self, after: Option<&str>, limit: usize) -> Result<Vec<Account>, String> {
+        let db = self.get_database()?;
+
+        // Walk the primary map from the key of the given account, skipping corrupt accounts
+        let cursor = after.map(|id| Cursor::after(id, None));
+        let query_result = db.scan_from(limit, cursor)?;
+
+        let accounts = query_result
+            .results
+            .into_iter()
+            .map(account_from_document)
             .collect();
 
         Ok(accounts)
@@ -145,16 +243,15 @@ impl Repository<Account> for AccountRepositoryImpl {
         let accounts = query_result
             .results
             .into_iter()
-            .map(|doc| doc.data)
+            .map(account_from_document)
             .collect();
 
         Ok(accounts)
     }
 
-    fn delete(&self, _id: &<Account as Model>::PrimaryKey) -> Result<bool, Self::Error> {
-        // TODO: ic-nosql Database doesn't have a delete method, so we'll return false for now
-        // This would need to be implemented in the ic-nosql library
-        Ok(false)
+    fn delete(&self, id: &<Account as Model>::PrimaryKey) -> Result<bool, Self::Error> {
+        let db = self.get_database()?;
+        Ok(db.delete(id, None).is_ok())
     }
 
     fn exists(&self, id: &<Account as Model>::PrimaryKey) -> Result<bool, Self::Error> {
@@ -165,9 +262,11 @@ impl Repository<Account> for AccountRepositoryImpl {
 #[cfg(test)]
 mod account_repository_tests {
     use candid::Principal;
+    use std::collections::BTreeMap;
     use std::rc::Rc;
 
     use crate::domain::models::account::{Account, AccountState};
+    use crate::domain::models::account_metadata::*;
     use crate::domain::models::signer::SignatureAlgorithm;
     use crate::domain::repositories::account_repository::IAccountRepository;
     use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
@@ -365,10 +464,10 @@ mod account_repository_tests {
     fn test_update_account() {
         let repo = setup();
         let owner = Principal::from_text("2vxsx-fae").unwrap();
-        let mut account = create_test_account("test-id-8", owner);
+        let account = create_test_account("test-id-8", owner);
 
-        // Insert the account first
-        let _ = repo
+        // Insert the account first, updates start from the stored account
+        let mut account = repo
             .insert(account.clone())
             .expect("Failed to insert account");
 
@@ -466,4 +565,173 @@ mod account_repository_tests {
         assert_eq!(account1_retrieved.id(), account1.id());
         assert_eq!(account2_retrieved.id(), account2.id());
     }
+
+    #[test]
+    fn test_find_by_tag_follows_tag_changes() {
+        let repo = setup();
+        let owner = Principal::from_text("2vxsx-fae").unwrap();
+        let tagged = |id: &str, tags: Vec<&str>| {
+            let mut account = repo
+                .get(id)
+                .unwrap_or_else(|_| create_test_account(id, owner));
+            let metadata = AccountMetadata::new(
+                None,
+                None,
+                BTreeMap::new(),
+                tags.into_iter().map(String::from).collect(),
+                MetadataTransferPolicy::default(),
+            )
+            .unwrap();
+            account.set_metadata(metadata).unwrap();
+            repo.insert(account).expect("Failed to insert account");
+        };
+        let ids = |tag: &str, page: usize| -> Vec<String> {
+            repo.find_by_tag(&owner.to_string(), tag, 1, page)
+                .unwrap()
+                .iter()
+                .map(|account| account.id().clone())
+                .collect()
+        };
+
+        tagged("tag-test-1", vec!["cold"]);
+        tagged("tag-test-2", vec!["cold", "defi"]);
+        assert_eq!(ids("cold", 1), vec!["tag-test-1"]);
+        assert_eq!(ids("cold", 2), vec!["tag-test-2"]);
+        assert!(ids("cold", 3).is_empty());
+
+        // Removing a tag deletes its entry without leaving a gap in the pages
+        tagged("tag-test-1", vec!["defi"]);
+        assert_eq!(ids("cold", 1), vec!["tag-test-2"]);
+        assert!(ids("cold", 2).is_empty());
+        // Updated accounts move to the end of their index entries
+        assert_eq!(ids("defi", 2), vec!["tag-test-1"]);
+        assert!(ids("unused", 1).is_empty());
+    }
+
+    #[test]
+    fn test_find_by_state_and_approved_address() {
+        let repo = setup();
+        let owner = Principal::from_text("2vxsx-fae").unwrap();
+        let dex = Principal::from_text("rrkah-fqaaa-aaaaa-aaaaq-cai").unwrap();
+        let ids = |accounts: Vec<Account>| -> Vec<String> {
+            accounts
+                .iter()
+                .map(|account| account.id().clone())
+                .collect()
+        };
+
+        let mut account = create_test_account("index-test-1", owner);
+        account.approve_address(dex).unwrap();
+        let mut account = repo
+            .insert(account.clone())
+            .expect("Failed to insert account");
+        repo.insert(create_test_account("index-test-2", owner))
+            .expect("Failed to insert account");
+
+        let approved = repo
+            .find_by_approved_address(&dex.to_string(), 10, 1)
+            .unwrap();
+        assert_eq!(ids(approved), vec!["index-test-1"]);
+        let locked = repo.find_by_state(&AccountState::Locked, 10, 1).unwrap();
+        assert!(ids(locked).contains(&"index-test-2".to_string()));
+
+        // State changes move the account to the index entry of its new state
+        account.unlock().unwrap();
+        repo.insert(account).expect("Failed to update account");
+        let unlocked = repo.find_by_state(&AccountState::Unlocked, 10, 1).unwrap();
+        assert_eq!(ids(unlocked), vec!["index-test-1"]);
+        let locked = repo.find_by_state(&AccountState::Locked, 10, 1).unwrap();
+        assert!(!ids(locked).contains(&"index-test-1".to_string()));
+        assert!(repo
+            .find_by_state(&AccountState::Active, 10, 1)
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_insert_detects_concurrent_updates() {
+        let repo = setup();
+        let owner = Principal::from_text("2vxsx-fae").unwrap();
+        let account = create_test_account("version-test-1", owner);
+
+        let stored = repo
+            .insert(account.clone())
+            .expect("Failed to insert account");
+        assert_eq!(stored.stored_version(), Some(1));
+        // Creating the same account twice is a conflict
+        assert!(repo.insert(account).is_err());
+
+        // Two updates read the same version, the second one is rejected
+        let mut first = repo.get("version-test-1").unwrap();
+        let second = repo.get("version-test-1").unwrap();
+        first.unlock().unwrap();
+        let first = repo.insert(first).expect("Failed to update account");
+        assert_eq!(first.stored_version(), Some(2));
+        assert_eq!(
+            repo.insert(second).unwrap_err(),
+            "Account version-test-1 was updated concurrently, retry the request"
+        );
+
+        let retrieved = repo.get("version-test-1").unwrap();
+        assert_eq!(retrieved.account_state(), &AccountState::Unlocked);
+        assert_eq!(retrieved.stored_version(), Some(2));
+    }
+
+    #[test]
+    fn test_delete_account() {
+        let repo = setup();
+        let owner = Principal::from_text("2vxsx-fae").unwrap();
+        let mut account = create_test_account("delete-test-1", owner);
+        let metadata = AccountMetadata::new(
+            None,
+            None,
+            BTreeMap::new(),
+            vec!["doomed".to_string()],
+            MetadataTransferPolicy::default(),
+        )
+        .unwrap();
+        account.set_metadata(metadata).unwrap();
+        repo.insert(account).expect("Failed to insert account");
+
+        let id = "delete-test-1".to_string();
+        assert_eq!(ic_nosql::traits::Repository::delete(&repo, &id), Ok(true));
+        assert!(!repo.exists(&id));
+        assert!(repo
+            .find_by_tag(&owner.to_string(), "doomed", 10, 1)
+            .unwrap()
+            .is_empty());
+        assert!(repo
+            .find_by_owner(&owner.to_string(), 100, 1)
+            .map(|accounts| accounts.iter().all(|account| account.id() != &id))
+            .unwrap_or(true));
+        assert_eq!(ic_nosql::traits::Repository::delete(&repo, &id), Ok(false));
+    }
+
+    #[test]
+    fn test_largest_metadata_fits_stored_account() {
+        let repo = setup();
+        let owner = Principal::from_text("2vxsx-fae").unwrap();
+        let mut account = create_test_account("metadata-size-test-1", owner);
+        let attributes = (0..MAX_ATTRIBUTES)
+            .map(|i| {
+                let key = format!("{}{}", "k".repeat(MAX_ATTRIBUTE_KEY_LENGTH - 1), i);
+                (key, "v".repeat(MAX_ATTRIBUTE_VALUE_LENGTH))
+            })
+            .collect::<BTreeMap<_, _>>();
+        let tags = (0..MAX_TAGS)
+            .map(|i| format!("{}{}", "t".repeat(MAX_TAG_LENGTH - 1), i))
+            .collect();
+        let metadata = AccountMetadata::new(
+            Some("é".repeat(MAX_LABEL_LENGTH / 2)),
+            Some("x".repeat(MAX_DESCRIPTION_LENGTH)),
+            attributes,
+            tags,
+            MetadataTransferPolicy::default(),
+        )
+        .unwrap();
+        account.set_metadata(metadata).unwrap();
+
+        // Stays under the size bound of a stored document
+        repo.insert(account).expect("Failed to insert account");
+    }
 }
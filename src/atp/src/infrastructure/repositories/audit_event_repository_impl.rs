@@ -0,0 +1,120 @@
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::audit_event::AuditEvent;
+use crate::domain::repositories::audit_event_repository::IAuditEventRepository;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static AUDIT_EVENT_REPOSITORY: RefCell<Option<AuditEventRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct AuditEventRepositoryImpl {}
+
+impl AuditEventRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and audit event repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the AuditEvent model, partitioned by account ID
+        db_manager.register_model("audit_events", Some(4), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        AUDIT_EVENT_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(AuditEventRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global audit event repository instance
+    pub fn global() -> Self {
+        AUDIT_EVENT_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => panic!(
+                "AuditEventRepositoryImpl not initialized! Call AuditEventRepositoryImpl::init() first."
+            ),
+        })
+    }
+
+    /// Get a database instance for AuditEvent operations
+    fn get_database(&self) -> Result<ic_nosql::Database<AuditEvent>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("audit_events")
+        })
+    }
+}
+
+impl IAuditEventRepository for AuditEventRepositoryImpl {
+    fn insert(&self, event: AuditEvent) -> Result<AuditEvent, String> {
+        let db = self.get_database()?;
+        let document = db.insert(event.account_id().clone(), Some(event.id().clone()), event)?;
+        Ok(document.data)
+    }
+
+    fn find_by_account(
+        &self,
+        account_id: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<AuditEvent>, String> {
+        let db = self.get_database()?;
+        let query_result = db.query(Some(account_id), None, page_size, page)?;
+
+        let events = query_result
+            .results
+            .into_iter()
+            .map(|doc| doc.data)
+            .collect();
+
+        Ok(events)
+    }
+}
+
+#[cfg(test)]
+mod audit_event_repository_tests {
+    use candid::Principal;
+
+    use crate::domain::models::audit_event::{AuditEvent, AuditEventKind};
+    use crate::domain::repositories::audit_event_repository::IAuditEventRepository;
+    use crate::infrastructure::repositories::audit_event_repository_impl::AuditEventRepositoryImpl;
+
+    #[test]
+    fn test_events_are_listed_in_order() {
+        AuditEventRepositoryImpl::init().expect("Failed to initialize repository");
+        let repo = AuditEventRepositoryImpl::new();
+
+        // Insert out of order, including two events with the same timestamp
+        for (timestamp, sequence) in [(200, 3), (100, 1), (100, 2)] {
+            repo.insert(AuditEvent::new(
+                "account-1".to_string(),
+                AuditEventKind::InactivityNotice,
+                Principal::anonymous(),
+                timestamp,
+                sequence,
+                "notice".to_string(),
+            ))
+            .expect("Failed to insert event");
+        }
+
+        let events = repo
+            .find_by_account("account-1", 10, 1)
+            .expect("Failed to find events");
+        let timestamps: Vec<u64> = events.iter().map(|event| *event.timestamp()).collect();
+        assert_eq!(timestamps, vec![100, 100, 200]);
+        assert!(events[0].id() < events[1].id());
+    }
+}
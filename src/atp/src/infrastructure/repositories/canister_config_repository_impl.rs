@@ -0,0 +1,73 @@
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::canister_config::{CanisterConfig, CANISTER_CONFIG_ID};
+use crate::domain::repositories::canister_config_repository::ICanisterConfigRepository;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static CANISTER_CONFIG_REPOSITORY: RefCell<Option<CanisterConfigRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct CanisterConfigRepositoryImpl {}
+
+impl CanisterConfigRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and canister config repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the CanisterConfig model, a single document
+        db_manager.register_model("config", Some(8), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        CANISTER_CONFIG_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(CanisterConfigRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global canister config repository instance
+    pub fn global() -> Self {
+        CANISTER_CONFIG_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => {
+                panic!("CanisterConfigRepositoryImpl not initialized! Call CanisterConfigRepositoryImpl::init() first.")
+            }
+        })
+    }
+
+    /// Get a database instance for CanisterConfig operations
+    fn get_database(&self) -> Result<ic_nosql::Database<CanisterConfig>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("config")
+        })
+    }
+}
+
+impl ICanisterConfigRepository for CanisterConfigRepositoryImpl {
+    fn insert(&self, config: CanisterConfig) -> Result<CanisterConfig, String> {
+        let db = self.get_database()?;
+        let document = db.insert(config.id().clone(), None, config)?;
+        Ok(document.data)
+    }
+
+    fn get(&self) -> Result<CanisterConfig, String> {
+        let db = self.get_database()?;
+        let document = db.get(CANISTER_CONFIG_ID, None)?;
+        Ok(document.data)
+    }
+}
@@ -0,0 +1,73 @@
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::fee::FeeAccount;
+use crate::domain::repositories::fee_account_repository::IFeeAccountRepository;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static FEE_ACCOUNT_REPOSITORY: RefCell<Option<FeeAccountRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct FeeAccountRepositoryImpl {}
+
+impl FeeAccountRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and fee account repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the FeeAccount model, keyed by the account ID
+        db_manager.register_model("fee_accounts", Some(9), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        FEE_ACCOUNT_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(FeeAccountRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global fee account repository instance
+    pub fn global() -> Self {
+        FEE_ACCOUNT_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => {
+                panic!("FeeAccountRepositoryImpl not initialized! Call FeeAccountRepositoryImpl::init() first.")
+            }
+        })
+    }
+
+    /// Get a database instance for FeeAccount operations
+    fn get_database(&self) -> Result<ic_nosql::Database<FeeAccount>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("fee_accounts")
+        })
+    }
+}
+
+impl IFeeAccountRepository for FeeAccountRepositoryImpl {
+    fn insert(&self, account: FeeAccount) -> Result<FeeAccount, String> {
+        let db = self.get_database()?;
+        let document = db.insert(account.id().clone(), None, account)?;
+        Ok(document.data)
+    }
+
+    fn get(&self, id: &str) -> Result<FeeAccount, String> {
+        let db = self.get_database()?;
+        let document = db.get(id, None)?;
+        Ok(document.data)
+    }
+}
@@ -0,0 +1,155 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::ops::Bound;
+use std::rc::Rc;
+
+use crate::domain::models::account::{Account, AccountState};
+use crate::domain::repositories::account_repository::IAccountRepository;
+use crate::infrastructure::repositories::account_repository_impl::concurrent_update_error;
+
+/// Account repository kept in heap memory, for tests running without stable memory
+///
+/// Clones share the same accounts. Pagination follows the stable memory repository:
+/// pages start at 1 and owner pages past the end are errors.
+#[derive(Clone, Default)]
+pub struct InMemoryAccountRepositoryImpl {
+    accounts: Rc<RefCell<BTreeMap<String, Account>>>,
+}
+
+impl InMemoryAccountRepositoryImpl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+// Slice a page out of the matching accounts
+fn paginate(accounts: Vec<Account>, page_size: usize, page: usize) -> Result<Vec<Account>, String> {
+    if page_size == 0 {
+        return Err("Page size must be greater than 0.".to_string());
+    }
+    if page == 0 {
+        return Err("Page number must be greater than 0.".to_string());
+    }
+    Ok(accounts
+        .into_iter()
+        .skip((page - 1) * page_size)
+        .take(page_size)
+        .collect())
+}
+
+impl IAccountRepository for InMemoryAccountRepositoryImpl {
+    fn insert(&self, mut account: Account) -> Result<Account, String> {
+        let mut accounts = self.accounts.borrow_mut();
+        let stored_version = accounts
+            .get(account.id())
+            .and_then(|stored| stored.stored_version());
+        if account.stored_version() != stored_version {
+            return Err(concurrent_update_error(account.id()));
+        }
+        account.set_stored_version(stored_version.map_or(1, |version| version + 1));
+        accounts.insert(account.id().clone(), account.clone());
+        Ok(account)
+    }
+
+    fn get(&self, id: &str) -> Result<Account, String> {
+        self.accounts
+            .borrow()
+            .get(id)
+            .cloned()
+            .ok_or_else(|| "Document not found.".to_string())
+    }
+
+    fn exists(&self, id: &str) -> bool {
+        self.accounts.borrow().contains_key(id)
+    }
+
+    fn find_by_owner(
+        &self,
+        owner: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        let owned: Vec<Account> = self
+            .accounts
+            .borrow()
+            .values()
+            .filter(|account| account.owner().to_string() == owner)
+            .cloned()
+            .collect();
+        let accounts = paginate(owned, page_size, page)?;
+        if accounts.is_empty() {
+            return Err(format!("Page {} does not exist", page));
+        }
+        Ok(accounts)
+    }
+
+    fn find_by_tag(
+        &self,
+        owner: &str,
+        tag: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        let tagged = self
+            .accounts
+            .borrow()
+            .values()
+            .filter(|account| account.owner().to_string() == owner)
+            .filter(|account| account.metadata().has_tag(tag))
+            .cloned()
+            .collect();
+        paginate(tagged, page_size, page)
+    }
+
+    fn find_by_approved_address(
+        &self,
+        address: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        let approved = self
+            .accounts
+            .borrow()
+            .values()
+            .filter(|account| {
+                account
+                    .approved_address()
+                    .is_some_and(|approved| approved.to_string() == address)
+            })
+            .cloned()
+            .collect();
+        paginate(approved, page_size, page)
+    }
+
+    fn find_by_state(
+        &self,
+        state: &AccountState,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<Account>, String> {
+        let in_state = self
+            .accounts
+            .borrow()
+            .values()
+            .filter(|account| account.account_state() == state)
+            .cloned()
+            .collect();
+        paginate(in_state, page_size, page)
+    }
+
+    fn scan_after(&self, after: Option<&str>, limit: usize) -> Result<Vec<Account>, String> {
+        if limit == 0 {
+            return Err("Limit must be greater than 0.".to_string());
+        }
+        let start = match after {
+            Some(id) => Bound::Excluded(id.to_string()),
+            None => Bound::Unbounded,
+        };
+        let accounts = self.accounts.borrow();
+        Ok(accounts
+            .range((start, Bound::Unbounded))
+            .map(|(_, account)| account.clone())
+            .take(limit)
+            .collect())
+    }
+}
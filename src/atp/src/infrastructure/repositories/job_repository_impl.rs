@@ -0,0 +1,89 @@
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::job::Job;
+use crate::domain::repositories::job_repository::IJobRepository;
+
+/// Upper bound on the number of jobs listed at once
+const MAX_JOBS: usize = 1000;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static JOB_REPOSITORY: RefCell<Option<JobRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct JobRepositoryImpl {}
+
+impl JobRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and job repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the Job model, keyed by job name
+        db_manager.register_model("jobs", Some(5), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        JOB_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(JobRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global job repository instance
+    pub fn global() -> Self {
+        JOB_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => {
+                panic!("JobRepositoryImpl not initialized! Call JobRepositoryImpl::init() first.")
+            }
+        })
+    }
+
+    /// Get a database instance for Job operations
+    fn get_database(&self) -> Result<ic_nosql::Database<Job>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("jobs")
+        })
+    }
+}
+
+impl IJobRepository for JobRepositoryImpl {
+    fn insert(&self, job: Job) -> Result<Job, String> {
+        let db = self.get_database()?;
+        let document = db.insert(job.name().clone(), None, job)?;
+        Ok(document.data)
+    }
+
+    fn get(&self, name: &str) -> Result<Job, String> {
+        let db = self.get_database()?;
+        let document = db.get(name, None)?;
+        Ok(document.data)
+    }
+
+    fn find_all(&self) -> Result<Vec<Job>, String> {
+        let db = self.get_database()?;
+        let query_result = db.scan(MAX_JOBS, 1)?;
+
+        let jobs = query_result
+            .results
+            .into_iter()
+            .map(|doc| doc.data)
+            .collect();
+
+        Ok(jobs)
+    }
+}
@@ -0,0 +1,257 @@
+//! Deterministic in-process signer for tests
+//!
+//! Keys are derived from a seed and the derivation path, so the same account always gets
+//! the same key without a replica or threshold keys. Never use it to hold real funds.
+
+use atp_caip::curve::Curve;
+use ed25519_dalek::Signer;
+use ethers_core::types::transaction::eip1559::Eip1559TransactionRequest;
+use k256::ecdsa::signature::hazmat::PrehashSigner;
+use k256::sha2::{Digest, Sha256};
+
+use crate::domain::models::signer::SignatureAlgorithm;
+use crate::domain::repositories::signer_repository::{
+    ISignerRepository, PublicKeyReply, SignatureReply,
+};
+use crate::infrastructure::repositories::signer_repository_impl::sign_eip1559_transaction_with;
+
+#[derive(Clone)]
+pub struct LocalSignerRepositoryImpl {
+    seed: Vec<u8>,
+}
+
+impl Default for LocalSignerRepositoryImpl {
+    fn default() -> Self {
+        Self::new(b"atp-local-signer")
+    }
+}
+
+impl LocalSignerRepositoryImpl {
+    pub fn new(seed: &[u8]) -> Self {
+        Self {
+            seed: seed.to_vec(),
+        }
+    }
+
+    // Hash the seed, a domain tag and the length-prefixed path elements into key material
+    fn derive_secret(&self, tag: &str, derivation_path: &[Vec<u8>], counter: u32) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.seed);
+        hasher.update(tag.as_bytes());
+        for element in derivation_path {
+            hasher.update((element.len() as u64).to_be_bytes());
+            hasher.update(element);
+        }
+        hasher.update(counter.to_be_bytes());
+        hasher.finalize().into()
+    }
+
+    // The rare hashes outside the secp256k1 scalar range are skipped
+    fn secp256k1_key(&self, derivation_path: &[Vec<u8>]) -> k256::ecdsa::SigningKey {
+        (0..)
+            .find_map(|counter| {
+                let secret = self.derive_secret("secp256k1", derivation_path, counter);
+                k256::ecdsa::SigningKey::from_slice(&secret).ok()
+            })
+            .expect("Failed to derive a secp256k1 key")
+    }
+
+    fn ed25519_key(&self, derivation_path: &[Vec<u8>]) -> ed25519_dalek::SigningKey {
+        ed25519_dalek::SigningKey::from_bytes(&self.derive_secret("ed25519", derivation_path, 0))
+    }
+
+    fn public_key(
+        &self,
+        algorithm: SignatureAlgorithm,
+        curve: Curve,
+        derivation_path: &[Vec<u8>],
+    ) -> Result<Vec<u8>, String> {
+        match (algorithm, curve) {
+            // Both secp256k1 schemes return the SEC1 compressed key, like the management canister
+            (_, Curve::Secp256k1) => Ok(self
+                .secp256k1_key(derivation_path)
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes()
+                .to_vec()),
+            (SignatureAlgorithm::Schnorr, Curve::Ed25519) => Ok(self
+                .ed25519_key(derivation_path)
+                .verifying_key()
+                .to_bytes()
+                .to_vec()),
+            (SignatureAlgorithm::Ecdsa, Curve::Ed25519) => {
+                Err("Curve not supported for ECDSA".to_string())
+            }
+        }
+    }
+
+    fn signature(
+        &self,
+        algorithm: SignatureAlgorithm,
+        curve: Curve,
+        message: &[u8],
+        derivation_path: &[Vec<u8>],
+    ) -> Result<Vec<u8>, String> {
+        match (algorithm, curve) {
+            (SignatureAlgorithm::Ecdsa, Curve::Secp256k1) => {
+                let signature: k256::ecdsa::Signature = self
+                    .secp256k1_key(derivation_path)
+                    .sign_prehash(message)
+                    .map_err(|e| format!("sign failed {}", e))?;
+                Ok(signature.to_bytes().to_vec())
+            }
+            (SignatureAlgorithm::Schnorr, Curve::Secp256k1) => {
+                let key = self.secp256k1_key(derivation_path);
+                let signing_key = k256::schnorr::SigningKey::from_bytes(&key.to_bytes())
+                    .map_err(|e| format!("sign failed {}", e))?;
+                // Zero auxiliary randomness keeps the signatures deterministic
+                let signature = signing_key
+                    .sign_raw(message, &[0; 32])
+                    .map_err(|e| format!("sign failed {}", e))?;
+                Ok(signature.to_bytes().to_vec())
+            }
+            (SignatureAlgorithm::Schnorr, Curve::Ed25519) => Ok(self
+                .ed25519_key(derivation_path)
+                .sign(message)
+                .to_bytes()
+                .to_vec()),
+            (SignatureAlgorithm::Ecdsa, Curve::Ed25519) => {
+                Err("Curve not supported for ECDSA".to_string())
+            }
+        }
+    }
+}
+
+impl ISignerRepository for LocalSignerRepositoryImpl {
+    async fn generate_public_key(
+        &self,
+        algorithm: SignatureAlgorithm,
+        curve: Curve,
+        derivation_path: Vec<Vec<u8>>,
+    ) -> Result<PublicKeyReply, String> {
+        Ok(PublicKeyReply {
+            public_key: self.public_key(algorithm, curve, &derivation_path)?,
+            chain_code: self
+                .derive_secret("chain_code", &derivation_path, 0)
+                .to_vec(),
+        })
+    }
+
+    async fn sign(
+        &self,
+        algorithm: SignatureAlgorithm,
+        curve: Curve,
+        message_hash: Vec<u8>,
+        derivation_path: Vec<Vec<u8>>,
+    ) -> Result<SignatureReply, String> {
+        Ok(SignatureReply {
+            signature: self.signature(algorithm, curve, &message_hash, &derivation_path)?,
+        })
+    }
+
+    async fn sign_eip1559_transaction(
+        &self,
+        tx: Eip1559TransactionRequest,
+        derivation_path: Vec<Vec<u8>>,
+    ) -> Result<String, String> {
+        sign_eip1559_transaction_with(self, tx, derivation_path).await
+    }
+}
+
+#[cfg(test)]
+mod local_signer_repository_tests {
+    use super::*;
+    use ed25519_dalek::Verifier;
+    use k256::ecdsa::signature::hazmat::PrehashVerifier;
+
+    fn path(id: &str) -> Vec<Vec<u8>> {
+        vec![id.as_bytes().to_vec()]
+    }
+
+    #[tokio::test]
+    async fn test_keys_are_deterministic_per_path() {
+        let signer = LocalSignerRepositoryImpl::new(b"seed");
+        let key = |id: &str, curve: Curve| {
+            let algorithm = match curve {
+                Curve::Secp256k1 => SignatureAlgorithm::Ecdsa,
+                Curve::Ed25519 => SignatureAlgorithm::Schnorr,
+            };
+            signer.public_key(algorithm, curve, &path(id)).unwrap()
+        };
+
+        assert_eq!(key("a", Curve::Secp256k1), key("a", Curve::Secp256k1));
+        assert_ne!(key("a", Curve::Secp256k1), key("b", Curve::Secp256k1));
+        assert_eq!(key("a", Curve::Secp256k1).len(), 33);
+        assert_eq!(key("a", Curve::Ed25519).len(), 32);
+
+        let other = LocalSignerRepositoryImpl::new(b"other seed");
+        assert_ne!(
+            other
+                .generate_public_key(SignatureAlgorithm::Ecdsa, Curve::Secp256k1, path("a"))
+                .await
+                .unwrap()
+                .public_key,
+            key("a", Curve::Secp256k1)
+        );
+        assert!(signer
+            .generate_public_key(SignatureAlgorithm::Ecdsa, Curve::Ed25519, path("a"))
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_signatures_verify() {
+        let signer = LocalSignerRepositoryImpl::default();
+        let message = [7u8; 32];
+
+        let signature = signer
+            .sign(
+                SignatureAlgorithm::Ecdsa,
+                Curve::Secp256k1,
+                message.to_vec(),
+                path("a"),
+            )
+            .await
+            .unwrap()
+            .signature;
+        let signature = k256::ecdsa::Signature::from_slice(&signature).unwrap();
+        assert!(signer
+            .secp256k1_key(&path("a"))
+            .verifying_key()
+            .verify_prehash(&message, &signature)
+            .is_ok());
+
+        let signature = signer
+            .sign(
+                SignatureAlgorithm::Schnorr,
+                Curve::Secp256k1,
+                message.to_vec(),
+                path("a"),
+            )
+            .await
+            .unwrap()
+            .signature;
+        let key =
+            k256::schnorr::SigningKey::from_bytes(&signer.secp256k1_key(&path("a")).to_bytes())
+                .unwrap();
+        let signature = k256::schnorr::Signature::try_from(signature.as_slice()).unwrap();
+        assert!(key.verifying_key().verify_raw(&message, &signature).is_ok());
+
+        let signature = signer
+            .sign(
+                SignatureAlgorithm::Schnorr,
+                Curve::Ed25519,
+                message.to_vec(),
+                path("a"),
+            )
+            .await
+            .unwrap()
+            .signature;
+        let signature = ed25519_dalek::Signature::from_slice(&signature).unwrap();
+        assert!(signer
+            .ed25519_key(&path("a"))
+            .verifying_key()
+            .verify(&message, &signature)
+            .is_ok());
+    }
+}
@@ -0,0 +1,38 @@
+use candid::{Nat, Principal};
+use ic_cdk::api::management_canister::main::{deposit_cycles, CanisterIdRecord};
+
+use crate::domain::repositories::payment_repository::{
+    IPaymentRepository, Icrc1Account, Icrc1TransferArg, Icrc1TransferError,
+};
+
+#[derive(Clone, Default)]
+pub struct PaymentRepositoryImpl {}
+
+impl PaymentRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl IPaymentRepository for PaymentRepositoryImpl {
+    async fn balance_of(&self, ledger: Principal, account: Icrc1Account) -> Result<Nat, String> {
+        let (balance,): (Nat,) = ic_cdk::call(ledger, "icrc1_balance_of", (account,))
+            .await
+            .map_err(|e| format!("icrc1_balance_of failed {}", e.1))?;
+        Ok(balance)
+    }
+
+    async fn transfer(&self, ledger: Principal, args: Icrc1TransferArg) -> Result<Nat, String> {
+        let (result,): (Result<Nat, Icrc1TransferError>,) =
+            ic_cdk::call(ledger, "icrc1_transfer", (args,))
+                .await
+                .map_err(|e| format!("icrc1_transfer failed {}", e.1))?;
+        result.map_err(|e| format!("icrc1_transfer rejected {:?}", e))
+    }
+
+    async fn deposit_cycles(&self, canister_id: Principal, cycles: u128) -> Result<(), String> {
+        deposit_cycles(CanisterIdRecord { canister_id }, cycles)
+            .await
+            .map_err(|e| format!("deposit_cycles failed {}", e.1))
+    }
+}
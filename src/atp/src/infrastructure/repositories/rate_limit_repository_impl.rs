@@ -0,0 +1,48 @@
+use candid::Principal;
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use crate::domain::models::rate_limit::{RateLimitOperation, TokenBucket};
+use crate::domain::repositories::rate_limit_repository::IRateLimitRepository;
+
+// Buckets only throttle callers, so they live in heap memory and are reset by upgrades
+thread_local! {
+    static BUCKETS: RefCell<BTreeMap<(Principal, RateLimitOperation), TokenBucket>> = const { RefCell::new(BTreeMap::new()) };
+}
+
+#[derive(Clone, Default)]
+pub struct RateLimitRepositoryImpl {}
+
+impl RateLimitRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl IRateLimitRepository for RateLimitRepositoryImpl {
+    fn insert(&self, caller: Principal, operation: RateLimitOperation, bucket: TokenBucket) {
+        BUCKETS.with(|buckets| {
+            buckets.borrow_mut().insert((caller, operation), bucket);
+        });
+    }
+
+    fn get(&self, caller: &Principal, operation: &RateLimitOperation) -> Option<TokenBucket> {
+        BUCKETS.with(|buckets| buckets.borrow().get(&(*caller, operation.clone())).cloned())
+    }
+
+    fn remove(&self, caller: &Principal, operation: &RateLimitOperation) {
+        BUCKETS.with(|buckets| {
+            buckets.borrow_mut().remove(&(*caller, operation.clone()));
+        });
+    }
+
+    fn find_all(&self) -> Vec<(Principal, RateLimitOperation, TokenBucket)> {
+        BUCKETS.with(|buckets| {
+            buckets
+                .borrow()
+                .iter()
+                .map(|((caller, operation), bucket)| (*caller, operation.clone(), bucket.clone()))
+                .collect()
+        })
+    }
+}
@@ -0,0 +1,87 @@
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::registry::RegistryEntry;
+use crate::domain::repositories::registry_repository::IRegistryRepository;
+
+/// Upper bound on the number of registry entries loaded at once
+const MAX_REGISTRY_ENTRIES: usize = 10_000;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static REGISTRY_REPOSITORY: RefCell<Option<RegistryRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct RegistryRepositoryImpl {}
+
+impl RegistryRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and registry repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the RegistryEntry model, partitioned by entry kind
+        db_manager.register_model("registry", Some(7), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        REGISTRY_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(RegistryRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global registry repository instance
+    pub fn global() -> Self {
+        REGISTRY_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => {
+                panic!("RegistryRepositoryImpl not initialized! Call RegistryRepositoryImpl::init() first.")
+            }
+        })
+    }
+
+    /// Get a database instance for RegistryEntry operations
+    fn get_database(&self) -> Result<ic_nosql::Database<RegistryEntry>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("registry")
+        })
+    }
+}
+
+impl IRegistryRepository for RegistryRepositoryImpl {
+    fn insert(&self, entry: RegistryEntry) -> Result<RegistryEntry, String> {
+        let db = self.get_database()?;
+        let document = db.insert(
+            entry.kind().partition().to_string(),
+            Some(entry.key().clone()),
+            entry,
+        )?;
+        Ok(document.data)
+    }
+
+    fn find_all(&self) -> Result<Vec<RegistryEntry>, String> {
+        let db = self.get_database()?;
+        let query_result = db.scan(MAX_REGISTRY_ENTRIES, 1)?;
+
+        let entries = query_result
+            .results
+            .into_iter()
+            .map(|doc| doc.data)
+            .collect();
+
+        Ok(entries)
+    }
+}
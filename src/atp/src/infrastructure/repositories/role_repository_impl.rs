@@ -0,0 +1,90 @@
+use candid::Principal;
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::role::RoleAssignment;
+use crate::domain::repositories::role_repository::IRoleRepository;
+
+/// Upper bound on the number of role assignments listed at once
+const MAX_ROLE_ASSIGNMENTS: usize = 1000;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static ROLE_REPOSITORY: RefCell<Option<RoleRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct RoleRepositoryImpl {}
+
+impl RoleRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and role repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the RoleAssignment model, keyed by principal
+        db_manager.register_model("roles", Some(6), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        ROLE_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(RoleRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global role repository instance
+    pub fn global() -> Self {
+        ROLE_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => {
+                panic!("RoleRepositoryImpl not initialized! Call RoleRepositoryImpl::init() first.")
+            }
+        })
+    }
+
+    /// Get a database instance for RoleAssignment operations
+    fn get_database(&self) -> Result<ic_nosql::Database<RoleAssignment>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("roles")
+        })
+    }
+}
+
+impl IRoleRepository for RoleRepositoryImpl {
+    fn insert(&self, assignment: RoleAssignment) -> Result<RoleAssignment, String> {
+        let db = self.get_database()?;
+        let document = db.insert(assignment.principal().to_string(), None, assignment)?;
+        Ok(document.data)
+    }
+
+    fn get(&self, principal: &Principal) -> Result<RoleAssignment, String> {
+        let db = self.get_database()?;
+        let document = db.get(&principal.to_string(), None)?;
+        Ok(document.data)
+    }
+
+    fn find_all(&self) -> Result<Vec<RoleAssignment>, String> {
+        let db = self.get_database()?;
+        let query_result = db.scan(MAX_ROLE_ASSIGNMENTS, 1)?;
+
+        let assignments = query_result
+            .results
+            .into_iter()
+            .map(|doc| doc.data)
+            .collect();
+
+        Ok(assignments)
+    }
+}
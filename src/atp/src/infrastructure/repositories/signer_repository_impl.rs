@@ -36,6 +36,11 @@ impl SignerRepositoryImpl {
         });
     }
 
+    /// Get the threshold key used for signing
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
     /// Get the global signer repository instance
     pub fn global() -> Self {
         SIGNER_REPOSITORY.with(|repo| match &*repo.borrow() {
@@ -52,7 +57,7 @@ impl ISignerRepository for SignerRepositoryImpl {
         &self,
         algorithm: SignatureAlgorithm,
         curve: Curve,
-        derivation_path: String,
+        derivation_path: Vec<Vec<u8>>,
     ) -> impl Future<Output = Result<PublicKeyReply, String>> {
         async move {
             let result = match algorithm {
@@ -60,7 +65,7 @@ impl ISignerRepository for SignerRepositoryImpl {
                     Curve::Secp256k1 => {
                         let request = EcdsaPublicKeyRequest {
                             canister_id: None,
-                            derivation_path: vec![derivation_path.as_bytes().to_vec()],
+                            derivation_path,
                             key_id: EcdsaKeyId {
                                 curve: EcdsaKeyIdCurve::Ecdsa,
                                 name: self.key_id.clone(),
@@ -82,7 +87,7 @@ impl ISignerRepository for SignerRepositoryImpl {
                     Curve::Secp256k1 => {
                         let request = SchnorrPublicKeyRequest {
                             canister_id: None,
-                            derivation_path: vec![derivation_path.as_bytes().to_vec()],
+                            derivation_path,
                             key_id: SchnorrKeyId {
                                 algorithm: SchnorrKeyIdAlgorithm::SchnorrBip340Secp256k1,
                                 name: self.key_id.clone(),
@@ -101,7 +106,7 @@ impl ISignerRepository for SignerRepositoryImpl {
                     Curve::Ed25519 => {
                         let request = SchnorrPublicKeyRequest {
                             canister_id: None,
-                            derivation_path: vec![derivation_path.as_bytes().to_vec()],
+                            derivation_path,
                             key_id: SchnorrKeyId {
                                 algorithm: SchnorrKeyIdAlgorithm::SchnorrEd25519,
                                 name: self.key_id.clone(),
@@ -128,14 +133,14 @@ impl ISignerRepository for SignerRepositoryImpl {
         algorithm: SignatureAlgorithm,
         curve: Curve,
         message_hash: Vec<u8>,
-        derivation_path: String,
+        derivation_path: Vec<Vec<u8>>,
     ) -> impl Future<Output = Result<SignatureReply, String>> {
         async move {
             match algorithm {
                 SignatureAlgorithm::Ecdsa => {
                     let request = EcdsaSignatureRequest {
                         message_hash: message_hash.to_vec(),
-                        derivation_path: vec![derivation_path.as_bytes().to_vec()],
+                        derivation_path,
                         key_id: EcdsaKeyId {
                             curve: EcdsaKeyIdCurve::Ecdsa,
                             name: self.key_id.clone(),
@@ -157,7 +162,7 @@ impl ISignerRepository for SignerRepositoryImpl {
                     Curve::Secp256k1 => {
                         let request = SchnorrSignatureRequest {
                             message: message_hash.to_vec(),
-                            derivation_path: vec![derivation_path.as_bytes().to_vec()],
+                            derivation_path,
                             key_id: SchnorrKeyId {
                                 algorithm: SchnorrKeyIdAlgorithm::SchnorrBip340Secp256k1,
                                 name: self.key_id.clone(),
@@ -177,7 +182,7 @@ impl ISignerRepository for SignerRepositoryImpl {
                     Curve::Ed25519 => {
                         let request = SchnorrSignatureRequest {
                             message: message_hash.to_vec(),
-                            derivation_path: vec![derivation_path.as_bytes().to_vec()],
+                            derivation_path,
                             key_id: SchnorrKeyId {
                                 algorithm: SchnorrKeyIdAlgorithm::SchnorrEd25519,
                                 name: self.key_id.clone(),
@@ -202,54 +207,63 @@ impl ISignerRepository for SignerRepositoryImpl {
     fn sign_eip1559_transaction(
         &self,
         tx: Eip1559TransactionRequest,
-        derivation_path: String,
+        derivation_path: Vec<Vec<u8>>,
     ) -> impl Future<Output = Result<String, String>> {
-        async move {
-            const EIP1559_TX_ID: u8 = 2;
+        async move { sign_eip1559_transaction_with(self, tx, derivation_path).await }
+    }
+}
 
-            // Get the public key
-            let public_key = self
-                .generate_public_key(
-                    SignatureAlgorithm::Ecdsa,
-                    Curve::Secp256k1,
-                    derivation_path.clone(),
-                )
-                .await?
-                .public_key;
+/// Sign an EIP-1559 transaction with the secp256k1 ECDSA key of any signer
+///
+/// Returns the signed transaction as a hex string, ready to broadcast.
+pub(crate) async fn sign_eip1559_transaction_with<S: ISignerRepository + Sync>(
+    signer: &S,
+    tx: Eip1559TransactionRequest,
+    derivation_path: Vec<Vec<u8>>,
+) -> Result<String, String> {
+    const EIP1559_TX_ID: u8 = 2;
 
-            // Prepare transaction for signing
-            let mut unsigned_tx_bytes = tx.rlp().to_vec();
-            unsigned_tx_bytes.insert(0, EIP1559_TX_ID);
+    // Get the public key
+    let public_key = signer
+        .generate_public_key(
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            derivation_path.clone(),
+        )
+        .await?
+        .public_key;
 
-            let txhash = keccak256(&unsigned_tx_bytes);
+    // Prepare transaction for signing
+    let mut unsigned_tx_bytes = tx.rlp().to_vec();
+    unsigned_tx_bytes.insert(0, EIP1559_TX_ID);
 
-            let signature = self
-                .sign(
-                    SignatureAlgorithm::Ecdsa,
-                    Curve::Secp256k1,
-                    txhash.to_vec(),
-                    derivation_path.clone(),
-                )
-                .await?
-                .signature;
+    let txhash = keccak256(&unsigned_tx_bytes);
 
-            // Recover signature parity
-            let v = recover_signature_parity(&txhash, &signature, &public_key)
-                .map_err(|e| format!("Signature recovery failed: {}", e))?;
+    let signature = signer
+        .sign(
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            txhash.to_vec(),
+            derivation_path,
+        )
+        .await?
+        .signature;
 
-            let signature = Signature {
-                v: v as u64,
-                r: U256::from_big_endian(&signature[0..32]),
-                s: U256::from_big_endian(&signature[32..64]),
-            };
+    // Recover signature parity
+    let v = recover_signature_parity(&txhash, &signature, &public_key)
+        .map_err(|e| format!("Signature recovery failed: {}", e))?;
 
-            // Create signed transaction
-            let mut signed_tx_bytes = tx.rlp_signed(&signature).to_vec();
-            signed_tx_bytes.insert(0, EIP1559_TX_ID);
+    let signature = Signature {
+        v: v as u64,
+        r: U256::from_big_endian(&signature[0..32]),
+        s: U256::from_big_endian(&signature[32..64]),
+    };
 
-            Ok(format!("0x{}", hex::encode(&signed_tx_bytes)))
-        }
-    }
+    // Create signed transaction
+    let mut signed_tx_bytes = tx.rlp_signed(&signature).to_vec();
+    signed_tx_bytes.insert(0, EIP1559_TX_ID);
+
+    Ok(format!("0x{}", hex::encode(&signed_tx_bytes)))
 }
 
 fn recover_signature_parity(message: &[u8], signature: &[u8], pubkey: &[u8]) -> Result<u8, String> {
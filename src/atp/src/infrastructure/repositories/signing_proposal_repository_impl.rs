@@ -0,0 +1,174 @@
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::signing_proposal::SigningProposal;
+use crate::domain::repositories::signing_proposal_repository::ISigningProposalRepository;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static SIGNING_PROPOSAL_REPOSITORY: RefCell<Option<SigningProposalRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct SigningProposalRepositoryImpl {}
+
+impl SigningProposalRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and signing proposal repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the SigningProposal model, partitioned by account ID
+        db_manager.register_model("signing_proposals", Some(2), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        SIGNING_PROPOSAL_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(SigningProposalRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global signing proposal repository instance
+    pub fn global() -> Self {
+        SIGNING_PROPOSAL_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => panic!(
+                "SigningProposalRepositoryImpl not initialized! Call SigningProposalRepositoryImpl::init() first."
+            ),
+        })
+    }
+
+    /// Get a database instance for SigningProposal operations
+    fn get_database(&self) -> Result<ic_nosql::Database<SigningProposal>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("signing_proposals")
+        })
+    }
+}
+
+impl ISigningProposalRepository for SigningProposalRepositoryImpl {
+    fn insert(&self, proposal: SigningProposal) -> Result<SigningProposal, String> {
+        let db = self.get_database()?;
+        let document = db.insert(
+            proposal.account_id().clone(),
+            Some(proposal.id().clone()),
+            proposal,
+        )?;
+        Ok(document.data)
+    }
+
+    fn get(&self, account_id: &str, proposal_id: &str) -> Result<SigningProposal, String> {
+        let db = self.get_database()?;
+        let document = db.get(account_id, Some(proposal_id.to_string()))?;
+        Ok(document.data)
+    }
+
+    fn find_by_account(
+        &self,
+        account_id: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<SigningProposal>, String> {
+        let db = self.get_database()?;
+        let query_result = db.query(Some(account_id), None, page_size, page)?;
+
+        let proposals = query_result
+            .results
+            .into_iter()
+            .map(|doc| doc.data)
+            .collect();
+
+        Ok(proposals)
+    }
+}
+
+#[cfg(test)]
+mod signing_proposal_repository_tests {
+    use atp_caip::curve::Curve;
+    use candid::Principal;
+
+    use crate::domain::models::account::Account;
+    use crate::domain::models::signer::SignatureAlgorithm;
+    use crate::domain::models::signing_proposal::{
+        SigningPayload, SigningProposal, SigningProposalStatus,
+    };
+    use crate::domain::repositories::signing_proposal_repository::ISigningProposalRepository;
+    use crate::infrastructure::repositories::signing_proposal_repository_impl::SigningProposalRepositoryImpl;
+
+    // Helper function to create a test proposal
+    fn create_test_proposal(account_id: &str, proposal_id: &str) -> SigningProposal {
+        let owner = Principal::from_text("2vxsx-fae").unwrap();
+        let account = Account::new(
+            account_id.to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            owner,
+        );
+        SigningProposal::new(
+            proposal_id.to_string(),
+            &account,
+            owner,
+            SigningPayload::Message {
+                message_hex: "deadbeef".to_string(),
+            },
+            0,
+            100,
+        )
+    }
+
+    // Set up a clean test environment before each test
+    fn setup() -> SigningProposalRepositoryImpl {
+        SigningProposalRepositoryImpl::init().expect("Failed to initialize repository");
+        SigningProposalRepositoryImpl::new()
+    }
+
+    #[test]
+    fn test_insert_and_get_proposal() {
+        let repo = setup();
+        let proposal = create_test_proposal("account-1", "proposal-1");
+
+        repo.insert(proposal.clone())
+            .expect("Failed to insert proposal");
+
+        let retrieved = repo
+            .get("account-1", "proposal-1")
+            .expect("Failed to get proposal");
+        assert_eq!(retrieved.id(), proposal.id());
+        assert_eq!(retrieved.account_id(), proposal.account_id());
+        assert_eq!(retrieved.status(), &SigningProposalStatus::Pending);
+
+        // The proposal is only reachable under its own account
+        assert!(repo.get("account-2", "proposal-1").is_err());
+    }
+
+    #[test]
+    fn test_find_by_account() {
+        let repo = setup();
+        for proposal_id in ["proposal-a", "proposal-b", "proposal-c"] {
+            repo.insert(create_test_proposal("account-3", proposal_id))
+                .expect("Failed to insert proposal");
+        }
+        repo.insert(create_test_proposal("account-4", "proposal-d"))
+            .expect("Failed to insert proposal");
+
+        let proposals = repo
+            .find_by_account("account-3", 100, 1)
+            .expect("Failed to find proposals");
+        assert_eq!(proposals.len(), 3);
+        assert!(proposals.iter().all(|p| p.account_id() == "account-3"));
+    }
+}
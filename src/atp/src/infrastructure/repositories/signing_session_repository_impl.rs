@@ -0,0 +1,162 @@
+use ic_nosql::DatabaseManager;
+use std::cell::RefCell;
+
+use crate::domain::models::signing_session::SigningSession;
+use crate::domain::repositories::signing_session_repository::ISigningSessionRepository;
+
+thread_local! {
+    static DB_MANAGER: RefCell<Option<DatabaseManager>> = const { RefCell::new(None) };
+    static SIGNING_SESSION_REPOSITORY: RefCell<Option<SigningSessionRepositoryImpl>> = const { RefCell::new(None) };
+}
+
+#[derive(Clone, Default)]
+pub struct SigningSessionRepositoryImpl {}
+
+impl SigningSessionRepositoryImpl {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    /// Initialize the database manager and signing session repository
+    pub fn init() -> Result<(), String> {
+        // Initialize database manager
+        let db_manager = DatabaseManager::new();
+
+        // Register the SigningSession model, partitioned by account ID
+        db_manager.register_model("signing_sessions", Some(3), None)?;
+
+        // Store the database manager
+        DB_MANAGER.with(|manager| {
+            *manager.borrow_mut() = Some(db_manager);
+        });
+
+        // Initialize repository instance
+        SIGNING_SESSION_REPOSITORY.with(|repo| {
+            *repo.borrow_mut() = Some(SigningSessionRepositoryImpl::new());
+        });
+
+        Ok(())
+    }
+
+    /// Get the global signing session repository instance
+    pub fn global() -> Self {
+        SIGNING_SESSION_REPOSITORY.with(|repo| match &*repo.borrow() {
+            Some(instance) => instance.clone(),
+            None => panic!(
+                "SigningSessionRepositoryImpl not initialized! Call SigningSessionRepositoryImpl::init() first."
+            ),
+        })
+    }
+
+    /// Get a database instance for SigningSession operations
+    fn get_database(&self) -> Result<ic_nosql::Database<SigningSession>, String> {
+        DB_MANAGER.with(|manager| {
+            let manager = manager.borrow();
+            let db_manager = manager.as_ref().ok_or("Database manager not initialized")?;
+            db_manager.get_simple_database("signing_sessions")
+        })
+    }
+}
+
+impl ISigningSessionRepository for SigningSessionRepositoryImpl {
+    fn insert(&self, session: SigningSession) -> Result<SigningSession, String> {
+        let db = self.get_database()?;
+        let document = db.insert(
+            session.account_id().clone(),
+            Some(session.id().clone()),
+            session,
+        )?;
+        Ok(document.data)
+    }
+
+    fn get(&self, account_id: &str, session_id: &str) -> Result<SigningSession, String> {
+        let db = self.get_database()?;
+        let document = db.get(account_id, Some(session_id.to_string()))?;
+        Ok(document.data)
+    }
+
+    fn find_by_account(
+        &self,
+        account_id: &str,
+        page_size: usize,
+        page: usize,
+    ) -> Result<Vec<SigningSession>, String> {
+        let db = self.get_database()?;
+        let query_result = db.query(Some(account_id), None, page_size, page)?;
+
+        let sessions = query_result
+            .results
+            .into_iter()
+            .map(|doc| doc.data)
+            .collect();
+
+        Ok(sessions)
+    }
+}
+
+#[cfg(test)]
+mod signing_session_repository_tests {
+    use atp_caip::curve::Curve;
+    use candid::Principal;
+
+    use crate::domain::models::account::Account;
+    use crate::domain::models::signer::SignatureAlgorithm;
+    use crate::domain::models::signing_session::{SessionLimits, SigningSession};
+    use crate::domain::repositories::signing_session_repository::ISigningSessionRepository;
+    use crate::infrastructure::repositories::signing_session_repository_impl::SigningSessionRepositoryImpl;
+
+    // Helper function to create a test session
+    fn create_test_session(account_id: &str, session_id: &str) -> SigningSession {
+        let owner = Principal::from_slice(&[1; 29]);
+        let account = Account::new(
+            account_id.to_string(),
+            owner,
+            vec![1, 2, 3],
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            owner,
+        );
+        SigningSession::new(
+            session_id.to_string(),
+            &account,
+            Principal::from_slice(&[2; 29]),
+            0,
+            100,
+            SessionLimits {
+                max_signatures: 10,
+                allowed_chains: vec![],
+                allowed_methods: vec![],
+                max_value: None,
+            },
+        )
+    }
+
+    // Set up a clean test environment before each test
+    fn setup() -> SigningSessionRepositoryImpl {
+        SigningSessionRepositoryImpl::init().expect("Failed to initialize repository");
+        SigningSessionRepositoryImpl::new()
+    }
+
+    #[test]
+    fn test_insert_and_find_sessions() {
+        let repo = setup();
+        for session_id in ["session-a", "session-b"] {
+            repo.insert(create_test_session("account-1", session_id))
+                .expect("Failed to insert session");
+        }
+        repo.insert(create_test_session("account-2", "session-c"))
+            .expect("Failed to insert session");
+
+        let retrieved = repo
+            .get("account-1", "session-a")
+            .expect("Failed to get session");
+        assert_eq!(retrieved.id(), "session-a");
+        assert!(repo.get("account-2", "session-a").is_err());
+
+        let sessions = repo
+            .find_by_account("account-1", 100, 1)
+            .expect("Failed to find sessions");
+        assert_eq!(sessions.len(), 2);
+        assert!(sessions.iter().all(|s| s.account_id() == "account-1"));
+    }
+}
@@ -3,4 +3,18 @@ pub mod domain;
 pub mod endpoints;
 pub mod infrastructure;
 pub mod lifecycle;
+pub mod scheduler;
 pub mod utils;
+
+use crate::application::dtos::account_messages::*;
+use crate::application::dtos::audit_messages::*;
+use crate::application::dtos::canister_config_messages::*;
+use crate::application::dtos::fee_messages::*;
+use crate::application::dtos::job_messages::*;
+use crate::application::dtos::registry_messages::*;
+use crate::application::dtos::role_messages::*;
+use crate::application::dtos::signing_proposal_messages::*;
+use crate::application::dtos::signing_session_messages::*;
+
+// Export the Candid interface
+ic_cdk::export_candid!();
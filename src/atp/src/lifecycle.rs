@@ -1,19 +1,61 @@
 use ic_cdk::api::time;
-use ic_cdk::{heartbeat, init, post_upgrade, pre_upgrade};
+use ic_cdk::{init, inspect_message, post_upgrade, pre_upgrade};
 
+use crate::application::dtos::canister_config_messages::{CanisterArgs, InitArgs, UpgradeArgs};
+use crate::application::services::canister_config_service::CanisterConfigService;
+use crate::application::services::registry_service::RegistryService;
+use crate::application::services::role_service::RoleService;
+use crate::endpoints::account_endpoints::get_rate_limit_service;
 use crate::infrastructure::repositories::account_repository_impl::AccountRepositoryImpl;
+use crate::infrastructure::repositories::audit_event_repository_impl::AuditEventRepositoryImpl;
+use crate::infrastructure::repositories::canister_config_repository_impl::CanisterConfigRepositoryImpl;
+use crate::infrastructure::repositories::fee_account_repository_impl::FeeAccountRepositoryImpl;
+use crate::infrastructure::repositories::job_repository_impl::JobRepositoryImpl;
+use crate::infrastructure::repositories::registry_repository_impl::RegistryRepositoryImpl;
+use crate::infrastructure::repositories::role_repository_impl::RoleRepositoryImpl;
 use crate::infrastructure::repositories::signer_repository_impl::SignerRepositoryImpl;
-use crate::utils::config::KEY_ID;
+use crate::infrastructure::repositories::signing_proposal_repository_impl::SigningProposalRepositoryImpl;
+use crate::infrastructure::repositories::signing_session_repository_impl::SigningSessionRepositoryImpl;
+use crate::scheduler;
+use crate::utils::config::registry_seed_config;
 
 /// Initialize the canister
 /// This function is called exactly once when the canister is first deployed
+///
+/// Settings missing from the init arguments fall back to the build defaults.
 #[init]
-fn init() {
+fn init(args: Option<CanisterArgs>) {
     ic_cdk::println!("[{}] Initializing canister", time());
 
+    let args = match args {
+        None => InitArgs::default(),
+        Some(CanisterArgs::Init(args)) => args,
+        Some(CanisterArgs::Upgrade(_)) => ic_cdk::trap("Expected init arguments"),
+    };
+
     // Initialize the repositories
-    SignerRepositoryImpl::init(KEY_ID.to_string());
-    AccountRepositoryImpl::init().expect("Failed to initialize account repository");
+    init_repositories();
+    let config = CanisterConfigService::new(CanisterConfigRepositoryImpl::global())
+        .install(&args)
+        .unwrap_or_else(|e| ic_cdk::trap(&e));
+    SignerRepositoryImpl::init(config.key_id().clone());
+
+    // The installing principal becomes the first admin, controllers are implicit admins
+    let mut admins = vec![ic_cdk::api::caller()];
+    admins.extend(args.admins.unwrap_or_default());
+    RoleService::new(RoleRepositoryImpl::global())
+        .seed_admins(admins)
+        .expect("Failed to seed admin roles");
+
+    // Seed the chain registry, later changes go through the admin endpoints
+    let registry_config =
+        registry_seed_config(args.registry_seed).unwrap_or_else(|e| ic_cdk::trap(&e));
+    RegistryService::new(RegistryRepositoryImpl::global())
+        .seed(registry_config)
+        .expect("Failed to seed chain registry");
+
+    // Timers do not survive upgrades, so jobs are registered on every install
+    scheduler::register_jobs();
 
     ic_cdk::println!("[{}] Canister initialized successfully", time());
 }
@@ -38,13 +80,44 @@ fn pre_upgrade() {
 
 /// Post-upgrade hook
 /// This function is called after a canister upgrade to restore state
+///
+/// Settings missing from the upgrade arguments keep their stored value.
 #[post_upgrade]
-fn post_upgrade() {
+fn post_upgrade(args: Option<CanisterArgs>) {
     ic_cdk::println!("[{}] Restoring after canister upgrade", time());
 
+    let args = match args {
+        None | Some(CanisterArgs::Upgrade(None)) => UpgradeArgs::default(),
+        Some(CanisterArgs::Upgrade(Some(args))) => args,
+        Some(CanisterArgs::Init(_)) => ic_cdk::trap("Expected upgrade arguments"),
+    };
+
     // Re-initialize the repositories
-    SignerRepositoryImpl::init(KEY_ID.to_string());
-    AccountRepositoryImpl::init().expect("Failed to initialize account repository");
+    init_repositories();
+    let config = CanisterConfigService::new(CanisterConfigRepositoryImpl::global())
+        .upgrade(&args)
+        .unwrap_or_else(|e| ic_cdk::trap(&e));
+    SignerRepositoryImpl::init(config.key_id().clone());
+
+    if let Some(admins) = args.admins {
+        RoleService::new(RoleRepositoryImpl::global())
+            .seed_admins(admins)
+            .expect("Failed to seed admin roles");
+    }
+
+    // Canisters installed before the registry was persisted start from the default one
+    let registry_service = RegistryService::new(RegistryRepositoryImpl::global());
+    match args.registry_seed {
+        Some(registry_seed) => registry_service
+            .replace(registry_seed_config(Some(registry_seed)).unwrap_or_else(|e| ic_cdk::trap(&e)))
+            .unwrap_or_else(|e| ic_cdk::trap(&e)),
+        None => registry_service
+            .seed(registry_seed_config(None).expect("Invalid default chain registry"))
+            .expect("Failed to seed chain registry"),
+    }
+
+    // Timers do not survive upgrades, so jobs are registered on every install
+    scheduler::register_jobs();
 
     // If you saved any additional data in pre_upgrade, restore it here
     //
@@ -56,10 +129,29 @@ fn post_upgrade() {
     ic_cdk::println!("[{}] Post-upgrade completed successfully", time());
 }
 
-/// Heartbeat function for periodic tasks
-/// This is called regularly by the IC system
-#[heartbeat]
-fn heartbeat() {
-    // Add any periodic cleanup or maintenance tasks here
-    // Example: clean_old_records();
+/// Inspect ingress messages before they are executed
+///
+/// Rejects anonymous callers and callers that are out of rate limit, so spam does not cost
+/// the canister an update call. Calls from other canisters skip this check.
+#[inspect_message]
+fn inspect_message() {
+    let method = ic_cdk::api::call::method_name();
+    match get_rate_limit_service().inspect_ingress(&method, &ic_cdk::api::caller()) {
+        Ok(()) => ic_cdk::api::call::accept_message(),
+        Err(e) => ic_cdk::trap(&e),
+    }
+}
+
+// Initialize the repositories backed by stable memory
+fn init_repositories() {
+    CanisterConfigRepositoryImpl::init().expect("Failed to initialize canister config repository");
+    AccountRepositoryImpl::init().expect("Failed to initialize account repository");
+    SigningProposalRepositoryImpl::init()
+        .expect("Failed to initialize signing proposal repository");
+    SigningSessionRepositoryImpl::init().expect("Failed to initialize signing session repository");
+    AuditEventRepositoryImpl::init().expect("Failed to initialize audit event repository");
+    JobRepositoryImpl::init().expect("Failed to initialize job repository");
+    RoleRepositoryImpl::init().expect("Failed to initialize role repository");
+    RegistryRepositoryImpl::init().expect("Failed to initialize registry repository");
+    FeeAccountRepositoryImpl::init().expect("Failed to initialize fee account repository");
 }
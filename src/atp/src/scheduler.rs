@@ -0,0 +1,175 @@
+//! Background job scheduler built on ic-cdk-timers
+//!
+//! Timers do not survive upgrades, so every job is registered again from
+//! `init` and `post_upgrade`. The run history of each job is kept in stable
+//! memory through the job repository.
+
+use std::time::Duration;
+
+use crate::domain::models::job::{Job, JobSchedule};
+use crate::domain::repositories::job_repository::IJobRepository;
+use crate::endpoints::account_endpoints::get_rate_limit_service;
+use crate::endpoints::inheritance_endpoints;
+use crate::infrastructure::repositories::job_repository_impl::JobRepositoryImpl;
+use crate::utils::ic::api::get_ic_api;
+
+/// Name of the job sweeping accounts for inactive owners, one page per run
+const INHERITANCE_CHECK_JOB: &str = "inheritance_check";
+
+/// A background job and the function it runs
+pub struct JobDefinition {
+    pub name: &'static str,
+    pub schedule: JobSchedule,
+    pub run: fn() -> Result<(), String>,
+}
+
+/// Jobs started on every install and upgrade
+fn jobs() -> Vec<JobDefinition> {
+    vec![
+        JobDefinition {
+            name: INHERITANCE_CHECK_JOB,
+            schedule: JobSchedule::Recurring {
+                interval_seconds: 10 * 60,
+            },
+            run: run_inheritance_check,
+        },
+        JobDefinition {
+            name: "rate_limit_cleanup",
+            schedule: JobSchedule::Recurring {
+                interval_seconds: 10 * 60,
+            },
+            run: run_rate_limit_cleanup,
+        },
+    ]
+}
+
+/// Register every job of the canister with the timer subsystem
+pub fn register_jobs() {
+    for job in jobs() {
+        register(job);
+    }
+}
+
+/// Register a single job, keeping the run history of a previous registration
+pub fn register(job: JobDefinition) {
+    let JobDefinition {
+        name,
+        schedule,
+        run,
+    } = job;
+    if let Err(e) = upsert_metadata(name, schedule.clone()) {
+        get_ic_api().println(&format!("Failed to store metadata of job {}: {}", name, e));
+    }
+
+    match schedule {
+        JobSchedule::Recurring { interval_seconds } => {
+            ic_cdk_timers::set_timer_interval(Duration::from_secs(interval_seconds), move || {
+                execute(name, run)
+            });
+        }
+        JobSchedule::OneShot { delay_seconds } => {
+            ic_cdk_timers::set_timer(Duration::from_secs(delay_seconds), move || {
+                execute(name, run)
+            });
+        }
+    }
+}
+
+// Create or refresh the stored metadata of a job
+fn upsert_metadata(name: &str, schedule: JobSchedule) -> Result<Job, String> {
+    let repository = JobRepositoryImpl::global();
+    let now = get_ic_api().time();
+    let job = match repository.get(name) {
+        Ok(mut job) => {
+            job.reschedule(schedule, now);
+            job
+        }
+        Err(_) => Job::new(name.to_string(), schedule, now),
+    };
+    repository.insert(job)
+}
+
+// Run a job and record the outcome in its metadata
+fn execute(name: &str, run: fn() -> Result<(), String>) {
+    let ic_api = get_ic_api();
+    let result = run();
+    if let Err(e) = &result {
+        ic_api.println(&format!("[{}] Job {} failed: {}", ic_api.time(), name, e));
+    }
+
+    let repository = JobRepositoryImpl::global();
+    let recorded = repository.get(name).and_then(|mut job| {
+        job.record_run(ic_api.time(), result);
+        repository.insert(job)
+    });
+    if let Err(e) = recorded {
+        ic_api.println(&format!("Failed to record run of job {}: {}", name, e));
+    }
+}
+
+// Record inactivity notices for the next page of accounts their beneficiary can claim
+fn run_inheritance_check() -> Result<(), String> {
+    let repository = JobRepositoryImpl::global();
+    let mut job = repository.get(INHERITANCE_CHECK_JOB)?;
+    let (notices, last_swept) =
+        inheritance_endpoints::get_service().notify_inactive_owners(job.position().as_deref())?;
+    job.set_position(last_swept);
+    repository.insert(job)?;
+    if notices > 0 {
+        let ic_api = get_ic_api();
+        ic_api.println(&format!(
+            "[{}] Recorded {} inactivity notices",
+            ic_api.time(),
+            notices
+        ));
+    }
+    Ok(())
+}
+
+// Drop the rate limit buckets of idle callers to bound heap usage
+fn run_rate_limit_cleanup() -> Result<(), String> {
+    get_rate_limit_service().cleanup();
+    Ok(())
+}
+
+#[cfg(test)]
+mod scheduler_tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::utils::ic::api::set_ic_api;
+    use crate::utils::ic::mock::MockIcApi;
+
+    fn failing_job() -> Result<(), String> {
+        Err("boom".to_string())
+    }
+
+    fn succeeding_job() -> Result<(), String> {
+        Ok(())
+    }
+
+    #[test]
+    fn test_runs_are_recorded_across_registrations() {
+        JobRepositoryImpl::init().expect("Failed to initialize repository");
+        set_ic_api(Rc::new(MockIcApi::new().with_time(100)));
+        let schedule = JobSchedule::Recurring {
+            interval_seconds: 60,
+        };
+
+        upsert_metadata("test_job", schedule.clone()).unwrap();
+        execute("test_job", failing_job);
+        execute("test_job", succeeding_job);
+
+        // Registering again, as after an upgrade, keeps the history
+        set_ic_api(Rc::new(MockIcApi::new().with_time(200)));
+        upsert_metadata("test_job", schedule).unwrap();
+
+        let job = JobRepositoryImpl::global().get("test_job").unwrap();
+        assert_eq!(job.registered_at(), &200);
+        assert_eq!(job.run_count(), &2);
+        assert_eq!(job.failure_count(), &1);
+        assert_eq!(job.last_success_at(), &Some(100));
+        assert_eq!(job.last_error(), &None);
+        assert_eq!(JobRepositoryImpl::global().find_all().unwrap().len(), 1);
+    }
+}
@@ -1,8 +1,10 @@
 use atp_caip::Curve;
-use atp_chain_registry::{ChainConfig, ChainRegistry};
+use atp_chain_registry::{ChainConfig, ChainRegistry, RegistryConfig, DEFAULT_CONFIG};
 use std::collections::HashMap;
 
 /*
+* Default threshold key, overridden by the `key_id` init and upgrade argument.
+*
 * dfx_test_key: Only available on the local replica started by dfx.
 * test_key_1: Test key available on the ICP mainnet.
 * key_1: Production key available on the ICP mainnet.
@@ -21,70 +23,69 @@ compile_error!(
 compile_error!("One of the features `local`, `test`, or `production` must be enabled.");
 
 #[cfg(feature = "local")]
-pub const KEY_ID: &str = "dfx_test_key";
+pub const DEFAULT_KEY_ID: &str = "dfx_test_key";
 
 #[cfg(feature = "test")]
-pub const KEY_ID: &str = "test_key_1";
+pub const DEFAULT_KEY_ID: &str = "test_key_1";
 
 #[cfg(feature = "production")]
-pub const KEY_ID: &str = "key_1";
-
-pub fn get_chain_registry() -> Result<ChainRegistry, String> {
-    // Create hardcoded chain configurations for canister environment
-    let mut registry = ChainRegistry::new();
-
-    // EIP155 Wildcard Chain (Ethereum family)
-    let eip155_chain = ChainConfig {
-        chain_id: "eip155:*".to_string(),
-        name: "EIP155 Wildcard Chain".to_string(),
-        native_asset: "slip44:60".to_string(),
-        rpc_endpoints: vec![],
-        explorer_url: None,
-        cryptographic_curve: vec![Curve::Secp256k1],
-        is_testnet: false,
-        assets: vec![],
-        metadata: HashMap::new(),
-    };
-
-    registry
-        .add_chain(eip155_chain)
-        .map_err(|e| format!("Failed to add EIP155 chain: {}", e))?;
-
-    // Solana Wildcard Chain
-    let solana_chain = ChainConfig {
-        chain_id: "solana:*".to_string(),
-        name: "Solana Wildcard Chain".to_string(),
-        native_asset: "slip44:501".to_string(),
-        rpc_endpoints: vec![],
-        explorer_url: None,
-        cryptographic_curve: vec![Curve::Ed25519],
-        is_testnet: false,
-        assets: vec![],
-        metadata: HashMap::new(),
-    };
-
-    registry
-        .add_chain(solana_chain)
-        .map_err(|e| format!("Failed to add Solana chain: {}", e))?;
-
-    // BIP122 Wildcard Chain (Bitcoin family)
-    let bip122_chain = ChainConfig {
-        chain_id: "bip122:*".to_string(),
-        name: "BIP122 Wildcard Chain".to_string(),
-        native_asset: "".to_string(),
-        rpc_endpoints: vec![],
-        explorer_url: None,
-        cryptographic_curve: vec![Curve::Secp256k1],
-        is_testnet: false,
-        assets: vec![],
-        metadata: HashMap::new(),
+pub const DEFAULT_KEY_ID: &str = "key_1";
+
+/// Parse the chain registry the canister is seeded with
+///
+/// Without a seed, the default configuration shipped with `atp_chain_registry`
+/// is used, extended with wildcard chains so every chain of the supported
+/// namespaces can generate addresses.
+pub fn registry_seed_config(seed_toml: Option<String>) -> Result<RegistryConfig, String> {
+    let config = match seed_toml {
+        Some(seed_toml) => ChainRegistry::config_from_toml(&seed_toml)
+            .map_err(|e| format!("Invalid chain registry seed: {}", e))?,
+        None => default_registry_config()?,
     };
+    config
+        .validate()
+        .map_err(|e| format!("Invalid chain registry seed: {}", e))?;
+    Ok(config)
+}
 
-    registry
-        .add_chain(bip122_chain)
-        .map_err(|e| format!("Failed to add BIP122 chain: {}", e))?;
+fn default_registry_config() -> Result<RegistryConfig, String> {
+    let mut config = ChainRegistry::config_from_toml(DEFAULT_CONFIG)
+        .map_err(|e| format!("Invalid default chain registry: {}", e))?;
+
+    // Wildcard chains of the EIP155 (Ethereum), Solana and BIP122 (Bitcoin) families
+    let wildcard_chains = [
+        (
+            "eip155:*",
+            "EIP155 Wildcard Chain",
+            "slip44:60",
+            Curve::Secp256k1,
+        ),
+        (
+            "solana:*",
+            "Solana Wildcard Chain",
+            "slip44:501",
+            Curve::Ed25519,
+        ),
+        ("bip122:*", "BIP122 Wildcard Chain", "", Curve::Secp256k1),
+    ];
+    for (chain_id, name, native_asset, curve) in wildcard_chains {
+        config.chains.insert(
+            chain_id.to_string(),
+            ChainConfig {
+                chain_id: chain_id.to_string(),
+                name: name.to_string(),
+                native_asset: native_asset.to_string(),
+                rpc_endpoints: vec![],
+                explorer_url: None,
+                cryptographic_curve: vec![curve],
+                is_testnet: false,
+                assets: vec![],
+                metadata: HashMap::new(),
+            },
+        );
+    }
 
-    Ok(registry)
+    Ok(config)
 }
 
 #[cfg(test)]
@@ -92,12 +93,31 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_get_chain_registry() {
-        let registry = get_chain_registry();
+    fn test_registry_seed_config() {
+        let config = registry_seed_config(None);
         assert!(
-            registry.is_ok(),
-            "Failed to get chain registry: {:?}",
-            registry.err()
+            config.is_ok(),
+            "Failed to parse default chain registry: {:?}",
+            config.err()
         );
+        let config = config.unwrap();
+        assert!(config.chains.contains_key("eip155:1"));
+        assert!(config.chains.contains_key("bip122:*"));
+
+        let seed = r#"
+            [chains."eip155:*"]
+            chain_id = "eip155:*"
+            name = "EIP155 Wildcard Chain"
+            native_asset = "slip44:60"
+            rpc_endpoints = []
+            cryptographic_curve = ["secp256k1"]
+            is_testnet = false
+            assets = []
+        "#;
+        let config = registry_seed_config(Some(seed.to_string())).unwrap();
+        assert_eq!(config.chains.len(), 1);
+        assert!(config.chains.contains_key("eip155:*"));
+
+        assert!(registry_seed_config(Some("chains = 1".to_string())).is_err());
     }
 }
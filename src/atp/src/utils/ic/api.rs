@@ -16,6 +16,15 @@ pub trait IcApi {
     /// Get the current IC time in nanoseconds
     fn time(&self) -> u64;
 
+    /// Check whether the principal is a controller of the canister
+    fn is_controller(&self, principal: &Principal) -> bool;
+
+    /// Get the cycles attached to the current call that have not been accepted yet
+    fn msg_cycles_available(&self) -> u128;
+
+    /// Accept up to `max_amount` of the cycles attached to the current call
+    fn msg_cycles_accept(&self, max_amount: u128) -> u128;
+
     /// Print a debug message to the IC console
     fn println(&self, message: &str);
 }
@@ -36,6 +45,18 @@ impl IcApi for DefaultIcApi {
         ic_cdk::api::time()
     }
 
+    fn is_controller(&self, principal: &Principal) -> bool {
+        ic_cdk::api::is_controller(principal)
+    }
+
+    fn msg_cycles_available(&self) -> u128 {
+        ic_cdk::api::call::msg_cycles_available128()
+    }
+
+    fn msg_cycles_accept(&self, max_amount: u128) -> u128 {
+        ic_cdk::api::call::msg_cycles_accept128(max_amount)
+    }
+
     fn println(&self, message: &str) {
         ic_cdk::println!("{}", message);
     }
@@ -12,6 +12,9 @@ pub struct MockIcApi {
     caller: RefCell<Principal>,
     id: RefCell<Principal>,
     time: RefCell<u64>,
+    controllers: RefCell<Vec<Principal>>,
+    cycles: RefCell<u128>,
+    accepted_cycles: RefCell<u128>,
     logs: RefCell<Vec<String>>,
 }
 
@@ -29,6 +32,9 @@ impl Default for MockIcApi {
                     .unwrap_or_default()
                     .as_nanos() as u64,
             ),
+            controllers: RefCell::new(Vec::new()),
+            cycles: RefCell::new(0),
+            accepted_cycles: RefCell::new(0),
             logs: RefCell::new(Vec::new()),
         }
     }
@@ -58,6 +64,23 @@ impl MockIcApi {
         self
     }
 
+    /// Set the controllers of the canister for testing
+    pub fn with_controllers(self, controllers: Vec<Principal>) -> Self {
+        *self.controllers.borrow_mut() = controllers;
+        self
+    }
+
+    /// Set the cycles attached to the call for testing
+    pub fn with_cycles(self, cycles: u128) -> Self {
+        *self.cycles.borrow_mut() = cycles;
+        self
+    }
+
+    /// Get the cycles that have been accepted
+    pub fn accepted_cycles(&self) -> u128 {
+        *self.accepted_cycles.borrow()
+    }
+
     /// Get the logs that have been captured
     pub fn get_logs(&self) -> Vec<String> {
         self.logs.borrow().clone()
@@ -77,6 +100,22 @@ impl IcApi for MockIcApi {
         self.time.borrow().clone()
     }
 
+    fn is_controller(&self, principal: &Principal) -> bool {
+        self.controllers.borrow().contains(principal)
+    }
+
+    fn msg_cycles_available(&self) -> u128 {
+        *self.cycles.borrow()
+    }
+
+    fn msg_cycles_accept(&self, max_amount: u128) -> u128 {
+        let mut cycles = self.cycles.borrow_mut();
+        let accepted = max_amount.min(*cycles);
+        *cycles -= accepted;
+        *self.accepted_cycles.borrow_mut() += accepted;
+        accepted
+    }
+
     fn println(&self, message: &str) {
         self.logs.borrow_mut().push(message.to_string());
     }
@@ -138,6 +177,21 @@ impl IcApi for SpyIcApi {
         self.mock.time()
     }
 
+    fn is_controller(&self, principal: &Principal) -> bool {
+        self.record_call("is_controller");
+        self.mock.is_controller(principal)
+    }
+
+    fn msg_cycles_available(&self) -> u128 {
+        self.record_call("msg_cycles_available");
+        self.mock.msg_cycles_available()
+    }
+
+    fn msg_cycles_accept(&self, max_amount: u128) -> u128 {
+        self.record_call("msg_cycles_accept");
+        self.mock.msg_cycles_accept(max_amount)
+    }
+
     fn println(&self, message: &str) {
         self.record_call("println");
         self.mock.println(message);
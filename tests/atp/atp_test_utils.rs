@@ -6,8 +6,12 @@ use atp_caip::curve::Curve;
 use atp_caip::ChainId;
 use candid::{Encode, Principal};
 use ic_atp::application::dtos::account_messages::*;
+use ic_atp::application::dtos::canister_config_messages::*;
 use ic_atp::application::dtos::eip1559::Eip1559TransactionRequestDTO;
+use ic_atp::application::dtos::signing_proposal_messages::*;
+use ic_atp::application::dtos::signing_session_messages::*;
 use ic_atp::domain::models::signer::SignatureAlgorithm;
+use ic_atp::domain::models::signing_proposal::SigningPayload;
 use std::str::FromStr;
 
 // Convenience function to create TestEnvironment for ATP canister
@@ -21,6 +25,27 @@ pub fn create_atp_canister_env_with_config(
     TestEnvironment::new_with_config("ic-atp", "ic_atp", config)
 }
 
+// Convenience function to install the ATP canister with init or upgrade arguments
+pub fn create_atp_canister_env_with_args(
+    args: &CanisterArgs,
+) -> Result<TestEnvironment, Box<dyn std::error::Error>> {
+    create_atp_canister_env_with_config(TestConfig::default().with_init_args(Encode!(args)?))
+}
+
+// Helper to read the canister configuration
+pub fn get_canister_config(
+    env: &TestEnvironment,
+    caller: Principal,
+) -> Result<GetCanisterConfigResponse, Box<dyn std::error::Error>> {
+    let result: Result<GetCanisterConfigResponse, String> =
+        env.update_call("get_canister_config", Encode!().unwrap(), Some(caller))?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
+
 // Helper to create a new account
 pub fn create_test_account(
     env: &TestEnvironment,
@@ -131,6 +156,8 @@ pub fn sign_message(
     let request = SignRequest {
         account_id: account_id.to_string(),
         message_hex: message_hex.to_string(),
+        chain_id: None,
+        key_version: None,
     };
 
     let result: Result<SignResponse, String> =
@@ -152,6 +179,7 @@ pub fn sign_eip1559_transaction(
     let request = SignEip1559TransactionRequest {
         account_id: account_id.to_string(),
         tx_request,
+        key_version: None,
     };
 
     let result: Result<SignEip1559TransactionResponse, String> = env.update_call(
@@ -166,6 +194,124 @@ pub fn sign_eip1559_transaction(
     }
 }
 
+// Helper to rotate the key of an account
+pub fn rotate_key(
+    env: &TestEnvironment,
+    account_id: &str,
+    caller: Principal,
+) -> Result<RotateKeyResponse, Box<dyn std::error::Error>> {
+    let request = RotateKeyRequest {
+        account_id: account_id.to_string(),
+    };
+
+    let result: Result<RotateKeyResponse, String> =
+        env.update_call("rotate_key", Encode!(&request).unwrap(), Some(caller))?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Helper to sign a message with a retired key
+pub fn sign_message_with_key_version(
+    env: &TestEnvironment,
+    account_id: &str,
+    message_hex: &str,
+    key_version: u32,
+    caller: Principal,
+) -> Result<SignResponse, Box<dyn std::error::Error>> {
+    let request = SignRequest {
+        account_id: account_id.to_string(),
+        message_hex: message_hex.to_string(),
+        chain_id: None,
+        key_version: Some(key_version),
+    };
+
+    let result: Result<SignResponse, String> =
+        env.update_call("sign", Encode!(&request).unwrap(), Some(caller))?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Helper to set the guardians of an account
+pub fn set_guardians(
+    env: &TestEnvironment,
+    account_id: &str,
+    guardians: Vec<Principal>,
+    threshold: u8,
+    caller: Principal,
+) -> Result<SetGuardiansResponse, Box<dyn std::error::Error>> {
+    let request = SetGuardiansRequest {
+        account_id: account_id.to_string(),
+        guardians,
+        threshold,
+        recovery_time_lock_seconds: None,
+    };
+
+    let result: Result<SetGuardiansResponse, String> =
+        env.update_call("set_guardians", Encode!(&request).unwrap(), Some(caller))?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Helper to create a signing proposal for a message
+pub fn create_signing_proposal(
+    env: &TestEnvironment,
+    account_id: &str,
+    message_hex: &str,
+    caller: Principal,
+) -> Result<CreateSigningProposalResponse, Box<dyn std::error::Error>> {
+    let request = CreateSigningProposalRequest {
+        account_id: account_id.to_string(),
+        payload: SigningPayload::Message {
+            message_hex: message_hex.to_string(),
+        },
+        ttl_seconds: None,
+    };
+
+    let result: Result<CreateSigningProposalResponse, String> = env.update_call(
+        "create_signing_proposal",
+        Encode!(&request).unwrap(),
+        Some(caller),
+    )?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Helper to approve a signing proposal
+pub fn approve_signing_proposal(
+    env: &TestEnvironment,
+    account_id: &str,
+    proposal_id: &str,
+    caller: Principal,
+) -> Result<ApproveSigningProposalResponse, Box<dyn std::error::Error>> {
+    let request = ApproveSigningProposalRequest {
+        account_id: account_id.to_string(),
+        proposal_id: proposal_id.to_string(),
+    };
+
+    let result: Result<ApproveSigningProposalResponse, String> = env.update_call(
+        "approve_signing_proposal",
+        Encode!(&request).unwrap(),
+        Some(caller),
+    )?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
+
 // Helper to generate address for any chain
 pub fn generate_address(
     env: &TestEnvironment,
@@ -203,3 +349,57 @@ pub fn create_test_eip1559_transaction() -> Eip1559TransactionRequestDTO {
         data: Some(vec![]),              // Empty data as Vec<u8>
     }
 }
+
+// Helper to grant an unrestricted signing session to a delegate
+pub fn grant_signing_session(
+    env: &TestEnvironment,
+    account_id: &str,
+    delegate: Principal,
+    max_signatures: u32,
+    caller: Principal,
+) -> Result<GrantSigningSessionResponse, Box<dyn std::error::Error>> {
+    let request = GrantSigningSessionRequest {
+        account_id: account_id.to_string(),
+        delegate,
+        ttl_seconds: 60 * 60,
+        max_signatures,
+        allowed_chains: vec![],
+        allowed_methods: vec![],
+        max_value: None,
+    };
+
+    let result: Result<GrantSigningSessionResponse, String> = env.update_call(
+        "grant_signing_session",
+        Encode!(&request).unwrap(),
+        Some(caller),
+    )?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Helper to revoke a signing session
+pub fn revoke_signing_session(
+    env: &TestEnvironment,
+    account_id: &str,
+    session_id: &str,
+    caller: Principal,
+) -> Result<RevokeSigningSessionResponse, Box<dyn std::error::Error>> {
+    let request = RevokeSigningSessionRequest {
+        account_id: account_id.to_string(),
+        session_id: session_id.to_string(),
+    };
+
+    let result: Result<RevokeSigningSessionResponse, String> = env.update_call(
+        "revoke_signing_session",
+        Encode!(&request).unwrap(),
+        Some(caller),
+    )?;
+
+    match result {
+        Ok(response) => Ok(response),
+        Err(e) => Err(e.into()),
+    }
+}
@@ -1,8 +1,17 @@
 use crate::atp::atp_test_utils::*;
 use crate::test_utils::TestDataGenerator;
 use atp_caip::curve::Curve;
+use candid::Encode;
+use ic_atp::application::dtos::account_messages::{CreateAccountRequest, CreateAccountResponse};
+use ic_atp::application::dtos::canister_config_messages::*;
+use ic_atp::application::dtos::fee_messages::GetFeesResponse;
+use ic_atp::application::dtos::registry_messages::ListSupportedChainsResponse;
 use ic_atp::domain::models::account::AccountState;
+use ic_atp::domain::models::canister_config::FeeSettings;
+use ic_atp::domain::models::fee::{CurveFee, FeeOperation};
+use ic_atp::domain::models::rate_limit::{RateLimit, RateLimitSettings};
 use ic_atp::domain::models::signer::SignatureAlgorithm;
+use ic_atp::domain::models::signing_proposal::SigningProposalStatus;
 
 #[test]
 fn test_dex_to_user_complete_flow() -> Result<(), Box<dyn std::error::Error>> {
@@ -84,3 +93,395 @@ fn test_dex_to_user_complete_flow() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn test_guardian_approved_signing_flow() -> Result<(), Box<dyn std::error::Error>> {
+    let env = create_atp_canister_env()?;
+
+    let owner = TestDataGenerator::generate_test_principal("owner");
+    let guardians = [
+        candid::Principal::from_slice(&[1; 29]),
+        candid::Principal::from_slice(&[2; 29]),
+        candid::Principal::from_slice(&[3; 29]),
+    ];
+
+    // Create and activate an account owned by the owner
+    let account = create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        owner,
+        owner,
+    )?;
+    let account_id = account.account.id;
+    unlock_account(&env, &account_id, owner)?;
+    activate_account(&env, &account_id, owner)?;
+
+    // Require 2 of 3 guardians to approve signatures
+    let guarded = set_guardians(&env, &account_id, guardians.to_vec(), 2, owner)?;
+    assert_eq!(guarded.account.guardian_threshold, 2);
+    assert_eq!(guarded.account.guardians.len(), 3);
+
+    // Direct signing is rejected once guardians are set
+    let test_message = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+    assert!(sign_message(&env, &account_id, test_message, owner).is_err());
+
+    // The owner proposes, the guardians approve
+    let proposal = create_signing_proposal(&env, &account_id, test_message, owner)?.proposal;
+    assert_eq!(proposal.status, SigningProposalStatus::Pending);
+
+    let first = approve_signing_proposal(&env, &account_id, &proposal.id, guardians[0])?;
+    assert_eq!(first.proposal.status, SigningProposalStatus::Pending);
+    assert!(first.proposal.signature.is_none());
+
+    // The owner cannot approve their own proposal
+    assert!(approve_signing_proposal(&env, &account_id, &proposal.id, owner).is_err());
+
+    let second = approve_signing_proposal(&env, &account_id, &proposal.id, guardians[2])?;
+    assert_eq!(second.proposal.status, SigningProposalStatus::Executed);
+    assert!(second.proposal.signature.is_some());
+
+    Ok(())
+}
+
+#[test]
+fn test_delegated_signing_session() -> Result<(), Box<dyn std::error::Error>> {
+    let env = create_atp_canister_env()?;
+
+    let owner = TestDataGenerator::generate_test_principal("owner");
+    let delegate = candid::Principal::from_slice(&[7; 29]);
+
+    // Create and activate an account owned by the owner
+    let account = create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        owner,
+        owner,
+    )?;
+    let account_id = account.account.id;
+    unlock_account(&env, &account_id, owner)?;
+    activate_account(&env, &account_id, owner)?;
+
+    // The delegate cannot sign without a session
+    let test_message = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+    assert!(sign_message(&env, &account_id, test_message, delegate).is_err());
+
+    // A two-signature session lets the delegate sign twice
+    let session = grant_signing_session(&env, &account_id, delegate, 2, owner)?.session;
+    assert!(session.active);
+    sign_message(&env, &account_id, test_message, delegate)?;
+    sign_message(&env, &account_id, test_message, delegate)?;
+    assert!(sign_message(&env, &account_id, test_message, delegate).is_err());
+
+    // A revoked session can no longer sign
+    let session = grant_signing_session(&env, &account_id, delegate, 2, owner)?.session;
+    let revoked = revoke_signing_session(&env, &account_id, &session.id, owner)?.session;
+    assert!(revoked.revoked);
+    assert!(!revoked.active);
+    assert!(sign_message(&env, &account_id, test_message, delegate).is_err());
+
+    // The owner can still sign
+    sign_message(&env, &account_id, test_message, owner)?;
+
+    Ok(())
+}
+
+#[test]
+fn test_install_with_init_args() -> Result<(), Box<dyn std::error::Error>> {
+    let admin = TestDataGenerator::generate_test_principal("admin");
+    let user = TestDataGenerator::generate_test_principal("user");
+
+    let registry_seed = r#"
+        [chains."eip155:*"]
+        chain_id = "eip155:*"
+        name = "EIP155 Wildcard Chain"
+        native_asset = "slip44:60"
+        rpc_endpoints = []
+        cryptographic_curve = ["secp256k1"]
+        is_testnet = false
+        assets = []
+    "#;
+    let fee_settings = FeeSettings {
+        sign_cycles: 100,
+        ..Default::default()
+    };
+    let env = create_atp_canister_env_with_args(&CanisterArgs::Init(InitArgs {
+        key_id: Some("test_key_1".to_string()),
+        registry_seed: Some(registry_seed.to_string()),
+        admins: Some(vec![admin]),
+        fee_settings: Some(fee_settings.clone()),
+        rate_limits: None,
+    }))?;
+
+    let key_id: String = env.query_call("get_key_id", Encode!().unwrap())?;
+    assert_eq!(key_id, "test_key_1");
+
+    // Only the seeded admins can read the configuration
+    let config = get_canister_config(&env, admin)?.config;
+    assert_eq!(config.key_id, "test_key_1");
+    assert_eq!(config.fee_settings, fee_settings);
+    assert!(get_canister_config(&env, user).is_err());
+
+    // The registry only knows the seeded chains
+    let chains: Result<ListSupportedChainsResponse, String> =
+        env.query_call("list_supported_chains", Encode!().unwrap())?;
+    let chains = chains?.chains;
+    assert_eq!(chains.len(), 1);
+    assert_eq!(chains[0].chain_id, "eip155:*");
+
+    let account = create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        user,
+        user,
+    )?;
+    assert!(generate_address(&env, &account.account.id, "eip155:137").is_ok());
+    assert!(generate_address(
+        &env,
+        &account.account.id,
+        "bip122:000000000019d6689c085ae165831e93"
+    )
+    .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_upgrade_args_update_config() -> Result<(), Box<dyn std::error::Error>> {
+    let admin = TestDataGenerator::generate_test_principal("admin");
+    let auditor = TestDataGenerator::generate_test_principal("auditor");
+
+    // Without arguments the canister uses the build defaults
+    let env = create_atp_canister_env_with_args(&CanisterArgs::Init(InitArgs {
+        admins: Some(vec![admin]),
+        ..Default::default()
+    }))?;
+    let config = get_canister_config(&env, admin)?.config;
+    assert_eq!(config.key_id, "dfx_test_key");
+    assert_eq!(config.fee_settings, FeeSettings::default());
+
+    let fee_settings = FeeSettings {
+        create_account_cycles: 5_000,
+        ..Default::default()
+    };
+    env.upgrade_canister_with_args(Encode!(&CanisterArgs::Upgrade(Some(UpgradeArgs {
+        fee_settings: Some(fee_settings.clone()),
+        admins: Some(vec![auditor]),
+        ..Default::default()
+    })))?)?;
+
+    // Unset fields keep their stored value
+    let config = get_canister_config(&env, auditor)?.config;
+    assert_eq!(config.key_id, "dfx_test_key");
+    assert_eq!(config.fee_settings, fee_settings);
+
+    // An upgrade without arguments leaves the configuration untouched
+    env.upgrade_canister()?;
+    let config = get_canister_config(&env, admin)?.config;
+    assert_eq!(config.fee_settings, fee_settings);
+
+    // Init arguments are rejected on upgrade
+    assert!(env
+        .upgrade_canister_with_args(Encode!(&CanisterArgs::Init(InitArgs::default()))?)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_rotate_key_after_transfer() -> Result<(), Box<dyn std::error::Error>> {
+    let env = create_atp_canister_env()?;
+
+    let dex_principal = TestDataGenerator::generate_test_principal("dex");
+    let user_principal = TestDataGenerator::generate_test_principal("user");
+
+    let account = create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        dex_principal,
+        dex_principal,
+    )?;
+    let account_id = account.account.id.clone();
+    let old_address = generate_address(&env, &account_id, "eip155:1")?.address;
+
+    // The key cannot be rotated while the DEX holds the account
+    assert!(rotate_key(&env, &account_id, dex_principal).is_err());
+
+    transfer_account(&env, &account_id, user_principal, dex_principal)?;
+    assert!(rotate_key(&env, &account_id, dex_principal).is_err());
+
+    let rotated = rotate_key(&env, &account_id, user_principal)?;
+    assert_eq!(rotated.previous_key_version, 0);
+    assert_eq!(rotated.account.key_version, 1);
+    assert_ne!(
+        rotated.account.public_key_hex,
+        account.account.public_key_hex
+    );
+
+    // The plan sweeps every registered chain from the old address to the new one
+    let new_address = generate_address(&env, &account_id, "eip155:1")?.address;
+    assert_ne!(old_address, new_address);
+    let ethereum = rotated
+        .sweep_plan
+        .iter()
+        .find(|entry| entry.chain_id == "eip155:1")
+        .expect("Ethereum mainnet should be in the sweep plan");
+    assert_eq!(ethereum.from_address, old_address);
+    assert_eq!(ethereum.to_address, new_address);
+    assert!(rotated
+        .sweep_plan
+        .iter()
+        .all(|entry| !entry.chain_id.ends_with(":*")));
+
+    // The owner sweeps with the retired key before activating the account
+    let test_message = "0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef";
+    assert!(sign_message(&env, &account_id, test_message, user_principal).is_err());
+    let sweep = sign_message_with_key_version(&env, &account_id, test_message, 0, user_principal)?;
+    assert!(!sweep.signature.is_empty());
+    assert!(
+        sign_message_with_key_version(&env, &account_id, test_message, 0, dex_principal).is_err()
+    );
+
+    activate_account(&env, &account_id, user_principal)?;
+    let signed = sign_message(&env, &account_id, test_message, user_principal)?;
+    assert_ne!(signed.signature, sweep.signature);
+
+    Ok(())
+}
+
+#[test]
+fn test_fees_required_for_account_creation() -> Result<(), Box<dyn std::error::Error>> {
+    let user = TestDataGenerator::generate_test_principal("user");
+
+    let env = create_atp_canister_env_with_args(&CanisterArgs::Init(InitArgs {
+        fee_settings: Some(FeeSettings {
+            create_account_cycles: 1_000_000,
+            sign_cycles: 500_000,
+            rotate_key_cycles: None,
+            curve_fees: vec![CurveFee {
+                operation: FeeOperation::CreateAccount,
+                curve: Curve::Ed25519,
+                cycles: 0,
+            }],
+            ledger: None,
+        }),
+        ..Default::default()
+    }))?;
+
+    let fees: Result<GetFeesResponse, String> = env.query_call("get_fees", Encode!().unwrap())?;
+    let fees = fees?;
+    assert_eq!(fees.fees.len(), 6);
+    assert!(fees.ledger.is_none());
+    let create_secp256k1 = fees
+        .fees
+        .iter()
+        .find(|fee| fee.operation == FeeOperation::CreateAccount && fee.curve == Curve::Secp256k1)
+        .unwrap();
+    assert_eq!(create_secp256k1.cycles, 1_000_000);
+
+    // Ingress calls cannot attach cycles and nothing is prepaid
+    assert!(create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        user,
+        user
+    )
+    .is_err());
+
+    // The ed25519 override makes account creation free
+    let account = create_test_account(
+        &env,
+        SignatureAlgorithm::Schnorr,
+        Curve::Ed25519,
+        user,
+        user,
+    )?;
+    assert_eq!(account.account.owner, user.to_string());
+
+    Ok(())
+}
+
+#[test]
+fn test_rate_limits_on_account_creation() -> Result<(), Box<dyn std::error::Error>> {
+    let user = TestDataGenerator::generate_test_principal("user");
+    let other = TestDataGenerator::generate_test_principal("other");
+
+    let env = create_atp_canister_env_with_args(&CanisterArgs::Init(InitArgs {
+        rate_limits: Some(RateLimitSettings {
+            create_account: Some(RateLimit {
+                capacity: 2,
+                refill_seconds: 60 * 60,
+            }),
+            sign: None,
+            rotate_key: None,
+            max_accounts_per_owner: Some(3),
+        }),
+        ..Default::default()
+    }))?;
+
+    // Anonymous ingress is rejected before it is executed
+    let request = CreateAccountRequest {
+        algorithm: SignatureAlgorithm::Ecdsa,
+        curve: Curve::Secp256k1,
+        approved_address: user,
+    };
+    assert!(env
+        .update_call::<Result<CreateAccountResponse, String>>(
+            "create_account",
+            Encode!(&request).unwrap(),
+            None,
+        )
+        .is_err());
+
+    // The bucket of the user is empty after two accounts, other callers are not affected
+    for _ in 0..2 {
+        create_test_account(
+            &env,
+            SignatureAlgorithm::Ecdsa,
+            Curve::Secp256k1,
+            user,
+            user,
+        )?;
+    }
+    assert!(create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        user,
+        user
+    )
+    .is_err());
+    create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        other,
+        other,
+    )?;
+
+    // Once refilled, the user is still bound by the account quota
+    env.pic
+        .advance_time(std::time::Duration::from_secs(2 * 60 * 60));
+    env.pic.tick();
+    create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        user,
+        user,
+    )?;
+    assert!(create_test_account(
+        &env,
+        SignatureAlgorithm::Ecdsa,
+        Curve::Secp256k1,
+        user,
+        user
+    )
+    .is_err());
+
+    Ok(())
+}
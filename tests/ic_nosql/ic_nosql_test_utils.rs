@@ -5,7 +5,7 @@
 
 use candid::Encode;
 
-use crate::test_utils::{TestConfig, TestEnvironment};
+use crate::test_utils::{get_canister_wasm_path, TestConfig, TestEnvironment};
 // Re-export domain models from example canister
 pub use example_canister::{Comment, Post, User};
 
@@ -45,6 +45,42 @@ pub fn create_example_canister_env_with_config(
     TestEnvironment::new_with_config("example-canister", "example_canister", config)
 }
 
+// Build the example canister with the posts schema from before summaries were added.
+// It gets its own target directory so it never replaces the current wasm.
+pub fn legacy_schema_example_canister_wasm() -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+    let wasm_path = get_canister_wasm_path("example_canister");
+    let target_dir = wasm_path
+        .ancestors()
+        .nth(3)
+        .ok_or("Invalid wasm path")?
+        .join("legacy-schema");
+
+    let status = std::process::Command::new("cargo")
+        .args(&[
+            "build",
+            "--target",
+            "wasm32-unknown-unknown",
+            "--release",
+            "--package",
+            "example-canister",
+            "--features",
+            "legacy-schema",
+            "--target-dir",
+        ])
+        .arg(&target_dir)
+        .status()?;
+
+    if !status.success() {
+        return Err("Failed to build legacy schema example canister".into());
+    }
+
+    let wasm_path = target_dir
+        .join("wasm32-unknown-unknown")
+        .join("release")
+        .join("example_canister.wasm");
+    Ok(std::fs::read(wasm_path)?)
+}
+
 // Common test patterns for example canister
 pub fn create_users_batch(
     env: &TestEnvironment,
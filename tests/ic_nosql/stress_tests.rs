@@ -6,14 +6,15 @@
 use crate::{
     ic_nosql::ic_nosql_test_utils::{
         create_example_canister_env, create_posts_batch, create_users_batch,
-        ExampleCanisterTestDataGenerator, User,
+        legacy_schema_example_canister_wasm, Comment, ExampleCanisterTestDataGenerator, Post, User,
     },
     test_utils::{assert_success_rate, PerformanceMetrics},
 };
-use candid::Encode;
+use candid::{CandidType, Deserialize, Encode};
+use ic_nosql::SchemaMetadata;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 #[test]
 fn stress_test_create_many_users() -> Result<(), Box<dyn std::error::Error>> {
@@ -445,3 +446,222 @@ fn stress_test_canister_upgrade() -> Result<(), Box<dyn std::error::Error>> {
 
     Ok(())
 }
+
+#[test]
+fn stress_test_delete_users_and_posts() -> Result<(), Box<dyn std::error::Error>> {
+    let env = create_example_canister_env()?;
+    let mut delete_metrics = PerformanceMetrics::new();
+
+    const NUM_USERS: usize = 40;
+    const POSTS_PER_USER: usize = 2;
+
+    println!("Setting up {} users for delete test...", NUM_USERS);
+
+    let start_time = Instant::now();
+
+    let user_ids = create_users_batch(&env, NUM_USERS, "delete_user")?;
+    let post_ids = create_posts_batch(&env, &user_ids[..NUM_USERS / 4], POSTS_PER_USER, "Delete")?;
+
+    // Delete every other user and every post
+    let (deleted, kept): (Vec<_>, Vec<_>) = user_ids
+        .iter()
+        .enumerate()
+        .partition(|(index, _)| index % 2 == 0);
+    for (_, user_id) in &deleted {
+        let (duration, result) =
+            env.timed_update_call::<Result<User, String>>("delete_user", Encode!(user_id).unwrap(), None);
+        let success = matches!(&result, Ok(Ok(user)) if &&user.id == user_id);
+        delete_metrics.record_operation(duration, success);
+    }
+    for post_id in &post_ids {
+        let (duration, result) =
+            env.timed_update_call::<Result<Post, String>>("delete_post", Encode!(post_id).unwrap(), None);
+        delete_metrics.record_operation(duration, matches!(result, Ok(Ok(_))));
+    }
+
+    let total_duration = start_time.elapsed();
+    println!("Total test execution time: {:?}", total_duration);
+
+    delete_metrics.print_summary("Delete Operations");
+
+    assert_success_rate(
+        delete_metrics.successful_operations,
+        delete_metrics.total_operations,
+        1.0,
+        "Deletes",
+    );
+
+    // Deleted documents are gone, the others are untouched
+    for (_, user_id) in &deleted {
+        let result: Result<User, String> = env.query_call("get_user", Encode!(user_id).unwrap())?;
+        assert!(result.is_err(), "Deleted user {} is still readable", user_id);
+        let result: Result<User, String> =
+            env.update_call("delete_user", Encode!(user_id).unwrap(), None)?;
+        assert!(result.is_err(), "User {} was deleted twice", user_id);
+    }
+    let verified_count = env.verify_entities_exist::<User>(
+        &kept.iter().map(|(_, id)| (*id).clone()).collect::<Vec<_>>(),
+        "get_user",
+    )?;
+    assert_eq!(verified_count, kept.len(), "Kept users went missing");
+
+    let users: Result<Vec<User>, String> =
+        env.query_call("list_users", Encode!(&1usize, &NUM_USERS).unwrap())?;
+    assert_eq!(users?.len(), kept.len());
+    let posts: Result<Vec<Post>, String> =
+        env.query_call("list_posts", Encode!(&1usize, &NUM_USERS).unwrap())?;
+    assert!(posts.is_err(), "Posts remain after deleting all of them");
+
+    // Freed entries can be written again
+    let recreated = create_users_batch(&env, deleted.len(), "recreated_user")?;
+    assert_eq!(recreated.len(), deleted.len());
+
+    Ok(())
+}
+
+#[test]
+fn stress_test_unique_usernames() -> Result<(), Box<dyn std::error::Error>> {
+    let env = create_example_canister_env()?;
+
+    const NUM_USERS: usize = 20;
+
+    let user_ids = create_users_batch(&env, NUM_USERS, "unique_user")?;
+    assert_eq!(user_ids.len(), NUM_USERS);
+
+    // Every username resolves to its user and cannot be taken again
+    for (index, user_id) in user_ids.iter().enumerate() {
+        let (username, email) = ExampleCanisterTestDataGenerator::generate_user(index, "unique_user");
+        let result: Result<User, String> =
+            env.query_call("get_user_by_username", Encode!(&username).unwrap())?;
+        assert_eq!(&result?.id, user_id);
+
+        let result: Result<User, String> =
+            env.update_call("create_user", Encode!(&username, &email).unwrap(), None)?;
+        assert!(result.is_err(), "Username {} was taken twice", username);
+    }
+
+    let users: Result<Vec<User>, String> =
+        env.query_call("list_users", Encode!(&1usize, &(NUM_USERS * 2)).unwrap())?;
+    assert_eq!(users?.len(), NUM_USERS);
+
+    // Deleting a user frees its username
+    let (username, email) = ExampleCanisterTestDataGenerator::generate_user(0, "unique_user");
+    let _: Result<User, String> = env.update_call("delete_user", Encode!(&user_ids[0]).unwrap(), None)?;
+    let result: Result<User, String> =
+        env.query_call("get_user_by_username", Encode!(&username).unwrap())?;
+    assert!(result.is_err());
+    let result: Result<User, String> =
+        env.update_call("create_user", Encode!(&username, &email).unwrap(), None)?;
+    assert!(result.is_ok());
+
+    Ok(())
+}
+
+#[test]
+fn stress_test_large_documents() -> Result<(), Box<dyn std::error::Error>> {
+    let env = create_example_canister_env()?;
+    let user_ids = create_users_batch(&env, 1, "large_user")?;
+
+    // Posts are chunked, so content far beyond 4 KiB is stored and survives upgrades
+    let content = ExampleCanisterTestDataGenerator::generate_large_content(50_000);
+    let result: Result<Post, String> = env.update_call(
+        "create_post",
+        Encode!(&user_ids[0], &"Large post", &content).unwrap(),
+        None,
+    )?;
+    let post = result?;
+
+    env.upgrade_canister()?;
+    let result: Result<Post, String> = env.query_call("get_post", Encode!(&post.id).unwrap())?;
+    assert_eq!(result?.content, content);
+
+    // Comments keep the default bound, an oversized one is rejected without trapping
+    let result: Result<Comment, String> = env.update_call(
+        "create_comment",
+        Encode!(&post.id, &user_ids[0], &content).unwrap(),
+        None,
+    )?;
+    assert!(result.unwrap_err().contains("exceeds the maximum size"));
+
+    Ok(())
+}
+
+// Posts as returned by the legacy canister, before summaries were added
+#[derive(CandidType, Deserialize)]
+struct LegacyPost {
+    id: String,
+}
+
+#[test]
+fn stress_test_upgrade_migrates_legacy_schema() -> Result<(), Box<dyn std::error::Error>> {
+    // The example canister migrates 10 posts per batch, so the migration spans 3 batches
+    const NUM_POSTS: usize = 25;
+
+    let env = create_example_canister_env()?;
+    env.pic
+        .reinstall_canister(
+            env.canister_id,
+            legacy_schema_example_canister_wasm()?,
+            Encode!().unwrap(),
+            None,
+        )
+        .map_err(|e| format!("Reinstall failed: {:?}", e))?;
+
+    let user_ids = create_users_batch(&env, 1, "legacy_user")?;
+    let mut post_ids = Vec::new();
+    for index in 0..NUM_POSTS {
+        let (title, content) =
+            ExampleCanisterTestDataGenerator::generate_post(index, &user_ids[0], "legacy");
+        let result: Result<LegacyPost, String> = env.update_call(
+            "create_post",
+            Encode!(&user_ids[0], &title, &content).unwrap(),
+            None,
+        )?;
+        post_ids.push(result?.id);
+    }
+
+    env.upgrade_canister()?;
+
+    // Reads migrate old documents before the background batches reach them
+    let result: Result<Post, String> =
+        env.query_call("get_post", Encode!(&post_ids[0]).unwrap())?;
+    let post = result?;
+    assert_eq!(post.summary, post.content);
+
+    // Upgrading again in the middle of the migration resumes it from the stored cursor
+    let mut upgraded_mid_migration = false;
+    let mut metadata = None;
+    for _ in 0..30 {
+        env.pic.advance_time(Duration::from_secs(1));
+        env.pic.tick();
+
+        let result: Result<SchemaMetadata, String> =
+            env.query_call("get_schema_metadata", Encode!(&"posts").unwrap())?;
+        let current = result?;
+        match &current.migration {
+            Some(progress) if progress.completed => {
+                metadata = Some(current);
+                break;
+            }
+            Some(_) if !upgraded_mid_migration => {
+                env.upgrade_canister()?;
+                upgraded_mid_migration = true;
+            }
+            _ => {}
+        }
+    }
+
+    let metadata = metadata.ok_or("Migration did not complete")?;
+    assert!(upgraded_mid_migration);
+    let progress = metadata.migration.unwrap();
+    assert_eq!(metadata.schema_version, 1);
+    assert_eq!((progress.from_version, progress.to_version), (0, 1));
+    assert_eq!(progress.migrated, NUM_POSTS as u64);
+    assert_eq!(progress.failed, 0);
+
+    let result: Result<Vec<Post>, String> =
+        env.query_call("list_posts", Encode!(&1usize, &NUM_POSTS).unwrap())?;
+    assert!(result?.iter().all(|post| post.summary == post.content));
+
+    Ok(())
+}
@@ -90,6 +90,7 @@ pub struct TestConfig {
     pub cycles_amount: u64,
     pub timeout_seconds: u64,
     pub retry_attempts: usize,
+    pub init_args: Vec<u8>,
 }
 
 impl Default for TestConfig {
@@ -98,6 +99,7 @@ impl Default for TestConfig {
             cycles_amount: 10_000_000_000_000, // 10T cycles
             timeout_seconds: 30,
             retry_attempts: 3,
+            init_args: Encode!().unwrap(),
         }
     }
 }
@@ -121,6 +123,11 @@ impl TestConfig {
         self.retry_attempts = retries;
         self
     }
+
+    pub fn with_init_args(mut self, init_args: Vec<u8>) -> Self {
+        self.init_args = init_args;
+        self
+    }
 }
 
 // Test environment setup
@@ -165,7 +172,7 @@ impl TestEnvironment {
         // Add cycles for testing
         pic.add_cycles(canister_id, config.cycles_amount.into());
 
-        pic.install_canister(canister_id, wasm_bytes, Encode!().unwrap(), None);
+        pic.install_canister(canister_id, wasm_bytes, config.init_args.clone(), None);
 
         Ok(Self {
             pic,
@@ -247,10 +254,17 @@ impl TestEnvironment {
     }
 
     pub fn upgrade_canister(&self) -> Result<(), Box<dyn std::error::Error>> {
+        self.upgrade_canister_with_args(Encode!().unwrap())
+    }
+
+    pub fn upgrade_canister_with_args(
+        &self,
+        upgrade_args: Vec<u8>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
         let wasm_path = get_canister_wasm_path(&self.canister_name);
         let wasm_bytes = std::fs::read(&wasm_path)?;
         self.pic
-            .upgrade_canister(self.canister_id, wasm_bytes, Encode!().unwrap(), None)
+            .upgrade_canister(self.canister_id, wasm_bytes, upgrade_args, None)
             .map_err(|e| format!("Upgrade failed: {:?}", e))?;
         Ok(())
     }